@@ -3,7 +3,9 @@
 
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use anyhow::anyhow;
 use clap::{Args, Parser, Subcommand};
 use ethers::utils::hex::ToHexExt;
 use serde_json::{json, Value};
@@ -19,16 +21,20 @@ use recall_provider::{
         parse_query_height, parse_token_amount,
     },
 };
-use recall_sdk::machine::bucket::validate_metadata;
+use recall_sdk::machine::bucket::{display_key, validate_metadata, DEFAULT_DOWNLOAD_RETRIES};
 use recall_sdk::{
+    compression::Compression,
     machine::{
         bucket::{
-            AddOptions, Bucket, DeleteOptions, GetOptions, ObjectState, QueryOptions,
-            UpdateObjectMetadataOptions,
+            AddOptions, Bucket, DeleteOptions, DeletePrefixOptions, GetOptions, ObjectState,
+            QueryOptions, UpdateObjectMetadataOptions,
         },
         Machine,
     },
     network::NetworkConfig,
+    p2p::Transport,
+    pattern::KeyPattern,
+    range::Range,
     TxParams,
 };
 use recall_signer::{
@@ -57,6 +63,8 @@ enum BucketCommands {
     Delete(BucketDeleteArgs),
     /// Get an object.
     Get(BucketGetArgs),
+    /// Get an object's info and metadata without downloading it.
+    Stat(BucketStatArgs),
     /// Query for objects.
     Query(BucketQueryArgs),
     /// Metadata for objects.
@@ -104,11 +112,35 @@ struct BucketAddArgs {
     /// Overwrite the object if it already exists.
     #[arg(short, long)]
     overwrite: bool,
+    /// Allow adding a zero-length object.
+    #[arg(long)]
+    allow_empty: bool,
+    /// Place an advisory immutability lock on the object, so later overwrites, deletes, and
+    /// metadata updates made with this client fail. Other clients can still change the object.
+    #[arg(long)]
+    immutable: bool,
+    /// Record the add time in the object's metadata, so lifecycle rules can expire or renew the
+    /// object by age.
+    #[arg(long)]
+    stamp_created_at: bool,
     /// User-defined metadata.
     #[arg(short, long, value_parser = parse_metadata)]
     metadata: Vec<(String, String)>,
     /// Input file (or stdin) containing the object to upload.
     input: PathBuf,
+    /// Compress the object before uploading it ("gzip" or "zstd").
+    /// The object is decompressed automatically when it's downloaded with `get`.
+    #[arg(long)]
+    compression: Option<Compression>,
+    /// Check the subnet's free capacity and the account's credit before uploading.
+    #[arg(long)]
+    check_admission: bool,
+    /// Estimate the gas and credit cost without uploading or broadcasting.
+    #[arg(long)]
+    dry_run: bool,
+    /// Limit the upload rate, in bytes per second.
+    #[arg(long)]
+    rate_limit: Option<u64>,
     /// Amount of tokens to use for inline buying of credits
     #[arg(long, value_parser = parse_token_amount)]
     token_amount: Option<TokenAmount>,
@@ -127,8 +159,16 @@ struct BucketDeleteArgs {
     /// Bucket machine address.
     #[arg(short, long, value_parser = parse_address)]
     address: Address,
-    /// Key of the object to delete.
+    /// Key of the object to delete, or the prefix of the objects to delete with `--recursive`.
     key: String,
+    /// Delete all objects under the key prefix.
+    /// Deletes are pipelined, so a "commit" broadcast mode is treated as "sync".
+    #[arg(short, long)]
+    recursive: bool,
+    /// Estimate the gas cost without broadcasting.
+    /// With `--recursive`, only list the objects that would be deleted.
+    #[arg(long)]
+    dry_run: bool,
     /// Broadcast mode for the transaction.
     #[arg(short, long, value_enum, env = "RECALL_BROADCAST_MODE", default_value_t = BroadcastMode::Commit)]
     broadcast_mode: BroadcastMode,
@@ -161,10 +201,55 @@ struct BucketGetArgs {
     /// Key of the object to get.
     key: String,
     /// Range of bytes to get from the object.
-    /// Format: "start-end" (inclusive).
+    /// Format: "start-end" (inclusive), "start-" (to the end), or "-length" (the last bytes).
     /// Example: "0-99" (first 100 bytes).
     #[arg(short, long)]
-    range: Option<String>,
+    range: Option<Range>,
+    /// Query block height.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+    /// File to write the object to. If not specified, the object is written to stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Maximum time to wait for the object to be resolved before downloading it, e.g., "30s".
+    /// If not specified, getting an object that isn't resolved yet results in an error.
+    #[arg(long, value_parser = humantime::parse_duration)]
+    resolve_timeout: Option<Duration>,
+    /// Write compressed objects as stored, without decompressing them.
+    #[arg(long)]
+    raw: bool,
+    /// Skip checking the downloaded object against its hash.
+    #[arg(long)]
+    skip_verify: bool,
+    /// Resume an interrupted download into an existing partial output file.
+    #[arg(long, requires = "output")]
+    resume: bool,
+    /// Limit the download rate, in bytes per second.
+    #[arg(long)]
+    rate_limit: Option<u64>,
+    /// Number of times to resume a download that fails mid-transfer.
+    #[arg(long, default_value_t = DEFAULT_DOWNLOAD_RETRIES)]
+    retries: u32,
+    /// Time to wait before the first resume attempt, doubled for each attempt after it, e.g., "1s".
+    #[arg(long, value_parser = humantime::parse_duration)]
+    retry_backoff: Option<Duration>,
+    /// How to download the object: "http" (from the Object API) or "p2p" (from the Object
+    /// API's iroh node, verifying each chunk against the object hash as it arrives).
+    #[arg(long, default_value_t = Transport::Http)]
+    transport: Transport,
+}
+
+#[derive(Clone, Debug, Args)]
+struct BucketStatArgs {
+    /// Bucket machine address.
+    #[arg(short, long, value_parser = parse_address)]
+    address: Address,
+    /// Key of the object.
+    key: String,
     /// Query block height.
     /// Possible values:
     /// "committed" (latest committed block),
@@ -198,6 +283,12 @@ struct BucketQueryArgs {
     /// or a specific block height, e.g., "123".
     #[arg(long, value_parser = parse_query_height, default_value = "committed")]
     height: FvmQueryHeight,
+    /// Only list objects whose keys match a glob, e.g., "logs/2024-*/error.log".
+    #[arg(long, value_parser = KeyPattern::glob, conflicts_with = "regex")]
+    glob: Option<KeyPattern>,
+    /// Only list objects whose keys match a regular expression.
+    #[arg(long, value_parser = KeyPattern::regex)]
+    regex: Option<KeyPattern>,
 }
 
 #[derive(Clone, Debug, Args)]
@@ -258,6 +349,7 @@ pub async fn handle_bucket(
             let tx_json = match &tx.status {
                 TxStatus::Pending(tx) => serde_json::to_value(tx)?,
                 TxStatus::Committed(receipt) => serde_json::to_value(receipt)?,
+                TxStatus::DryRun(estimate) => serde_json::to_value(estimate)?,
             };
 
             print_json(&json!({"address": address.encode_hex_with_prefix(), "tx": &tx_json}))
@@ -315,11 +407,24 @@ pub async fn handle_bucket(
                     AddOptions {
                         ttl: args.ttl,
                         metadata,
+                        stamp_created_at: args.stamp_created_at,
+                        clock: None,
                         overwrite: args.overwrite,
                         token_amount,
                         broadcast_mode,
                         gas_params,
                         show_progress,
+                        allow_empty: args.allow_empty,
+                        dedup: None,
+                        audit: None,
+                        resume_from: None,
+                        quota: None,
+                        compression: args.compression,
+                        precondition: None,
+                        check_admission: args.check_admission,
+                        dry_run: args.dry_run,
+                        rate_limit: args.rate_limit,
+                        immutable: args.immutable,
                     },
                 )
                 .await?;
@@ -345,6 +450,36 @@ pub async fn handle_bucket(
 
             let machine = Bucket::attach(args.address).await?;
             let from = signer.address();
+            if args.recursive {
+                let broadcast_mode = match &args.broadcast_mode {
+                    BroadcastMode::Commit => BroadcastMode::Sync.get(),
+                    mode => mode.get(),
+                };
+                let report = machine
+                    .delete_prefix(
+                        &provider,
+                        &mut signer,
+                        from,
+                        &args.key,
+                        DeletePrefixOptions {
+                            dry_run: args.dry_run,
+                            broadcast_mode,
+                            gas_params,
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+                let deleted = report
+                    .deleted
+                    .iter()
+                    .map(|key| Value::String(display_key(key)))
+                    .collect::<Vec<Value>>();
+                return print_json(&json!({
+                    "deleted": deleted,
+                    "size": report.size,
+                    "results": report.results,
+                }));
+            }
             let tx = machine
                 .delete(
                     &provider,
@@ -354,6 +489,7 @@ pub async fn handle_bucket(
                     DeleteOptions {
                         broadcast_mode,
                         gas_params,
+                        dry_run: args.dry_run,
                     },
                 )
                 .await?;
@@ -370,18 +506,57 @@ pub async fn handle_bucket(
             )?;
 
             let machine = Bucket::attach(args.address).await?;
-            machine
-                .get(
-                    &provider,
-                    &args.key,
-                    io::stdout(),
-                    GetOptions {
-                        range: args.range.clone(),
-                        height: args.height,
-                        show_progress: true,
-                    },
-                )
-                .await
+            let options = GetOptions {
+                range: args.range,
+                height: args.height,
+                show_progress: true,
+                resolve_timeout: args.resolve_timeout,
+                clock: None,
+                cache: None,
+                audit: None,
+                raw: args.raw,
+                skip_verify: args.skip_verify,
+                resume: args.resume,
+                rate_limit: args.rate_limit,
+                retries: args.retries,
+                retry_backoff: args.retry_backoff,
+                transport: args.transport,
+            };
+            match &args.output {
+                Some(path) => {
+                    machine
+                        .get_to_path(&provider, &args.key, path, options)
+                        .await
+                }
+                None => {
+                    machine
+                        .get(&provider, &args.key, io::stdout(), options)
+                        .await
+                }
+            }
+        }
+        BucketCommands::Stat(args) => {
+            let provider = JsonRpcProvider::new_http(
+                cfg.rpc_url,
+                cfg.subnet_id.chain_id(),
+                None,
+                Some(cfg.object_api_url),
+            )?;
+
+            let machine = Bucket::attach(args.address).await?;
+            let object = machine
+                .stat(&provider, &args.key, args.height)
+                .await?
+                .ok_or_else(|| anyhow!("object not found for key '{}'", args.key))?;
+
+            print_json(&json!({
+                "key": args.key,
+                "hash": object.hash.to_string(),
+                "recovery_hash": object.recovery_hash.to_string(),
+                "size": object.size,
+                "resolved": object.resolved,
+                "metadata": object.metadata,
+            }))
         }
         BucketCommands::Query(args) => {
             let provider =
@@ -397,6 +572,7 @@ pub async fn handle_bucket(
                         start_key: args.start_key.clone().map(|key| key.into_bytes()),
                         limit: args.limit,
                         height: args.height,
+                        pattern: args.glob.clone().or(args.regex.clone()),
                     },
                 )
                 .await?;
@@ -405,22 +581,18 @@ pub async fn handle_bucket(
                 .objects
                 .iter()
                 .map(|(key_bytes, object)| {
-                    let key = core::str::from_utf8(key_bytes)
-                        .unwrap_or_default()
-                        .to_string();
+                    let key = display_key(key_bytes);
                     json!({"key": key, "value": object_state_to_json(object)})
                 })
                 .collect::<Vec<Value>>();
             let common_prefixes = list
                 .common_prefixes
                 .iter()
-                .map(|v| Value::String(core::str::from_utf8(v).unwrap_or_default().to_string()))
+                .map(|v| Value::String(display_key(v)))
                 .collect::<Vec<Value>>();
 
             let next_key = match list.next_key {
-                Some(key) => {
-                    Value::String(core::str::from_utf8(&key).unwrap_or_default().to_string())
-                }
+                Some(key) => Value::String(display_key(&key)),
                 None => Value::Null,
             };
 
@@ -9,6 +9,7 @@ use cid::Cid;
 use clap::{Args, Subcommand};
 use clap_stdin::FileOrStdin;
 use ethers::utils::hex::ToHexExt;
+use futures::TryStreamExt;
 use serde_json::{json, Value};
 use tokio::io::AsyncReadExt;
 
@@ -22,7 +23,8 @@ use recall_provider::{
 };
 use recall_sdk::{
     machine::{
-        timehub::{PushOptions, Timehub},
+        bucket::{AddOptions, Bucket},
+        timehub::{PushDetachedOptions, PushOnceReturn, PushOptions, Timehub},
         Machine,
     },
     network::NetworkConfig,
@@ -52,12 +54,18 @@ enum TimehubCommands {
     Push(TimehubPushArgs),
     /// Get leaf at a given index and height.
     Leaf(TimehubLeafArgs),
+    /// Get the most recent leaves at a given height.
+    Peek(TimehubPeekArgs),
+    /// Get the leaves pushed between two times.
+    Between(TimehubBetweenArgs),
     /// Get leaf count at a given height.
     Count(TimehubQueryArgs),
     /// Get peaks at a given height.
     Peaks(TimehubQueryArgs),
     /// Get root at a given height.
     Root(TimehubQueryArgs),
+    /// Get leaf count, root, and last push time at a given height.
+    Stats(TimehubQueryArgs),
 }
 
 #[derive(Clone, Debug, Args)]
@@ -87,6 +95,23 @@ struct TimehubPushArgs {
     /// Input file (or stdin) containing the value to push.
     #[clap(default_value = "-")]
     input: FileOrStdin,
+    /// Bucket to store the input in as a detached blob.
+    /// If set, the input can be any content; it's added to the bucket at `--key`, and only
+    /// its hash is pushed, as a raw BLAKE3 CID.
+    #[arg(long, value_parser = parse_address, requires = "key")]
+    bucket: Option<Address>,
+    /// Key to store the input at in `--bucket`.
+    #[arg(long, requires = "bucket")]
+    key: Option<String>,
+    /// Idempotency key to push the input under.
+    /// If set, the input can be any content; a CID derived from the key and the input is
+    /// pushed, unless it's in one of the last `--window` leaves, e.g., when retrying a push
+    /// that timed out under the same key.
+    #[arg(long, conflicts_with = "bucket", requires = "window")]
+    idempotency_key: Option<String>,
+    /// Number of recent leaves, including pending ones, to check for `--idempotency-key`.
+    #[arg(long, requires = "idempotency_key")]
+    window: Option<u64>,
     /// Broadcast mode for the transaction.
     #[arg(short, long, value_enum, env = "RECALL_BROADCAST_MODE", default_value_t = BroadcastMode::Commit)]
     broadcast_mode: BroadcastMode,
@@ -124,6 +149,44 @@ struct TimehubLeafArgs {
     height: FvmQueryHeight,
 }
 
+#[derive(Clone, Debug, Args)]
+struct TimehubPeekArgs {
+    /// Timehub machine address.
+    #[arg(short, long, value_parser = parse_address)]
+    address: Address,
+    /// Number of leaves to get.
+    #[arg(short, long, default_value_t = 10)]
+    number: u64,
+    /// Query block height.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+}
+
+#[derive(Clone, Debug, Args)]
+struct TimehubBetweenArgs {
+    /// Timehub machine address.
+    #[arg(short, long, value_parser = parse_address)]
+    address: Address,
+    /// Start time (inclusive) in seconds since the UNIX epoch.
+    #[arg(long)]
+    from: u64,
+    /// End time (exclusive) in seconds since the UNIX epoch.
+    /// Defaults to no end.
+    #[arg(long, default_value_t = u64::MAX)]
+    to: u64,
+    /// Query block height.
+    /// Possible values:
+    /// "committed" (latest committed block),
+    /// "pending" (consider pending state changes),
+    /// or a specific block height, e.g., "123".
+    #[arg(long, value_parser = parse_query_height, default_value = "committed")]
+    height: FvmQueryHeight,
+}
+
 /// Timehub commmands handler.
 pub async fn handle_timehub(cfg: NetworkConfig, args: &TimehubArgs) -> anyhow::Result<()> {
     let provider = JsonRpcProvider::new_http(cfg.rpc_url, cfg.subnet_id.chain_id(), None, None)?;
@@ -149,6 +212,7 @@ pub async fn handle_timehub(cfg: NetworkConfig, args: &TimehubArgs) -> anyhow::R
             let tx_json = match &tx.status {
                 TxStatus::Pending(tx) => serde_json::to_value(tx)?,
                 TxStatus::Committed(receipt) => serde_json::to_value(receipt)?,
+                TxStatus::DryRun(estimate) => serde_json::to_value(estimate)?,
             };
 
             print_json(&json!({"address": address.encode_hex_with_prefix(), "tx": &tx_json}))
@@ -181,6 +245,56 @@ pub async fn handle_timehub(cfg: NetworkConfig, args: &TimehubArgs) -> anyhow::R
             let mut reader = args.input.into_async_reader().await?;
             let mut buf = Vec::new();
             reader.read_to_end(&mut buf).await?;
+
+            let machine = Timehub::attach(args.address).await?;
+            let from = signer.address();
+            let push_options = PushOptions {
+                broadcast_mode,
+                gas_params: gas_params.clone(),
+            };
+            if let (Some(bucket), Some(key)) = (args.bucket, &args.key) {
+                let bucket = Bucket::attach(bucket).await?;
+                let pushed = machine
+                    .push_detached(
+                        &provider,
+                        &mut signer,
+                        from,
+                        &bucket,
+                        key,
+                        Bytes::from(buf),
+                        PushDetachedOptions {
+                            add: AddOptions {
+                                broadcast_mode,
+                                gas_params,
+                                ..Default::default()
+                            },
+                            push: push_options,
+                        },
+                    )
+                    .await?;
+                return print_tx_json(&pushed.push);
+            }
+
+            if let (Some(key), Some(window)) = (&args.idempotency_key, args.window) {
+                let pushed = machine
+                    .push_once(
+                        &provider,
+                        &mut signer,
+                        from,
+                        key,
+                        Bytes::from(buf),
+                        window,
+                        push_options,
+                    )
+                    .await?;
+                return match pushed {
+                    PushOnceReturn::Pushed { tx, .. } => print_tx_json(&tx),
+                    PushOnceReturn::Existing { cid, index } => {
+                        print_json(&json!({"cid": cid, "index": index, "existing": true}))
+                    }
+                };
+            }
+
             let cid = match Cid::read_bytes(Cursor::new(buf.clone())) {
                 Ok(cid) => cid,
                 Err(_) => {
@@ -195,19 +309,8 @@ pub async fn handle_timehub(cfg: NetworkConfig, args: &TimehubArgs) -> anyhow::R
 
             let payload = Bytes::from(cid.to_bytes());
 
-            let machine = Timehub::attach(args.address).await?;
-            let from = signer.address();
             let tx = machine
-                .push(
-                    &provider,
-                    &mut signer,
-                    from,
-                    payload,
-                    PushOptions {
-                        broadcast_mode,
-                        gas_params,
-                    },
-                )
+                .push(&provider, &mut signer, from, payload, push_options)
                 .await?;
 
             print_tx_json(&tx)
@@ -227,6 +330,41 @@ pub async fn handle_timehub(cfg: NetworkConfig, args: &TimehubArgs) -> anyhow::R
 
             print_json(&leaf)
         }
+        TimehubCommands::Peek(args) => {
+            let machine = Timehub::attach(args.address).await?;
+            let leaves = machine.peek(&provider, args.number, args.height).await?;
+
+            let leaves = leaves
+                .into_iter()
+                .map(|(index, leaf)| {
+                    json!({
+                        "index": index,
+                        "timestamp": leaf.timestamp,
+                        "witnessed": leaf.witnessed.to_string(),
+                    })
+                })
+                .collect::<Vec<Value>>();
+            print_json(&leaves)
+        }
+        TimehubCommands::Between(args) => {
+            let machine = Timehub::attach(args.address).await?;
+            let leaves: Vec<_> = machine
+                .leaves_between(&provider, args.from, args.to, args.height)
+                .try_collect()
+                .await?;
+
+            let leaves = leaves
+                .into_iter()
+                .map(|(index, leaf)| {
+                    json!({
+                        "index": index,
+                        "timestamp": leaf.timestamp,
+                        "witnessed": leaf.witnessed.to_string(),
+                    })
+                })
+                .collect::<Vec<Value>>();
+            print_json(&leaves)
+        }
         TimehubCommands::Count(args) => {
             let machine = Timehub::attach(args.address).await?;
             let count = machine.count(&provider, args.height).await?;
@@ -241,9 +379,15 @@ pub async fn handle_timehub(cfg: NetworkConfig, args: &TimehubArgs) -> anyhow::R
         }
         TimehubCommands::Root(args) => {
             let machine = Timehub::attach(args.address).await?;
-            let root = machine.root(&provider, args.height).await?;
+            let root = machine.root_at(&provider, args.height).await?;
+
+            print_json(&json!({"root": root.root.to_string(), "height": root.height}))
+        }
+        TimehubCommands::Stats(args) => {
+            let machine = Timehub::attach(args.address).await?;
+            let stats = machine.stats(&provider, args.height).await?;
 
-            print_json(&json!({"root": root.to_string()}))
+            print_json(&stats)
         }
     }
 }
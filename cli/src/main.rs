@@ -236,6 +236,7 @@ fn print_tx_json<T: 'static>(tx_res: &TxResult<T>) -> anyhow::Result<()> {
     let json = match &tx_res.status {
         TxStatus::Pending(tx) => serde_json::to_string_pretty(tx)?,
         TxStatus::Committed(receipt) => serde_json::to_string_pretty(receipt)?,
+        TxStatus::DryRun(estimate) => serde_json::to_string_pretty(estimate)?,
     };
     println!("{}", json);
     Ok(())
@@ -0,0 +1,255 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt::Debug;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use fvm_shared::{address::Address, MethodNum};
+use serde::Serialize;
+
+use crate::tx::{TxResult, TxStatus};
+
+/// Target of the metric events emitted for every audited operation (see [`record`]).
+///
+/// Metric events carry `monotonic_counter.`-prefixed fields, which a
+/// [`tracing-opentelemetry`](https://docs.rs/tracing-opentelemetry) `MetricsLayer` exports as
+/// OpenTelemetry counters:
+/// - `recall.transactions`: signed transactions, with an `ok` attribute.
+/// - `recall.uploads` and `recall.uploaded_bytes`: object uploads, with an `ok` attribute.
+/// - `recall.downloads` and `recall.downloaded_bytes`: object downloads, with an `ok` attribute.
+pub const METRICS_TARGET: &str = "recall::metrics";
+
+/// The kind of an audited operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditKind {
+    /// A signed transaction.
+    Transaction,
+    /// An object upload.
+    Upload,
+    /// An object download.
+    Download,
+}
+
+/// A record of a signed transaction or object transfer.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditEvent {
+    /// Time of the event in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The kind of operation.
+    pub kind: AuditKind,
+    /// The account that signed the transaction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// The machine (or other recipient) address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
+    /// The invoked method number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<MethodNum>,
+    /// The object key, escaped if it isn't valid UTF-8.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// The object content hash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// The transferred size in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// The transaction gas limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<u64>,
+    /// The gas used by a committed transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<String>,
+    /// The transaction hash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    /// The error, if the operation failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEvent {
+    /// Create an event of the given kind with the current time and no details.
+    pub fn new(kind: AuditKind) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            kind,
+            from: None,
+            machine: None,
+            method: None,
+            key: None,
+            hash: None,
+            size: None,
+            gas_limit: None,
+            gas_used: None,
+            tx_hash: None,
+            error: None,
+        }
+    }
+
+    /// Create an event for a signed transaction and its outcome.
+    pub fn transaction<T>(
+        from: Address,
+        to: Address,
+        method: MethodNum,
+        gas_limit: u64,
+        result: &anyhow::Result<TxResult<T>>,
+    ) -> Self {
+        let mut event = Self::new(AuditKind::Transaction);
+        event.from = Some(from.to_string());
+        event.machine = Some(to.to_string());
+        event.method = Some(method);
+        event.gas_limit = Some(gas_limit);
+        match result {
+            Ok(tx) => {
+                event.tx_hash = Some(format!("{:#x}", tx.hash()));
+                if let TxStatus::Committed(receipt) = &tx.status {
+                    event.gas_used = receipt.gas_used.map(|g| g.to_string());
+                }
+            }
+            Err(e) => event.error = Some(e.to_string()),
+        }
+        event
+    }
+
+    /// Create an event for an object upload or download and its outcome.
+    /// `key` should already be escaped for display.
+    pub fn transfer(
+        kind: AuditKind,
+        machine: Address,
+        key: String,
+        hash: Option<String>,
+        result: &anyhow::Result<u64>,
+    ) -> Self {
+        let mut event = Self::new(kind);
+        event.machine = Some(machine.to_string());
+        event.key = Some(key);
+        event.hash = hash;
+        match result {
+            Ok(size) => event.size = Some(*size),
+            Err(e) => event.error = Some(e.to_string()),
+        }
+        event
+    }
+}
+
+/// A destination for audit events.
+///
+/// Sink errors are logged and never fail the audited operation.
+#[async_trait]
+pub trait AuditSink: Debug + Send + Sync {
+    /// Record an event.
+    async fn record(&self, event: &AuditEvent) -> anyhow::Result<()>;
+}
+
+/// Record an event to an optional sink, logging any failure.
+/// The event is also emitted as a metric event, whether or not there's a sink
+/// (see [`METRICS_TARGET`]).
+pub async fn record(sink: Option<&dyn AuditSink>, event: AuditEvent) {
+    emit_metrics(&event);
+    if let Some(sink) = sink {
+        if let Err(e) = sink.record(&event).await {
+            tracing::warn!(error = %e, kind = ?event.kind, "failed to record audit event");
+        }
+    }
+}
+
+/// Emit the metric event for an audit event.
+fn emit_metrics(event: &AuditEvent) {
+    let ok = event.error.is_none();
+    let size = event.size.unwrap_or_default();
+    match event.kind {
+        AuditKind::Transaction => tracing::info!(
+            target: METRICS_TARGET,
+            monotonic_counter.recall.transactions = 1u64,
+            ok,
+            "signed transaction"
+        ),
+        AuditKind::Upload => tracing::info!(
+            target: METRICS_TARGET,
+            monotonic_counter.recall.uploads = 1u64,
+            monotonic_counter.recall.uploaded_bytes = size,
+            ok,
+            "uploaded object"
+        ),
+        AuditKind::Download => tracing::info!(
+            target: METRICS_TARGET,
+            monotonic_counter.recall.downloads = 1u64,
+            monotonic_counter.recall.downloaded_bytes = size,
+            ok,
+            "downloaded object"
+        ),
+    }
+}
+
+/// An [`AuditSink`] that appends events to a file as JSON lines.
+#[derive(Debug)]
+pub struct FileAuditSink {
+    file: Mutex<File>,
+}
+
+impl FileAuditSink {
+    /// Open a file for appending, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn record(&self, event: &AuditEvent) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow!("audit file lock poisoned"))?;
+        file.write_all(&line)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// An [`AuditSink`] that posts each event as JSON to a webhook URL.
+#[derive(Debug)]
+pub struct WebhookAuditSink {
+    client: reqwest::Client,
+    url: reqwest::Url,
+}
+
+impl WebhookAuditSink {
+    /// Create a sink for the given URL.
+    pub fn new(url: reqwest::Url) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for WebhookAuditSink {
+    async fn record(&self, event: &AuditEvent) -> anyhow::Result<()> {
+        self.client
+            .post(self.url.clone())
+            .json(event)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
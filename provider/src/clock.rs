@@ -0,0 +1,181 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Injectable time for retries and polling.
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use backoff::{future::Sleeper, ExponentialBackoff};
+
+/// A future returned by [`Clock::sleep`].
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A source of time for retries, polling, and quota periods.
+///
+/// The provider and SDK use [`SystemClock`] unless another clock is given, e.g., a
+/// [`ManualClock`] that lets tests simulate timeouts and backoff without waiting.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+
+    /// Returns a future that completes once `duration` has passed on this clock.
+    fn sleep(&self, duration: Duration) -> Sleep;
+
+    /// Returns the current wall-clock time, e.g., for timestamps recorded in metadata.
+    /// The default implementation returns [`SystemTime::now`].
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// The system's monotonic clock, sleeping with the tokio timer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// A clock that only moves when it's advanced.
+///
+/// Sleeping advances the clock by the sleep duration and completes immediately, so a retry
+/// loop runs to its timeout without real waiting. Clones share the same time.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    start: Instant,
+    start_time: SystemTime,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// Create a clock starting at the current instant.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            start_time: SystemTime::now(),
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&self, duration: Duration) {
+        let mut elapsed = self.elapsed.lock().unwrap_or_else(|e| e.into_inner());
+        *elapsed += duration;
+    }
+
+    /// Returns the total time the clock has been advanced by.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.advance(duration);
+        Box::pin(std::future::ready(()))
+    }
+
+    fn system_time(&self) -> SystemTime {
+        self.start_time + self.elapsed()
+    }
+}
+
+/// Adapts a [`Clock`] to the clock and sleeper used by `backoff`.
+#[derive(Clone, Debug)]
+pub struct BackoffClock(Arc<dyn Clock>);
+
+impl backoff::Clock for BackoffClock {
+    fn now(&self) -> Instant {
+        self.0.now()
+    }
+}
+
+impl Sleeper for BackoffClock {
+    type Sleep = Sleep;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        self.0.sleep(duration)
+    }
+}
+
+/// Creates a default exponential backoff policy that measures elapsed time on `clock`.
+pub fn new_backoff_policy(
+    clock: Arc<dyn Clock>,
+    max_elapsed: Duration,
+) -> backoff::exponential::ExponentialBackoff<BackoffClock> {
+    let defaults = ExponentialBackoff::default();
+    backoff::exponential::ExponentialBackoff {
+        current_interval: defaults.initial_interval,
+        initial_interval: defaults.initial_interval,
+        randomization_factor: defaults.randomization_factor,
+        multiplier: defaults.multiplier,
+        max_interval: defaults.max_interval,
+        start_time: clock.now(),
+        max_elapsed_time: Some(max_elapsed),
+        clock: BackoffClock(clock),
+    }
+}
+
+/// Retries the operation according to the policy, sleeping on the policy's clock.
+pub async fn retry<T, E, F, Fut>(
+    policy: backoff::exponential::ExponentialBackoff<BackoffClock>,
+    operation: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, backoff::Error<E>>>,
+{
+    let sleeper = policy.clock.clone();
+    backoff::future::Retry::new(sleeper, policy, |_: E, _: Duration| {}, operation).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry_times_out_on_manual_clock() {
+        let clock = ManualClock::new();
+        let policy = new_backoff_policy(Arc::new(clock.clone()), Duration::from_secs(3600));
+        let mut attempts = 0;
+        let result: Result<(), &str> = retry(policy, || {
+            attempts += 1;
+            async { Err(backoff::Error::transient("not resolved")) }
+        })
+        .await;
+        assert_eq!(result, Err("not resolved"));
+        assert!(attempts > 1);
+        assert!(clock.elapsed() >= Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_manual_clock_system_time() {
+        let clock = ManualClock::new();
+        let start = clock.system_time();
+        clock.advance(Duration::from_secs(86400));
+        assert_eq!(
+            clock.system_time().duration_since(start).unwrap(),
+            Duration::from_secs(86400)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tendermint_rpc::Url;
+
+use crate::error::{self, ErrorCode};
+
+/// Number of consecutive failures after which an endpoint is considered unhealthy.
+const UNHEALTHY_AFTER_FAILURES: u32 = 3;
+
+/// Weight of the newest sample in an endpoint's moving average latency.
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+/// Observed health and latency of an Object API endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct EndpointMetrics {
+    /// The endpoint URL.
+    pub url: String,
+    /// Moving average request latency in milliseconds, if any request has completed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Total number of requests and probes.
+    pub requests: u64,
+    /// Total number of failed requests and probes.
+    pub failures: u64,
+    /// Whether the endpoint is selected for requests.
+    pub healthy: bool,
+}
+
+/// Error returned when an Object API endpoint can't be reached.
+///
+/// The chain may still be reachable, in which case queries and transactions keep working and
+/// only object uploads and downloads fail (see [`Availability::Degraded`]).
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectApiUnavailableError {
+    /// The endpoint URL.
+    pub url: String,
+    /// Why the endpoint couldn't be reached.
+    pub reason: String,
+}
+
+impl fmt::Display for ObjectApiUnavailableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: object API at {} is unavailable: {}",
+            self.code(),
+            self.url,
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for ObjectApiUnavailableError {}
+
+impl ErrorCode for ObjectApiUnavailableError {
+    fn code(&self) -> &'static str {
+        error::OBJECT_API_UNAVAILABLE
+    }
+}
+
+/// Which of a provider's services are reachable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Availability {
+    /// The chain RPC and at least one Object API endpoint are reachable.
+    Full,
+    /// The chain RPC is reachable, but no Object API endpoint is, or none is configured.
+    /// Queries and transactions work, e.g., listing and deleting objects, but object uploads
+    /// and downloads don't.
+    Degraded,
+    /// The chain RPC isn't reachable.
+    Unavailable,
+}
+
+/// The result of probing a provider's services.
+#[derive(Clone, Debug, Serialize)]
+pub struct AvailabilityReport {
+    /// The overall availability.
+    pub availability: Availability,
+    /// Why the chain RPC couldn't be reached, if it couldn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_error: Option<String>,
+    /// Object API endpoint metrics, including the probe. Empty if no Object API is configured.
+    pub objects: Vec<EndpointMetrics>,
+}
+
+#[derive(Debug, Default)]
+struct EndpointStats {
+    latency: Option<Duration>,
+    requests: u64,
+    failures: u64,
+    consecutive_failures: u32,
+}
+
+impl EndpointStats {
+    fn is_healthy(&self) -> bool {
+        self.consecutive_failures < UNHEALTHY_AFTER_FAILURES
+    }
+}
+
+/// A set of equivalent Object API endpoints, ranked by observed latency.
+///
+/// The first endpoint is the primary. It's used until another endpoint is measured to be faster,
+/// and when no endpoint is healthy.
+#[derive(Debug)]
+pub(crate) struct EndpointSet {
+    endpoints: Vec<(Url, Mutex<EndpointStats>)>,
+}
+
+impl EndpointSet {
+    pub(crate) fn new(primary: Url, mirrors: Vec<Url>) -> Self {
+        Self {
+            endpoints: std::iter::once(primary)
+                .chain(mirrors)
+                .map(|url| (url, Mutex::default()))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of endpoints.
+    pub(crate) fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// Returns the URL of the endpoint at `index`.
+    pub(crate) fn url(&self, index: usize) -> &Url {
+        &self.endpoints[index].0
+    }
+
+    /// Returns the index of the fastest healthy endpoint, or the first healthy endpoint if
+    /// none has been measured.
+    pub(crate) fn select(&self) -> usize {
+        let healthy: Vec<(usize, Option<Duration>)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (_, stats))| {
+                let stats = stats.lock().unwrap_or_else(|e| e.into_inner());
+                stats.is_healthy().then_some((index, stats.latency))
+            })
+            .collect();
+        healthy
+            .iter()
+            .filter_map(|(index, latency)| latency.map(|latency| (latency, *index)))
+            .min()
+            .map(|(_, index)| index)
+            .or_else(|| healthy.first().map(|(index, _)| *index))
+            .unwrap_or(0)
+    }
+
+    /// Record the outcome of a request to the endpoint at `index`.
+    pub(crate) fn record(&self, index: usize, elapsed: Duration, ok: bool) {
+        let mut stats = self.endpoints[index]
+            .1
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        stats.requests += 1;
+        if ok {
+            stats.consecutive_failures = 0;
+            stats.latency = Some(match stats.latency {
+                Some(latency) => {
+                    latency.mul_f64(1.0 - LATENCY_SMOOTHING) + elapsed.mul_f64(LATENCY_SMOOTHING)
+                }
+                None => elapsed,
+            });
+        } else {
+            stats.failures += 1;
+            stats.consecutive_failures += 1;
+        }
+    }
+
+    /// Returns the metrics of all endpoints, primary first.
+    pub(crate) fn metrics(&self) -> Vec<EndpointMetrics> {
+        self.endpoints
+            .iter()
+            .map(|(url, stats)| {
+                let stats = stats.lock().unwrap_or_else(|e| e.into_inner());
+                EndpointMetrics {
+                    url: url.to_string(),
+                    latency_ms: stats.latency.map(|l| l.as_millis() as u64),
+                    requests: stats.requests,
+                    failures: stats.failures,
+                    healthy: stats.is_healthy(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_endpoint_selection() {
+        let endpoints = EndpointSet::new(
+            Url::from_str("http://primary:8001").unwrap(),
+            vec![Url::from_str("http://mirror:8001").unwrap()],
+        );
+        // The primary is used until a mirror is measured
+        assert_eq!(endpoints.select(), 0);
+
+        endpoints.record(0, Duration::from_millis(200), true);
+        endpoints.record(1, Duration::from_millis(50), true);
+        assert_eq!(endpoints.select(), 1);
+
+        for _ in 0..UNHEALTHY_AFTER_FAILURES {
+            endpoints.record(1, Duration::from_millis(50), false);
+        }
+        assert_eq!(endpoints.select(), 0);
+        assert!(!endpoints.metrics()[1].healthy);
+    }
+}
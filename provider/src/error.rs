@@ -0,0 +1,39 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+/// Implemented by typed errors that carry a stable, machine-readable code.
+///
+/// Codes don't change between releases and aren't localized, so support and alerting tools can
+/// classify failures by code rather than by message. Errors include their code at the start of
+/// their display output, e.g., `RECALL-E-NET-001: ...`.
+pub trait ErrorCode: std::error::Error {
+    /// Returns the error code.
+    fn code(&self) -> &'static str;
+}
+
+/// Code of [`crate::util::NetworkMismatchError`].
+pub const NETWORK_MISMATCH: &str = "RECALL-E-NET-001";
+
+/// Code of `recall_sdk::quota::QuotaExceededError`.
+pub const QUOTA_EXCEEDED: &str = "RECALL-E-QUOTA-001";
+
+/// Code of `recall_sdk::machine::bucket::IntegrityError`.
+pub const OBJECT_INTEGRITY: &str = "RECALL-E-OBJ-001";
+
+/// Code of [`crate::endpoint::ObjectApiUnavailableError`].
+pub const OBJECT_API_UNAVAILABLE: &str = "RECALL-E-OBJ-002";
+
+/// Code of `recall_sdk::cost::InsufficientResourceError`.
+pub const INSUFFICIENT_RESOURCE: &str = "RECALL-E-CAP-001";
+
+/// Code of `recall_sdk::machine::bucket::PreconditionFailedError`.
+pub const OBJECT_PRECONDITION: &str = "RECALL-E-OBJ-003";
+
+/// Code of `recall_sdk::key_policy::InvalidKeyError`.
+pub const OBJECT_KEY_INVALID: &str = "RECALL-E-OBJ-004";
+
+/// Code of `recall_sdk::machine::bucket::ImmutableObjectError`.
+pub const OBJECT_IMMUTABLE: &str = "RECALL-E-OBJ-005";
+
+/// Code of `recall_sdk::root_cache::RootEquivocationError`.
+pub const TIMEHUB_ROOT_EQUIVOCATION: &str = "RECALL-E-TH-001";
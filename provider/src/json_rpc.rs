@@ -3,11 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use std::fmt::Display;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
-use backoff::{backoff::Backoff, future::retry, ExponentialBackoff};
+use backoff::{backoff::Backoff, exponential::ExponentialBackoff};
 use ethers::core::types as et;
 use ethers::utils::hex::ToHexExt;
 use fendermint_eth_api::conv::from_tm::{
@@ -17,24 +18,48 @@ use fvm_shared::{address::Address, chainid::ChainID};
 use reqwest::multipart::Form;
 use tendermint::{abci::response::DeliverTx, block::Height, hash::Hash};
 use tendermint_rpc::{
-    endpoint::abci_query::AbciQuery, endpoint::block_results, Client, Scheme, WebSocketClient,
-    WebSocketClientDriver, WebSocketClientUrl,
+    endpoint::abci_query::AbciQuery, endpoint::block_results, error::ErrorDetail, Client,
+    ResponseError, Scheme, WebSocketClient, WebSocketClientDriver, WebSocketClientUrl,
 };
 
 pub use tendermint_rpc::{HttpClient, Url};
 
-use crate::message::{serialize, ChainMessage};
-use crate::object::{NodeAddr, ObjectProvider, UploadResponse};
+use crate::clock::{self, retry, BackoffClock, Clock, SystemClock};
+use crate::endpoint::{
+    Availability, AvailabilityReport, EndpointMetrics, EndpointSet, ObjectApiUnavailableError,
+};
+use crate::message::{self, serialize, ChainMessage};
+use crate::object::{object_url, NodeAddr, ObjectProvider, UploadResponse};
 use crate::query::{FvmQuery, FvmQueryHeight, QueryProvider};
 use crate::tx::{BroadcastMode, TxProvider, TxResult};
+use crate::util::{polling_interval, BLOCK_INTERVAL_SAMPLE_SIZE};
 use crate::{Provider, TendermintClient};
 
-/// Creates a new backoff policy.
-fn new_backoff_policy(max_elapsed_secs: u64) -> ExponentialBackoff {
-    let mut eb = ExponentialBackoff {
-        max_elapsed_time: Some(Duration::from_secs(max_elapsed_secs)),
-        ..Default::default()
-    };
+/// Default maximum time to wait for a transaction to show up in the indexer.
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum time to wait for an Object API endpoint to respond to a probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time the Object API's node address is cached for before it's fetched again, so adds pick up
+/// a new node id after the Object API restarts.
+const NODE_ADDR_TTL: Duration = Duration::from_secs(60);
+
+/// Minimum time to wait for a transaction to be committed after a commit broadcast timed out.
+const COMMIT_FALLBACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Creates a new backoff policy that measures elapsed time on `clock`.
+///
+/// If the chain's block interval is known, the initial interval is derived from it.
+fn new_backoff_policy(
+    clock: Arc<dyn Clock>,
+    max_elapsed: Duration,
+    block_interval: Option<Duration>,
+) -> ExponentialBackoff<BackoffClock> {
+    let mut eb = clock::new_backoff_policy(clock, max_elapsed);
+    if let Some(block_interval) = block_interval {
+        eb.initial_interval = polling_interval(block_interval);
+    }
     eb.reset();
     eb
 }
@@ -45,12 +70,76 @@ pub struct JsonRpcProvider<C = HttpClient> {
     inner: C,
     chain_id: ChainID,
     objects: Option<ObjectClient>,
+    /// The observed block interval, measured once and reused for polling.
+    block_interval: Arc<OnceLock<Duration>>,
+    /// The network's block gas limit, fetched once and reused for validating gas params.
+    block_gas_limit: Arc<OnceLock<u64>>,
+    /// Number of blocks committed transactions must be buried under before they're returned.
+    confirmations: u64,
+    /// Whether commit broadcasts that time out wait for the transaction to be committed.
+    commit_fallback: bool,
+    /// The clock that retries and receipt polling are timed with.
+    clock: Arc<dyn Clock>,
 }
 
 #[derive(Clone)]
 struct ObjectClient {
     inner: reqwest::Client,
+    /// The primary Object API URL, used for uploads so they land on the node in `node_addr`.
     url: Url,
+    /// The primary and mirror endpoints that downloads are spread across.
+    endpoints: Arc<EndpointSet>,
+    /// The Object API's Iroh node address and when it was fetched, reused across uploads for
+    /// up to [`NODE_ADDR_TTL`]. It's cleared when an upload fails.
+    node_addr: Arc<Mutex<Option<(NodeAddr, Instant)>>>,
+}
+
+impl ObjectClient {
+    /// Send a request to the endpoint at `index`, recording its latency and health.
+    /// Client errors, e.g., for a missing object, don't count against the endpoint.
+    async fn send(
+        &self,
+        index: usize,
+        request: reqwest::RequestBuilder,
+    ) -> anyhow::Result<reqwest::Response> {
+        let started = Instant::now();
+        let result = request.send().await;
+        let ok = result
+            .as_ref()
+            .is_ok_and(|response| !response.status().is_server_error());
+        self.endpoints.record(index, started.elapsed(), ok);
+        result.map_err(|e| unavailable(self.endpoints.url(index), e))
+    }
+
+    /// Returns the cached node address if it was fetched within [`NODE_ADDR_TTL`].
+    fn cached_node_addr(&self, now: Instant) -> Option<NodeAddr> {
+        let cached = self.node_addr.lock().unwrap_or_else(|e| e.into_inner());
+        cached
+            .as_ref()
+            .filter(|(_, fetched)| now.saturating_duration_since(*fetched) < NODE_ADDR_TTL)
+            .map(|(addr, _)| addr.clone())
+    }
+
+    fn set_node_addr(&self, addr: Option<(NodeAddr, Instant)>) {
+        *self.node_addr.lock().unwrap_or_else(|e| e.into_inner()) = addr;
+    }
+
+    /// Probe the endpoint at `index`, returning whether it responded.
+    async fn probe(&self, index: usize) -> bool {
+        let url = format!("{}v1/node", self.endpoints.url(index));
+        let request = self.inner.get(url).timeout(PROBE_TIMEOUT);
+        match self.send(index, request).await {
+            Ok(response) => !response.status().is_server_error(),
+            Err(e) => {
+                tracing::debug!(
+                    endpoint = %self.endpoints.url(index),
+                    error = %e,
+                    "object endpoint probe failed"
+                );
+                false
+            }
+        }
+    }
 }
 
 impl JsonRpcProvider<HttpClient> {
@@ -63,16 +152,270 @@ impl JsonRpcProvider<HttpClient> {
         let inner = http_client(url, proxy_url)?;
         let objects = object_url.map(|url| ObjectClient {
             inner: reqwest::Client::new(),
+            endpoints: Arc::new(EndpointSet::new(url.clone(), Vec::new())),
             url,
+            node_addr: Default::default(),
         });
         Ok(Self {
             inner,
             chain_id,
             objects,
+            block_interval: Default::default(),
+            block_gas_limit: Default::default(),
+            confirmations: 0,
+            commit_fallback: false,
+            clock: Arc::new(SystemClock),
         })
     }
 }
 
+impl<C> JsonRpcProvider<C>
+where
+    C: Client + Send + Sync,
+{
+    /// Establishes connections to the node ahead of time, so the first user-visible operation
+    /// doesn't absorb DNS resolution and connection setup latency.
+    ///
+    /// This connects to the CometBFT RPC and, if configured, the Object API, whose node address
+    /// is cached for subsequent uploads.
+    /// The SDK's `Account::warm_up` also prefetches a signer's sequence.
+    pub async fn warm_up(&self) -> anyhow::Result<()> {
+        self.inner
+            .status()
+            .await
+            .context("failed to connect to CometBFT RPC")?;
+        if self.objects.is_some() {
+            self.node_addr()
+                .await
+                .context("failed to connect to object API")?;
+        }
+        Ok(())
+    }
+
+    /// Set the number of blocks a committed transaction must be buried under before
+    /// [`TxProvider::perform`] returns its receipt. Zero, the default, returns immediately.
+    ///
+    /// CometBFT blocks are final once committed, so this mainly guards against acting on a
+    /// node that later rolls back or replaces the block, e.g., after a state sync or a fork.
+    pub fn set_confirmations(&mut self, confirmations: u64) {
+        self.confirmations = confirmations;
+    }
+
+    /// Set whether a [`BroadcastMode::Commit`] broadcast that times out on the node falls back
+    /// to waiting for the transaction to be committed (see [`JsonRpcProvider::wait_for_commit`]).
+    ///
+    /// Nodes stop waiting for a commit after their `timeout_broadcast_tx_commit`, which is often
+    /// exceeded on congested networks even though the transaction lands shortly after.
+    pub fn set_commit_fallback(&mut self, enabled: bool) {
+        self.commit_fallback = enabled;
+    }
+
+    /// Set the clock that retries and receipt polling are timed with, e.g., a
+    /// [`ManualClock`](crate::clock::ManualClock) in tests. Defaults to the system clock.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Wait until a broadcasted transaction is committed, decoding its result with `f`.
+    pub async fn wait_for_commit<F, T>(&self, hash: Hash, f: F) -> anyhow::Result<TxResult<T>>
+    where
+        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        T: Sync + Send,
+    {
+        let block_interval = self.block_interval().await.ok();
+        let max_elapsed = block_interval
+            .map(|interval| COMMIT_FALLBACK_TIMEOUT.max(interval * 10))
+            .unwrap_or(COMMIT_FALLBACK_TIMEOUT);
+        let tx_res = retry(
+            new_backoff_policy(self.clock.clone(), max_elapsed, block_interval),
+            || async {
+                self.inner.tx(hash, false).await.map_err(|e| {
+                    backoff::Error::transient(anyhow!(
+                        "transaction was not committed (tx_hash={}): {}",
+                        hash.encode_hex_with_prefix(),
+                        e
+                    ))
+                })
+            },
+        )
+        .await?;
+        if tx_res.tx_result.code.is_err() {
+            return Err(anyhow!(format_err(
+                &tx_res.tx_result.info,
+                &tx_res.tx_result.log
+            )));
+        }
+
+        let return_data =
+            f(&tx_res.tx_result).context("error decoding data from deliver_tx in commit")?;
+
+        let receipt = if self.confirmations > 0 {
+            self.wait_for_confirmations(hash, self.confirmations)
+                .await?
+        } else {
+            self.eth_tx_receipt(hash, false).await?
+        };
+
+        Ok(TxResult::committed(receipt, Some(return_data)))
+    }
+
+    /// Wait until a transaction is `depth` blocks deep, returning its receipt.
+    ///
+    /// If the block containing the transaction is replaced while waiting, inclusion is
+    /// re-checked, and the wait restarts from the transaction's new block.
+    pub async fn wait_for_confirmations(
+        &self,
+        hash: Hash,
+        depth: u64,
+    ) -> anyhow::Result<et::TransactionReceipt> {
+        let block_interval = self.block_interval().await.ok();
+        let max_elapsed = block_interval
+            .map(|interval| {
+                // Allow a few blocks on top of the depth for the transaction to be included
+                let blocks = u32::try_from(depth).unwrap_or(u32::MAX).saturating_add(5);
+                RECEIPT_TIMEOUT.max(interval.saturating_mul(blocks))
+            })
+            .unwrap_or(RECEIPT_TIMEOUT);
+        let included = std::sync::Mutex::new(None);
+        retry(
+            new_backoff_policy(self.clock.clone(), max_elapsed, block_interval),
+            || async {
+                let transient = |e: anyhow::Error| backoff::Error::transient(e);
+                let tx_res = self.inner.tx(hash, false).await.map_err(|e| {
+                    transient(anyhow!(
+                        "cometbft transaction not found (tx_hash={}): {}",
+                        hash.encode_hex_with_prefix(),
+                        e
+                    ))
+                })?;
+                let header = self
+                    .inner
+                    .header(tx_res.height)
+                    .await
+                    .map_err(|e| transient(e.into()))?;
+                let block = (tx_res.height, header.header.hash());
+                {
+                    let mut included = included.lock().unwrap_or_else(|e| e.into_inner());
+                    if included.is_some_and(|previous| previous != block) {
+                        tracing::warn!(
+                            tx_hash = %hash.encode_hex_with_prefix(),
+                            height = %block.0,
+                            "transaction block was replaced; re-checking inclusion"
+                        );
+                    }
+                    *included = Some(block);
+                }
+
+                let latest = self
+                    .inner
+                    .status()
+                    .await
+                    .map_err(|e| transient(e.into()))?
+                    .sync_info
+                    .latest_block_height;
+                let confirmations = latest.value().saturating_sub(block.0.value());
+                if confirmations < depth {
+                    return Err(transient(anyhow!(
+                        "transaction has {} of {} confirmations (tx_hash={})",
+                        confirmations,
+                        depth,
+                        hash.encode_hex_with_prefix()
+                    )));
+                }
+                Ok(())
+            },
+        )
+        .await?;
+        self.eth_tx_receipt(hash, false).await
+    }
+
+    /// Add mirror Object API endpoints that serve the same objects as the primary.
+    ///
+    /// Downloads go to the fastest healthy endpoint, based on the latency of previous requests
+    /// and probes (see [`JsonRpcProvider::probe_object_endpoints`]).
+    /// Uploads always go to the primary, since the add transaction references its node.
+    pub fn set_object_mirrors(&mut self, mirrors: Vec<Url>) -> anyhow::Result<()> {
+        let client = self
+            .objects
+            .as_mut()
+            .ok_or_else(|| anyhow!("object provider is required"))?;
+        client.endpoints = Arc::new(EndpointSet::new(client.url.clone(), mirrors));
+        Ok(())
+    }
+
+    /// Probe the health and latency of all Object API endpoints.
+    ///
+    /// Probing periodically lets unmeasured mirrors be selected and unhealthy endpoints recover.
+    pub async fn probe_object_endpoints(&self) -> anyhow::Result<Vec<EndpointMetrics>> {
+        let client = self
+            .objects
+            .as_ref()
+            .ok_or_else(|| anyhow!("object provider is required"))?;
+        for index in 0..client.endpoints.len() {
+            client.probe(index).await;
+        }
+        Ok(client.endpoints.metrics())
+    }
+
+    /// Probe the chain RPC and the Object API endpoints.
+    ///
+    /// Unlike other calls, this doesn't fail when a service is down. When only the Object API
+    /// is unavailable, the report is [`Availability::Degraded`]: chain queries and transactions
+    /// still work, and object transfers fail with an [`ObjectApiUnavailableError`].
+    pub async fn probe(&self) -> AvailabilityReport {
+        let chain_error = self.inner.status().await.err().map(|e| e.to_string());
+        let mut objects_reachable = false;
+        if let Some(client) = &self.objects {
+            for index in 0..client.endpoints.len() {
+                objects_reachable |= client.probe(index).await;
+            }
+        }
+        let availability = match (chain_error.is_none(), objects_reachable) {
+            (false, _) => Availability::Unavailable,
+            (true, false) => Availability::Degraded,
+            (true, true) => Availability::Full,
+        };
+        AvailabilityReport {
+            availability,
+            chain_error,
+            objects: self.object_endpoint_metrics(),
+        }
+    }
+
+    /// Returns the observed health and latency of the Object API endpoints.
+    pub fn object_endpoint_metrics(&self) -> Vec<EndpointMetrics> {
+        self.objects
+            .as_ref()
+            .map(|client| client.endpoints.metrics())
+            .unwrap_or_default()
+    }
+
+    /// Returns the average block interval observed over recent blocks.
+    ///
+    /// The interval is measured once and cached.
+    pub async fn block_interval(&self) -> anyhow::Result<Duration> {
+        if let Some(interval) = self.block_interval.get() {
+            return Ok(*interval);
+        }
+
+        let status = self.inner.status().await?;
+        let latest = status.sync_info.latest_block_height.value();
+        let earliest = latest.saturating_sub(BLOCK_INTERVAL_SAMPLE_SIZE).max(1);
+        if earliest >= latest {
+            return Err(anyhow!("not enough blocks to measure the block interval"));
+        }
+        let header = self.inner.header(Height::try_from(earliest)?).await?;
+        let elapsed = status
+            .sync_info
+            .latest_block_time
+            .duration_since(header.header.time)
+            .map_err(|e| anyhow!("failed to measure block interval: {}", e))?;
+        let interval = elapsed / (latest - earliest) as u32;
+
+        Ok(*self.block_interval.get_or_init(|| interval))
+    }
+}
+
 impl<C> Provider<C> for JsonRpcProvider<C> where C: Client + Send + Sync {}
 
 impl<C> TendermintClient<C> for JsonRpcProvider<C>
@@ -106,6 +449,7 @@ impl<C> TxProvider for JsonRpcProvider<C>
 where
     C: Client + Sync + Send,
 {
+    #[tracing::instrument(name = "perform", skip_all, fields(mode = ?broadcast_mode))]
     async fn perform<F, T>(
         &self,
         message: ChainMessage,
@@ -140,7 +484,19 @@ where
                 }
             }
             BroadcastMode::Commit => {
-                let response = self.inner.broadcast_tx_commit(data).await?;
+                let hash = message::tx_hash(&message)?;
+                let response = match self.inner.broadcast_tx_commit(data).await {
+                    Ok(response) => response,
+                    Err(e) if self.commit_fallback && is_timeout(&e) => {
+                        tracing::warn!(
+                            tx_hash = %hash.encode_hex_with_prefix(),
+                            error = %e,
+                            "commit broadcast timed out; waiting for transaction to be committed"
+                        );
+                        return self.wait_for_commit(hash, f).await;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
                 if response.check_tx.code.is_err() {
                     return Err(anyhow!(format_err(
                         &response.check_tx.info,
@@ -156,7 +512,12 @@ where
                 let return_data = f(&response.deliver_tx)
                     .context("error decoding data from deliver_tx in commit")?;
 
-                let receipt = self.eth_tx_receipt(response.hash, false).await?;
+                let receipt = if self.confirmations > 0 {
+                    self.wait_for_confirmations(response.hash, self.confirmations)
+                        .await?
+                } else {
+                    self.eth_tx_receipt(response.hash, false).await?
+                };
 
                 Ok(TxResult::committed(receipt, Some(return_data)))
             }
@@ -169,26 +530,36 @@ where
         prove: bool,
     ) -> anyhow::Result<et::TransactionReceipt> {
         // Get tx and block header using backoff because they do not immediately show up
-        // in the indexer.
-        let tx_res = retry(new_backoff_policy(10), || async {
-            self.inner.tx(hash, prove).await.map_err(|e| {
-                backoff::Error::transient(anyhow!(
-                    "cometbft transaction not found (tx_hash={}): {}",
-                    hash.encode_hex_with_prefix(),
-                    e
-                ))
-            })
-        })
+        // in the indexer. The polling frequency is adapted to the observed block interval.
+        let block_interval = self.block_interval().await.ok();
+        let max_elapsed = block_interval
+            .map(|interval| RECEIPT_TIMEOUT.max(interval * 5))
+            .unwrap_or(RECEIPT_TIMEOUT);
+        let tx_res = retry(
+            new_backoff_policy(self.clock.clone(), max_elapsed, block_interval),
+            || async {
+                self.inner.tx(hash, prove).await.map_err(|e| {
+                    backoff::Error::transient(anyhow!(
+                        "cometbft transaction not found (tx_hash={}): {}",
+                        hash.encode_hex_with_prefix(),
+                        e
+                    ))
+                })
+            },
+        )
         .await?;
-        let header = retry(new_backoff_policy(10), || async {
-            self.inner.header(tx_res.height).await.map_err(|e| {
-                backoff::Error::transient(anyhow!(
-                    "transaction block header not found (tx_hash={}): {}",
-                    hash.encode_hex_with_prefix(),
-                    e
-                ))
-            })
-        })
+        let header = retry(
+            new_backoff_policy(self.clock.clone(), max_elapsed, block_interval),
+            || async {
+                self.inner.header(tx_res.height).await.map_err(|e| {
+                    backoff::Error::transient(anyhow!(
+                        "transaction block header not found (tx_hash={}): {}",
+                        hash.encode_hex_with_prefix(),
+                        e
+                    ))
+                })
+            },
+        )
         .await?;
 
         // Header is found, block results are expected to be present, raise error is not found
@@ -218,6 +589,15 @@ where
             ))
         }
     }
+
+    /// The limit is fetched once from the node's consensus params and cached.
+    async fn block_gas_limit(&self) -> anyhow::Result<u64> {
+        if let Some(limit) = self.block_gas_limit.get() {
+            return Ok(*limit);
+        }
+        let limit = message::block_gas_limit(self).await?;
+        Ok(*self.block_gas_limit.get_or_init(|| limit))
+    }
 }
 
 #[async_trait]
@@ -230,9 +610,18 @@ where
             .objects
             .clone()
             .ok_or_else(|| anyhow!("object provider is required"))?;
+        let now = self.clock.now();
+        if let Some(addr) = client.cached_node_addr(now) {
+            return Ok(addr);
+        }
 
         let url = format!("{}v1/node", client.url);
-        let response = client.inner.get(url).send().await?;
+        let response = client
+            .inner
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| unavailable(&client.url, e))?;
         if !response.status().is_success() {
             return Err(anyhow!(format!(
                 "failed to get node address info: {}",
@@ -241,9 +630,11 @@ where
         }
 
         let addr = response.json::<NodeAddr>().await?;
+        client.set_node_addr(Some((addr.clone(), now)));
         Ok(addr)
     }
 
+    #[tracing::instrument(name = "upload", skip_all, fields(size = size))]
     async fn upload(&self, body: reqwest::Body, size: u64) -> anyhow::Result<UploadResponse> {
         let client = self
             .objects
@@ -257,8 +648,16 @@ where
                 .mime_str("application/octet-stream")?,
         );
 
-        let response = client.inner.post(url).multipart(form).send().await?;
+        // The Object API may have restarted with a new node id, so the next add refetches it
+        let response = match client.inner.post(url).multipart(form).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                client.set_node_addr(None);
+                return Err(unavailable(&client.url, e));
+            }
+        };
         if !response.status().is_success() {
+            client.set_node_addr(None);
             return Err(anyhow!(format!(
                 "failed to upload object: {}",
                 response.text().await?
@@ -268,10 +667,15 @@ where
         Ok(upload_response)
     }
 
+    #[tracing::instrument(
+        name = "download",
+        skip_all,
+        fields(%address, key = %String::from_utf8_lossy(key), ?range, height = height)
+    )]
     async fn download(
         &self,
         address: Address,
-        key: &str,
+        key: &[u8],
         range: Option<String>,
         height: u64,
     ) -> anyhow::Result<reqwest::Response> {
@@ -280,20 +684,13 @@ where
             .clone()
             .ok_or_else(|| anyhow!("object provider is required"))?;
 
-        let url = format!(
-            "{}v1/objects/{}/{}?height={}",
-            client.url, address, key, height
-        );
-        let response = if let Some(range) = range {
-            client
-                .inner
-                .get(url)
-                .header("Range", format!("bytes={}", range))
-                .send()
-                .await?
-        } else {
-            client.inner.get(url).send().await?
-        };
+        let index = client.endpoints.select();
+        let url = object_url(client.endpoints.url(index), address, key, height);
+        let mut request = client.inner.get(url);
+        if let Some(range) = range {
+            request = request.header("Range", format!("bytes={}", range));
+        }
+        let response = client.send(index, request).await?;
         if !response.status().is_success() {
             return Err(anyhow!(format!(
                 "failed to download object: {}",
@@ -304,17 +701,20 @@ where
         Ok(response)
     }
 
-    async fn size(&self, address: Address, key: &str, height: u64) -> anyhow::Result<u64> {
+    #[tracing::instrument(
+        name = "size",
+        skip_all,
+        fields(%address, key = %String::from_utf8_lossy(key), height = height)
+    )]
+    async fn size(&self, address: Address, key: &[u8], height: u64) -> anyhow::Result<u64> {
         let client = self
             .objects
             .clone()
             .ok_or_else(|| anyhow!("object provider is required"))?;
 
-        let url = format!(
-            "{}v1/objects/{}/{}?height={}",
-            client.url, address, key, height
-        );
-        let response = client.inner.head(url).send().await?;
+        let index = client.endpoints.select();
+        let url = object_url(client.endpoints.url(index), address, key, height);
+        let response = client.send(index, client.inner.head(url)).await?;
         if !response.status().is_success() {
             return Err(anyhow!(format!(
                 "failed to get object size: {}",
@@ -330,6 +730,46 @@ where
             .parse()?;
         Ok(size)
     }
+
+    fn download_url(
+        &self,
+        address: Address,
+        key: &[u8],
+        height: u64,
+    ) -> anyhow::Result<reqwest::Url> {
+        let client = self
+            .objects
+            .as_ref()
+            .ok_or_else(|| anyhow!("object provider is required"))?;
+        // Shared URLs point at the primary endpoint, which is the most stable
+        let url = object_url(&client.url, address, key, height);
+        Ok(reqwest::Url::parse(&url)?)
+    }
+}
+
+/// Returns whether an RPC error is the node's response that it doesn't know of a transaction,
+/// e.g., from a lookup by hash.
+pub fn is_tx_not_found(err: &tendermint_rpc::Error) -> bool {
+    response_error(err)
+        .and_then(|e| e.data())
+        .is_some_and(|data| data.starts_with("tx (") && data.contains(") not found"))
+}
+
+/// Returns the error the node responded with, if the request reached the node.
+fn response_error(err: &tendermint_rpc::Error) -> Option<&ResponseError> {
+    match err.detail() {
+        ErrorDetail::Response(detail) => Some(&detail.source),
+        _ => None,
+    }
+}
+
+/// Returns whether an RPC error is the node giving up on waiting for a broadcasted transaction
+/// to be committed, after its `timeout_broadcast_tx_commit`.
+/// Client-side timeouts aren't included, since the transaction may never have reached the node.
+fn is_timeout(err: &tendermint_rpc::Error) -> bool {
+    response_error(err)
+        .and_then(|e| e.data())
+        .is_some_and(|data| data.contains("timed out waiting for tx to be included in a block"))
 }
 
 /// Format transaction receipt errors.
@@ -375,6 +815,19 @@ fn get_http_proxy_url(url_scheme: Scheme, proxy_url: Option<Url>) -> anyhow::Res
     }
 }
 
+/// Returns an [`ObjectApiUnavailableError`] if the request failed because the endpoint
+/// couldn't be reached, or the error as is otherwise.
+fn unavailable(url: &Url, err: reqwest::Error) -> anyhow::Error {
+    if err.is_connect() || err.is_timeout() {
+        anyhow::Error::new(ObjectApiUnavailableError {
+            url: url.to_string(),
+            reason: err.to_string(),
+        })
+    } else {
+        err.into()
+    }
+}
+
 /// Create a Tendermint HTTP client.
 pub fn http_client(url: Url, proxy_url: Option<Url>) -> anyhow::Result<HttpClient> {
     let proxy_url = get_http_proxy_url(url.scheme(), proxy_url)?;
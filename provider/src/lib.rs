@@ -5,12 +5,18 @@
 //!
 //! A chain and object provider for Recall.
 
+pub mod audit;
+pub mod clock;
+pub mod endpoint;
+pub mod error;
 pub mod json_rpc;
 pub mod message;
 pub mod object;
 mod provider;
 pub mod query;
+pub mod replay;
 pub mod response;
+pub mod snapshot;
 pub mod tx;
 pub mod util;
 
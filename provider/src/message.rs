@@ -2,8 +2,12 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use anyhow::anyhow;
 use fendermint_vm_actor_interface::system::SYSTEM_ACTOR_ADDR;
 use fvm_shared::{address::Address, econ::TokenAmount};
+use tendermint::{crypto::default::Sha256, hash::Hash};
+
+use crate::{Client, TendermintClient};
 
 pub use crate::{
     fvm_ipld_encoding::RawBytes,
@@ -11,14 +15,14 @@ pub use crate::{
 };
 pub use fendermint_vm_message::{
     chain::ChainMessage,
-    signed::{OriginKind, SignedMessage},
+    signed::{sign_secp256k1, OriginKind, SignedMessage},
 };
 
 const MIN_GAS_FEE_CAP: u64 = 100;
 const MIN_GAS_PREMIUM: u64 = 1;
 
 /// Gas parameters for transactions.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct GasParams {
     /// Maximum amount of gas that can be charged.
     pub gas_limit: u64,
@@ -60,6 +64,49 @@ impl GasParams {
             self.gas_premium = min_gas_premium;
         }
     }
+
+    /// Validates the gas params before signing.
+    ///
+    /// Returns a descriptive error for params the node would reject, so that the transaction
+    /// isn't signed with (and doesn't use up) a sequence.
+    /// `block_gas_limit` is the network's maximum gas limit, see [`block_gas_limit`].
+    /// A zero gas limit is allowed because it's estimated before signing.
+    pub fn validate(&self, block_gas_limit: u64) -> anyhow::Result<()> {
+        if self.gas_limit > block_gas_limit {
+            return Err(anyhow!(
+                "gas limit {} exceeds the block gas limit {}",
+                self.gas_limit,
+                block_gas_limit
+            ));
+        }
+        if self.gas_fee_cap < self.gas_premium {
+            return Err(anyhow!(
+                "gas fee cap {} must be greater than or equal to the gas premium {}",
+                self.gas_fee_cap.atto(),
+                self.gas_premium.atto()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the network's maximum gas limit per block, read from the node's latest consensus
+/// params.
+/// Falls back to [`fvm_shared::BLOCK_GAS_LIMIT`] if the network doesn't cap block gas.
+pub async fn block_gas_limit<C>(provider: &impl TendermintClient<C>) -> anyhow::Result<u64>
+where
+    C: Client + Send + Sync,
+{
+    let res = provider
+        .underlying()
+        .latest_consensus_params()
+        .await
+        .map_err(|e| anyhow!("failed to get consensus params: {}", e))?;
+    // CometBFT uses -1 for no limit
+    match u64::try_from(res.consensus_params.block.max_gas) {
+        Ok(max_gas) if max_gas > 0 => Ok(max_gas),
+        _ => Ok(fvm_shared::BLOCK_GAS_LIMIT),
+    }
 }
 
 /// Convenience method to create a local unsigned read-only message.
@@ -83,6 +130,14 @@ pub fn serialize(message: &ChainMessage) -> anyhow::Result<Vec<u8>> {
     Ok(fvm_ipld_encoding::to_vec(message)?)
 }
 
+/// Returns the CometBFT hash of a [`ChainMessage`], which the node indexes its transaction by.
+pub fn tx_hash(message: &ChainMessage) -> anyhow::Result<Hash> {
+    let data = serialize(message)?;
+    Ok(Hash::Sha256(
+        <Sha256 as tendermint::crypto::Sha256>::digest(data),
+    ))
+}
+
 /// Convenience method to serialize a [`SignedMessage`] for authentication.
 pub fn serialize_signed(message: &SignedMessage) -> anyhow::Result<Vec<u8>> {
     Ok(fvm_ipld_encoding::to_vec(message)?)
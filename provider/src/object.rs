@@ -1,10 +1,12 @@
 // Copyright 2025 Recall Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use anyhow::anyhow;
 use async_trait::async_trait;
 use fvm_shared::address::Address;
 pub use iroh::net::NodeAddr;
 use serde::Deserialize;
+use tendermint_rpc::Url;
 
 /// Provider for object interactions.
 #[async_trait]
@@ -16,19 +18,62 @@ pub trait ObjectProvider: Send + Sync {
     async fn upload(&self, body: reqwest::Body, size: u64) -> anyhow::Result<UploadResponse>;
 
     /// Download an object.
+    ///
+    /// Keys are arbitrary bytes and are percent-encoded in the request path.
     async fn download(
         &self,
         address: Address,
-        key: &str,
+        key: &[u8],
         range: Option<String>,
         height: u64,
     ) -> anyhow::Result<reqwest::Response>;
 
     /// Gets the object size.
-    async fn size(&self, address: Address, key: &str, height: u64) -> anyhow::Result<u64>;
+    async fn size(&self, address: Address, key: &[u8], height: u64) -> anyhow::Result<u64>;
+
+    /// Returns the URL that [`ObjectProvider::download`] requests an object from.
+    ///
+    /// Objects are publicly readable, so the URL can be shared to give read access to a single
+    /// object without sharing keys. The Object API doesn't support signed or expiring URLs;
+    /// a non-zero `height` pins the URL to the object as of that height.
+    /// The default implementation returns an error, for providers that can't build URLs.
+    fn download_url(
+        &self,
+        address: Address,
+        key: &[u8],
+        height: u64,
+    ) -> anyhow::Result<reqwest::Url> {
+        let _ = (address, key, height);
+        Err(anyhow!("object provider doesn't support download URLs"))
+    }
+}
+
+/// Returns the URL of an object on an Object API endpoint.
+pub fn object_url(endpoint: &Url, address: Address, key: &[u8], height: u64) -> String {
+    format!(
+        "{}v1/objects/{}/{}?height={}",
+        endpoint,
+        address,
+        encode_key(key),
+        height
+    )
+}
+
+/// Percent-encode an object key for use in a request path.
+/// Unreserved characters and the `/` separator are kept as-is.
+fn encode_key(key: &[u8]) -> String {
+    let mut encoded = String::with_capacity(key.len());
+    for &b in key {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b'/') {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+    encoded
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct UploadResponse {
     pub hash: String,
     pub metadata_hash: String,
@@ -27,6 +27,17 @@ pub struct QueryResponse<T> {
     pub value: T,
 }
 
+/// The result of simulating a message with [`QueryProvider::simulate`].
+#[derive(Debug, Clone)]
+pub struct SimulationResult<T> {
+    /// The height at which the simulation was performed.
+    pub height: Height,
+    /// Amount of gas used by the message.
+    pub gas_used: i64,
+    /// The decoded return value, or the reason the message would fail.
+    pub result: Result<T, String>,
+}
+
 /// Provider for submitting queries.
 #[async_trait]
 pub trait QueryProvider: Send + Sync {
@@ -50,6 +61,42 @@ pub trait QueryProvider: Send + Sync {
         Ok(QueryResponse { height, value })
     }
 
+    /// Simulate a message by running it in a read-only fashion.
+    ///
+    /// Unlike [`QueryProvider::call`], a message that would fail doesn't result in an error.
+    /// Instead, the failure reason is returned along with the gas used.
+    async fn simulate<F, T>(
+        &self,
+        message: Message,
+        height: FvmQueryHeight,
+        f: F,
+    ) -> anyhow::Result<SimulationResult<T>>
+    where
+        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        T: Sync + Send,
+    {
+        let res = self
+            .query(FvmQuery::Call(Box::new(message)), height)
+            .await?;
+        let height = res.height;
+        let tx = extract(res, parse_deliver_tx)?;
+        let result = if tx.code.is_err() {
+            let reason = [tx.info.as_str(), tx.log.as_str()]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join("; ");
+            Err(format!("exit code {}: {}", tx.code.value(), reason))
+        } else {
+            Ok(f(&tx)?)
+        };
+        Ok(SimulationResult {
+            height,
+            gas_used: tx.gas_used,
+            result,
+        })
+    }
+
     /// Estimate the gas limit of a message.
     async fn estimate_gas_limit(
         &self,
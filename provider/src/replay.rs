@@ -0,0 +1,405 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Records provider reads to a fixture file and replays them offline.
+//!
+//! A [`Recorder`] wraps a live provider and captures chain queries and Object API downloads
+//! and size checks. The captured [`Cassette`] can be saved as JSON and served by a
+//! [`Replayer`], so higher-level flows can be tested without a network.
+//! Uploads and transactions aren't recorded; they pass through a recorder and fail on replay.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use base64::Engine;
+use bytes::Bytes;
+use fvm_shared::address::Address;
+use serde::{Deserialize, Serialize};
+use tendermint_rpc::endpoint::abci_query::AbciQuery;
+
+use crate::object::{NodeAddr, ObjectProvider, UploadResponse};
+use crate::query::{FvmQuery, FvmQueryHeight, QueryProvider};
+
+/// A recorded provider request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Request {
+    /// An ABCI query. The query is base64-encoded CBOR.
+    Query { query: String, height: u64 },
+    /// An Object API download. The key is base64-encoded.
+    Download {
+        address: String,
+        key: String,
+        range: Option<String>,
+        height: u64,
+    },
+    /// An Object API size check. The key is base64-encoded.
+    Size {
+        address: String,
+        key: String,
+        height: u64,
+    },
+}
+
+impl Request {
+    fn query(query: &FvmQuery, height: FvmQueryHeight) -> anyhow::Result<Self> {
+        let query = fvm_ipld_encoding::to_vec(query).context("failed to encode query")?;
+        Ok(Self::Query {
+            query: encode(&query),
+            height: height.into(),
+        })
+    }
+
+    fn download(address: Address, key: &[u8], range: Option<String>, height: u64) -> Self {
+        Self::Download {
+            address: address.to_string(),
+            key: encode(key),
+            range,
+            height,
+        }
+    }
+
+    fn size(address: Address, key: &[u8], height: u64) -> Self {
+        Self::Size {
+            address: address.to_string(),
+            key: encode(key),
+            height,
+        }
+    }
+}
+
+/// A recorded provider response.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response {
+    /// An ABCI query response.
+    Query(AbciQuery),
+    /// An Object API download response. The body is base64-encoded.
+    Download { status: u16, body: String },
+    /// An Object API object size.
+    Size(u64),
+    /// An error returned by the provider.
+    Error(String),
+}
+
+impl Response {
+    fn from_result<T>(result: &anyhow::Result<T>, f: impl FnOnce(&T) -> Self) -> Self {
+        match result {
+            Ok(value) => f(value),
+            Err(e) => Self::Error(format!("{:#}", e)),
+        }
+    }
+}
+
+/// A request and the response it got.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interaction {
+    /// The request.
+    pub request: Request,
+    /// The response.
+    pub response: Response,
+}
+
+/// Recorded interactions in the order they were made.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    /// The interactions.
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Load a cassette from a JSON fixture file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read(path)
+            .with_context(|| format!("cannot read fixture file {}", path.display()))?;
+        serde_json::from_slice(&json)
+            .with_context(|| format!("invalid fixture file {}", path.display()))
+    }
+
+    /// Save the cassette to a JSON fixture file.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("cannot write fixture file {}", path.display()))
+    }
+}
+
+/// A provider wrapper that records the reads it passes through.
+pub struct Recorder<'a, P> {
+    provider: &'a P,
+    cassette: Mutex<Cassette>,
+}
+
+impl<'a, P> Recorder<'a, P> {
+    /// Create a recorder around a provider.
+    pub fn new(provider: &'a P) -> Self {
+        Recorder {
+            provider,
+            cassette: Mutex::default(),
+        }
+    }
+
+    /// Returns the interactions recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.cassette
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Save the interactions recorded so far to a JSON fixture file.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.cassette().save(path)
+    }
+
+    fn record(&self, request: Request, response: Response) {
+        self.cassette
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .interactions
+            .push(Interaction { request, response });
+    }
+}
+
+#[async_trait]
+impl<P> QueryProvider for Recorder<'_, P>
+where
+    P: QueryProvider,
+{
+    async fn query(&self, query: FvmQuery, height: FvmQueryHeight) -> anyhow::Result<AbciQuery> {
+        let request = Request::query(&query, height)?;
+        let result = self.provider.query(query, height).await;
+        self.record(
+            request,
+            Response::from_result(&result, |res| Response::Query(res.clone())),
+        );
+        result
+    }
+}
+
+#[async_trait]
+impl<P> ObjectProvider for Recorder<'_, P>
+where
+    P: ObjectProvider,
+{
+    async fn node_addr(&self) -> anyhow::Result<NodeAddr> {
+        self.provider.node_addr().await
+    }
+
+    async fn upload(&self, body: reqwest::Body, size: u64) -> anyhow::Result<UploadResponse> {
+        self.provider.upload(body, size).await
+    }
+
+    async fn download(
+        &self,
+        address: Address,
+        key: &[u8],
+        range: Option<String>,
+        height: u64,
+    ) -> anyhow::Result<reqwest::Response> {
+        let request = Request::download(address, key, range.clone(), height);
+        // Buffer the body so it can be both recorded and returned
+        let result = match self.provider.download(address, key, range, height).await {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                response
+                    .bytes()
+                    .await
+                    .map(|body| (status, body))
+                    .map_err(Into::into)
+            }
+            Err(e) => Err(e),
+        };
+        self.record(
+            request,
+            Response::from_result(&result, |(status, body)| Response::Download {
+                status: *status,
+                body: encode(body),
+            }),
+        );
+        let (status, body) = result?;
+        new_response(status, body)
+    }
+
+    async fn size(&self, address: Address, key: &[u8], height: u64) -> anyhow::Result<u64> {
+        let request = Request::size(address, key, height);
+        let result = self.provider.size(address, key, height).await;
+        self.record(
+            request,
+            Response::from_result(&result, |size| Response::Size(*size)),
+        );
+        result
+    }
+
+    fn download_url(
+        &self,
+        address: Address,
+        key: &[u8],
+        height: u64,
+    ) -> anyhow::Result<reqwest::Url> {
+        self.provider.download_url(address, key, height)
+    }
+}
+
+/// A provider that serves responses from a [`Cassette`] without a network.
+///
+/// Each request is answered with the first unused recorded response to an equal request.
+/// Once those are used up, the last one is repeated, so polling loops that make more requests
+/// than were recorded still settle.
+pub struct Replayer {
+    cassette: Cassette,
+    used: Mutex<Vec<bool>>,
+}
+
+impl Replayer {
+    /// Create a replayer serving the cassette.
+    pub fn new(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Replayer {
+            cassette,
+            used: Mutex::new(used),
+        }
+    }
+
+    /// Create a replayer serving a JSON fixture file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Returns the recorded response to the request.
+    pub fn replay(&self, request: &Request) -> anyhow::Result<Response> {
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        let mut last = None;
+        for (index, interaction) in self.cassette.interactions.iter().enumerate() {
+            if &interaction.request != request {
+                continue;
+            }
+            if !used[index] {
+                used[index] = true;
+                return Ok(interaction.response.clone());
+            }
+            last = Some(&interaction.response);
+        }
+        last.cloned()
+            .ok_or_else(|| anyhow!("no recorded response for request {:?}", request))
+    }
+}
+
+#[async_trait]
+impl QueryProvider for Replayer {
+    async fn query(&self, query: FvmQuery, height: FvmQueryHeight) -> anyhow::Result<AbciQuery> {
+        match self.replay(&Request::query(&query, height)?)? {
+            Response::Query(res) => Ok(res),
+            response => unexpected(response),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectProvider for Replayer {
+    async fn node_addr(&self) -> anyhow::Result<NodeAddr> {
+        Err(anyhow!("node address is not available when replaying"))
+    }
+
+    async fn upload(&self, _body: reqwest::Body, _size: u64) -> anyhow::Result<UploadResponse> {
+        Err(anyhow!("uploads are not supported when replaying"))
+    }
+
+    async fn download(
+        &self,
+        address: Address,
+        key: &[u8],
+        range: Option<String>,
+        height: u64,
+    ) -> anyhow::Result<reqwest::Response> {
+        match self.replay(&Request::download(address, key, range, height))? {
+            Response::Download { status, body } => new_response(status, decode(&body)?),
+            response => unexpected(response),
+        }
+    }
+
+    async fn size(&self, address: Address, key: &[u8], height: u64) -> anyhow::Result<u64> {
+        match self.replay(&Request::size(address, key, height))? {
+            Response::Size(size) => Ok(size),
+            response => unexpected(response),
+        }
+    }
+
+    fn download_url(
+        &self,
+        _address: Address,
+        _key: &[u8],
+        _height: u64,
+    ) -> anyhow::Result<reqwest::Url> {
+        Err(anyhow!("download URLs are not available when replaying"))
+    }
+}
+
+/// Returns the error a recorded error response stands for, or an error for a response of the
+/// wrong kind.
+fn unexpected<T>(response: Response) -> anyhow::Result<T> {
+    match response {
+        Response::Error(e) => Err(anyhow!(e)),
+        response => Err(anyhow!("unexpected recorded response {:?}", response)),
+    }
+}
+
+fn new_response(status: u16, body: impl Into<Bytes>) -> anyhow::Result<reqwest::Response> {
+    let response = http::Response::builder().status(status).body(body.into())?;
+    Ok(reqwest::Response::from(response))
+}
+
+fn encode(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+fn decode(data: &str) -> anyhow::Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .context("error parsing base64 to bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay() {
+        let request = Request::size(Address::new_id(1000), b"foo", 10);
+        let cassette = Cassette {
+            interactions: vec![
+                Interaction {
+                    request: request.clone(),
+                    response: Response::Error("object not resolved".into()),
+                },
+                Interaction {
+                    request: request.clone(),
+                    response: Response::Size(5),
+                },
+            ],
+        };
+        let path = std::env::temp_dir().join(format!("recall-replay-{}.json", std::process::id()));
+        cassette.save(&path).unwrap();
+        let replayer = Replayer::load(&path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert!(matches!(
+            replayer.replay(&request).unwrap(),
+            Response::Error(_)
+        ));
+        // The last response is repeated once all are used
+        for _ in 0..2 {
+            assert!(matches!(
+                replayer.replay(&request).unwrap(),
+                Response::Size(5)
+            ));
+        }
+        assert!(replayer
+            .replay(&Request::size(Address::new_id(1000), b"bar", 10))
+            .is_err());
+    }
+}
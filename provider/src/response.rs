@@ -25,10 +25,12 @@ pub(crate) fn encode_data(data: &[u8]) -> Bytes {
 /// Parse what Tendermint returns in the `data` field of [`DeliverTx`] into bytes.
 /// Somewhere along the way it replaces them with the bytes of a Base64 encoded string,
 /// and `tendermint_rpc` does not undo that wrapping.
+///
+/// The base64 is decoded directly from the response buffer to avoid copying large responses,
+/// e.g., object listings, into an intermediate string.
 pub(crate) fn decode_data(data: &Bytes) -> anyhow::Result<RawBytes> {
-    let b64 = String::from_utf8(data.to_vec()).context("error parsing data as base64 string")?;
     let data = base64::engine::general_purpose::STANDARD
-        .decode(b64)
+        .decode(data)
         .context("error parsing base64 to bytes")?;
     Ok(RawBytes::from(data))
 }
@@ -0,0 +1,94 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use async_trait::async_trait;
+use fvm_shared::address::Address;
+use tendermint_rpc::endpoint::abci_query::AbciQuery;
+
+use crate::object::{NodeAddr, ObjectProvider, UploadResponse};
+use crate::query::{FvmQuery, FvmQueryHeight, QueryProvider};
+
+/// A read-only view of the chain pinned at a single block height.
+///
+/// All queries and downloads made through the snapshot are routed to the captured height,
+/// regardless of the height they request, so multi-step reads (e.g., listing a bucket and then
+/// getting its objects) see a consistent state instead of racing the chain head.
+pub struct Snapshot<'a, P> {
+    provider: &'a P,
+    height: u64,
+}
+
+impl<'a, P> Snapshot<'a, P>
+where
+    P: QueryProvider,
+{
+    /// Create a snapshot at the latest committed height.
+    pub async fn latest(provider: &'a P) -> anyhow::Result<Self> {
+        let res = provider.state_params(FvmQueryHeight::Committed).await?;
+        Ok(Self::at(provider, res.height.value()))
+    }
+
+    /// Create a snapshot at the given height.
+    pub fn at(provider: &'a P, height: u64) -> Self {
+        Snapshot { provider, height }
+    }
+
+    /// Returns the snapshot height.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Returns the snapshot height as a query height.
+    pub fn query_height(&self) -> FvmQueryHeight {
+        FvmQueryHeight::Height(self.height)
+    }
+}
+
+#[async_trait]
+impl<P> QueryProvider for Snapshot<'_, P>
+where
+    P: QueryProvider,
+{
+    async fn query(&self, query: FvmQuery, _height: FvmQueryHeight) -> anyhow::Result<AbciQuery> {
+        self.provider.query(query, self.query_height()).await
+    }
+}
+
+#[async_trait]
+impl<P> ObjectProvider for Snapshot<'_, P>
+where
+    P: ObjectProvider,
+{
+    async fn node_addr(&self) -> anyhow::Result<NodeAddr> {
+        self.provider.node_addr().await
+    }
+
+    async fn upload(&self, body: reqwest::Body, size: u64) -> anyhow::Result<UploadResponse> {
+        self.provider.upload(body, size).await
+    }
+
+    async fn download(
+        &self,
+        address: Address,
+        key: &[u8],
+        range: Option<String>,
+        _height: u64,
+    ) -> anyhow::Result<reqwest::Response> {
+        self.provider
+            .download(address, key, range, self.height)
+            .await
+    }
+
+    async fn size(&self, address: Address, key: &[u8], _height: u64) -> anyhow::Result<u64> {
+        self.provider.size(address, key, self.height).await
+    }
+
+    fn download_url(
+        &self,
+        address: Address,
+        key: &[u8],
+        _height: u64,
+    ) -> anyhow::Result<reqwest::Url> {
+        self.provider.download_url(address, key, self.height)
+    }
+}
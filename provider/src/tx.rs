@@ -14,7 +14,7 @@ use crate::message::ChainMessage;
 pub use tendermint::{abci::response::DeliverTx, block::Height, Hash};
 
 /// Controls how the provider waits for the result of a transaction.
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 pub enum BroadcastMode {
     /// Return immediately after the transaction is broadcasted without waiting for check results.
     Async,
@@ -46,6 +46,23 @@ pub enum TxStatus {
     Pending(et::Transaction),
     /// The transaction has been committed to a finalized block.
     Committed(et::TransactionReceipt),
+    /// The transaction was built and estimated, but not sent.
+    DryRun(TxEstimate),
+}
+
+/// The estimated cost of a transaction that was built but not sent.
+#[derive(Debug, Clone, Serialize)]
+pub struct TxEstimate {
+    /// The transaction gas limit.
+    pub gas_limit: u64,
+    /// The maximum gas fee, i.e., the gas limit at the gas fee cap.
+    pub max_gas_fee: String,
+    /// Credits the transaction would commit, e.g., to store an object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credits: Option<String>,
+    /// The token cost of the credits at the current token to credit rate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<String>,
 }
 
 /// The result of a transaction.
@@ -89,11 +106,21 @@ impl<T> TxResult<T> {
         }
     }
 
+    /// Create a new result for a transaction that wasn't sent.
+    pub fn dry_run(estimate: TxEstimate) -> Self {
+        TxResult {
+            status: TxStatus::DryRun(estimate),
+            data: None,
+        }
+    }
+
     /// Returns the transaction hash.
+    /// A transaction that wasn't sent has a zero hash.
     pub fn hash(&self) -> et::TxHash {
         match self.status {
             TxStatus::Pending(ref tx) => tx.hash(),
             TxStatus::Committed(ref receipt) => receipt.transaction_hash,
+            TxStatus::DryRun(_) => et::TxHash::zero(),
         }
     }
 }
@@ -118,4 +145,11 @@ pub trait TxProvider: Send + Sync {
         hash: Hash,
         prove: bool,
     ) -> anyhow::Result<et::TransactionReceipt>;
+
+    /// Returns the network's maximum gas limit per block, which gas params are validated
+    /// against before signing.
+    /// The default implementation returns [`fvm_shared::BLOCK_GAS_LIMIT`].
+    async fn block_gas_limit(&self) -> anyhow::Result<u64> {
+        Ok(fvm_shared::BLOCK_GAS_LIMIT)
+    }
 }
@@ -2,21 +2,101 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::anyhow;
+use ethers::core::types as et;
 use fendermint_actor_blobs_shared::state::{Credit, TokenCreditRate};
 use fendermint_vm_actor_interface::eam::EthAddress;
 use fendermint_vm_message::query::FvmQueryHeight;
 use fvm_shared::{
-    address::{Address, Error, Network, Payload},
-    bigint::BigInt,
+    address::{current_network, Address, Error, Network, Payload},
+    bigint::{BigInt, Sign},
     econ::TokenAmount,
 };
-use rust_decimal::Decimal;
+use num_traits::ToPrimitive;
+
+use crate::error::{self, ErrorCode};
 
 pub use ipc_api::{ethers_address_to_fil_address, evm::payload_to_evm_address};
 
+/// Number of recent blocks used to measure a chain's block interval.
+pub const BLOCK_INTERVAL_SAMPLE_SIZE: u64 = 10;
+/// Lower bound for polling intervals derived from the block interval.
+const MIN_POLLING_INTERVAL: Duration = Duration::from_millis(100);
+/// Upper bound for polling intervals derived from the block interval.
+const MAX_POLLING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Returns a polling interval suited to the given block interval.
+///
+/// Polling twice per block keeps latency low on fast subnets without hammering slow parents.
+pub fn polling_interval(block_interval: Duration) -> Duration {
+    (block_interval / 2).clamp(MIN_POLLING_INTERVAL, MAX_POLLING_INTERVAL)
+}
+
+/// Error returned when an address's network prefix doesn't match the expected FVM network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkMismatchError {
+    /// The network the address was expected to belong to.
+    pub expected: Network,
+    /// The network indicated by the address prefix.
+    pub actual: Network,
+}
+
+impl fmt::Display for NetworkMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: address has a {:?} prefix but the current network is {:?}",
+            self.code(),
+            self.actual,
+            self.expected
+        )
+    }
+}
+
+impl std::error::Error for NetworkMismatchError {}
+
+impl ErrorCode for NetworkMismatchError {
+    fn code(&self) -> &'static str {
+        error::NETWORK_MISMATCH
+    }
+}
+
+/// Parse an f/t/eth-address from string, validating it against the current FVM network.
+///
+/// See [`parse_address_for_network`].
+pub fn parse_network_address(s: &str) -> anyhow::Result<Address> {
+    parse_address_for_network(s, current_network())
+}
+
+/// Parse an f/t/eth-address from string, validating it against the given FVM network.
+///
+/// Surrounding whitespace and prefix case are normalized, and eth-addresses,
+/// which aren't network specific, are converted to delegated addresses.
+/// An f/t-address with a prefix for a different network results in a [`NetworkMismatchError`].
+pub fn parse_address_for_network(s: &str, network: Network) -> anyhow::Result<Address> {
+    let s = s.trim();
+    let actual = match s.as_bytes().first() {
+        Some(b'f' | b'F') => Network::Mainnet,
+        Some(b't' | b'T') => Network::Testnet,
+        _ => {
+            let addr = ethers::types::Address::from_str(s)?;
+            return ethers_address_to_fil_address(&addr);
+        }
+    };
+    if actual != network {
+        return Err(NetworkMismatchError {
+            expected: network,
+            actual,
+        }
+        .into());
+    }
+    Ok(network.parse_address(&s.to_lowercase())?)
+}
+
 /// Parse an f/eth-address from string.
 pub fn parse_address(s: &str) -> anyhow::Result<Address> {
     let addr = Network::Mainnet
@@ -44,18 +124,92 @@ pub fn get_eth_address(a: Address) -> anyhow::Result<ethers::types::Address> {
     }
 }
 
-/// Parse the token amount from string.
+/// Number of decimal places of a whole token.
+const TOKEN_DECIMALS: usize = 18;
+
+/// Parse the token amount from a decimal string with an optional unit suffix.
+///
+/// Supported units are whole tokens (`RECALL`, the default) and `atto` (or `attoRECALL`),
+/// e.g., "1.5", "1.5 RECALL", or "100 atto".
+/// Parsing is exact, so whole token amounts support up to 18 decimal places.
 pub fn parse_token_amount(s: &str) -> anyhow::Result<TokenAmount> {
-    let decimal = Decimal::from_str(s)?;
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => (s[..i].trim_end(), &s[i..]),
+        None => (s, ""),
+    };
+    let decimals = match unit.to_lowercase().as_str() {
+        "" | "recall" => TOKEN_DECIMALS,
+        "atto" | "attorecall" => 0,
+        _ => return Err(anyhow!("unknown unit '{}' in '{}'", unit, s)),
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(anyhow!("invalid token amount '{}'", s));
+    }
+    if fraction.len() > decimals {
+        return Err(anyhow!(
+            "token amount '{}' has more than {} decimal places",
+            s,
+            decimals
+        ));
+    }
 
-    // Scale the decimal to atto (10^18)
-    let decimal_in_attos = decimal
-        .checked_mul(Decimal::new(1_000_000_000_000_000_000, 0))
-        .ok_or(anyhow!("overflow occurred when scaling '{}'", s))?;
+    let attos = format!("{}{:0<width$}", whole, fraction, width = decimals);
+    Ok(TokenAmount::from_atto(BigInt::from_str(&attos)?))
+}
+
+/// Conversion and formatting helpers for [`TokenAmount`].
+///
+/// `TokenAmount` displays as whole tokens with 18 decimal places of precision, e.g., "1.5".
+pub trait TokenAmountExt: Sized {
+    /// Parse an amount from a decimal string with an optional unit suffix.
+    /// See [`parse_token_amount`].
+    fn parse(s: &str) -> anyhow::Result<Self>;
+
+    /// Create an amount from an EVM value in attoRECALL.
+    fn from_u256(value: et::U256) -> Self;
+
+    /// Convert the amount to an EVM value in attoRECALL.
+    fn to_u256(&self) -> anyhow::Result<et::U256>;
+
+    /// Convert the amount to attoRECALL as a `u128`.
+    fn to_atto_u128(&self) -> anyhow::Result<u128>;
+
+    /// Format the amount as whole tokens with the unit, e.g., "1.5 RECALL".
+    fn to_recall_string(&self) -> String;
+}
+
+impl TokenAmountExt for TokenAmount {
+    fn parse(s: &str) -> anyhow::Result<Self> {
+        parse_token_amount(s)
+    }
+
+    fn from_u256(value: et::U256) -> Self {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        TokenAmount::from_atto(BigInt::from_bytes_be(Sign::Plus, &bytes))
+    }
+
+    fn to_u256(&self) -> anyhow::Result<et::U256> {
+        let (sign, bytes) = self.atto().to_bytes_be();
+        if sign == Sign::Minus || bytes.len() > 32 {
+            return Err(anyhow!("token amount {} is out of range", self));
+        }
+        Ok(et::U256::from_big_endian(&bytes))
+    }
 
-    Ok(TokenAmount::from_atto(BigInt::from_str(
-        &decimal_in_attos.trunc().to_string(),
-    )?))
+    fn to_atto_u128(&self) -> anyhow::Result<u128> {
+        self.atto()
+            .to_u128()
+            .ok_or_else(|| anyhow!("token amount {} is out of range", self))
+    }
+
+    fn to_recall_string(&self) -> String {
+        format!("{} RECALL", self)
+    }
 }
 
 /// Parse the token amount in attoRECALL (10**18) from string.
@@ -108,3 +262,60 @@ pub fn parse_metadata_optional(s: &str) -> anyhow::Result<(String, Option<String
         None => Ok((s.to_string(), None)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_for_network() {
+        let addr = parse_address_for_network(" T01 ", Network::Testnet).unwrap();
+        assert_eq!(addr, Address::new_id(1));
+
+        let err = parse_address_for_network("f01", Network::Testnet).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NetworkMismatchError>(),
+            Some(&NetworkMismatchError {
+                expected: Network::Testnet,
+                actual: Network::Mainnet,
+            })
+        );
+
+        // eth-addresses are valid on any network
+        let eth_addr = "0x7f6f1c1d6e0d0b5c0f8a4d2e3b1a9c8d7e6f5a4b";
+        assert!(parse_address_for_network(eth_addr, Network::Mainnet).is_ok());
+        assert!(parse_address_for_network(eth_addr, Network::Testnet).is_ok());
+    }
+
+    #[test]
+    fn test_token_amount_ext() {
+        let amount = TokenAmount::parse("1.5").unwrap();
+        assert_eq!(amount.to_recall_string(), "1.5 RECALL");
+        let value = amount.to_u256().unwrap();
+        assert_eq!(value, et::U256::from(1_500_000_000_000_000_000u128));
+        assert_eq!(TokenAmount::from_u256(value), amount);
+        assert_eq!(amount.to_atto_u128().unwrap(), 1_500_000_000_000_000_000);
+        assert!(TokenAmount::from_atto(-1).to_u256().is_err());
+    }
+
+    #[test]
+    fn test_parse_token_amount() {
+        let atto = |s: &str| parse_token_amount(s).unwrap().atto().to_string();
+        assert_eq!(atto("1"), "1000000000000000000");
+        assert_eq!(atto("1.5 RECALL"), "1500000000000000000");
+        assert_eq!(atto("0.000000000000000001"), "1");
+        assert_eq!(atto(".25"), "250000000000000000");
+        assert_eq!(atto("100 atto"), "100");
+        assert_eq!(atto("100attoRECALL"), "100");
+        assert_eq!(
+            atto("123456789012345678901234567890.123456789012345678"),
+            "123456789012345678901234567890123456789012345678"
+        );
+
+        assert!(parse_token_amount("0.0000000000000000001").is_err());
+        assert!(parse_token_amount("1.5 atto").is_err());
+        assert!(parse_token_amount("-1").is_err());
+        assert!(parse_token_amount("1 FIL").is_err());
+        assert!(parse_token_amount("").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{thread_rng, Rng};
+use tokio::runtime::Runtime;
+
+use recall_sdk::machine::{
+    bucket::{AddOptions, Bucket, GetOptions, QueryOptions},
+    Machine,
+};
+use recall_signer::Signer;
+
+mod common;
+
+/// Object sizes used for add and get benchmarks.
+const OBJECT_SIZES: [usize; 4] = [1024, 64 * 1024, 1024 * 1024, 16 * 1024 * 1024];
+/// Number of objects added for the listing benchmark.
+const LIST_OBJECTS: usize = 250;
+/// Page sizes used for the listing benchmark.
+const LIST_PAGE_SIZES: [u64; 3] = [10, 50, 250];
+
+fn random_data(size: usize) -> Vec<u8> {
+    let mut data = vec![0; size];
+    thread_rng().fill(&mut data[..]);
+    data
+}
+
+fn bench_add_get(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let (provider, mut signer) = rt.block_on(common::setup());
+    let (machine, _) = rt
+        .block_on(Bucket::new(
+            &provider,
+            &mut signer,
+            None,
+            HashMap::new(),
+            Default::default(),
+        ))
+        .expect("failed to create bucket");
+    let from = signer.address();
+
+    let mut group = c.benchmark_group("bucket/add");
+    group.sample_size(10);
+    for size in OBJECT_SIZES {
+        let data = random_data(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            b.to_async(&rt).iter(|| {
+                let mut signer = signer.clone();
+                let reader = Cursor::new(data.clone());
+                let machine = &machine;
+                let provider = &provider;
+                async move {
+                    machine
+                        .add_reader(
+                            provider,
+                            &mut signer,
+                            from,
+                            &format!("bench/add/{}", size),
+                            reader,
+                            size as u64,
+                            AddOptions {
+                                overwrite: true,
+                                ..Default::default()
+                            },
+                        )
+                        .await
+                        .expect("failed to add object")
+                }
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("bucket/get");
+    group.sample_size(10);
+    for size in OBJECT_SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.to_async(&rt).iter(|| async {
+                machine
+                    .get(
+                        &provider,
+                        &format!("bench/add/{}", size),
+                        tokio::io::sink(),
+                        GetOptions::default(),
+                    )
+                    .await
+                    .expect("failed to get object")
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_list(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let (provider, mut signer) = rt.block_on(common::setup());
+    let (machine, _) = rt
+        .block_on(Bucket::new(
+            &provider,
+            &mut signer,
+            None,
+            HashMap::new(),
+            Default::default(),
+        ))
+        .expect("failed to create bucket");
+    let from = signer.address();
+
+    rt.block_on(async {
+        for i in 0..LIST_OBJECTS {
+            let data = random_data(64);
+            machine
+                .add_reader(
+                    &provider,
+                    &mut signer,
+                    from,
+                    &format!("bench/list/{:05}", i),
+                    Cursor::new(data),
+                    64,
+                    AddOptions {
+                        overwrite: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .expect("failed to add object");
+        }
+    });
+
+    let mut group = c.benchmark_group("bucket/list");
+    group.sample_size(10);
+    for limit in LIST_PAGE_SIZES {
+        group.bench_function(BenchmarkId::from_parameter(limit), |b| {
+            b.to_async(&rt).iter(|| async {
+                let mut start_key = None;
+                loop {
+                    let list = machine
+                        .query(
+                            &provider,
+                            QueryOptions {
+                                prefix: "bench/list/".into(),
+                                start_key,
+                                limit,
+                                ..Default::default()
+                            },
+                        )
+                        .await
+                        .expect("failed to query objects");
+                    match list.next_key {
+                        Some(key) => start_key = Some(key),
+                        None => break,
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_add_get, bench_list);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::env;
+
+use recall_provider::json_rpc::JsonRpcProvider;
+use recall_sdk::network::{Network, NetworkConfig};
+use recall_signer::{key::parse_secret_key, AccountKind, Wallet};
+
+pub fn get_network() -> NetworkConfig {
+    let net_name = env::var("TEST_TARGET_NETWORK").unwrap_or("localnet".into());
+    let network = match net_name.as_str() {
+        "localnet" => Network::Localnet,
+        "testnet" => Network::Testnet,
+        "devnet" => Network::Devnet,
+        _ => panic!("cannot get bench target network config"),
+    };
+    network.init();
+    network.get_config()
+}
+
+#[allow(dead_code)]
+pub async fn setup() -> (JsonRpcProvider, Wallet) {
+    let cfg = get_network();
+    let sk = match env::var("RECALL_PRIVATE_KEY") {
+        Ok(sk) => parse_secret_key(&sk).expect("invalid secret key"),
+        Err(e) => panic!("cannot get secret key for bench runner {}", e),
+    };
+    let provider = JsonRpcProvider::new_http(
+        cfg.rpc_url,
+        cfg.subnet_id.chain_id(),
+        None,
+        Some(cfg.object_api_url),
+    )
+    .expect("failed to create provider");
+    let mut signer = Wallet::new_secp256k1(sk, AccountKind::Ethereum, cfg.subnet_id)
+        .expect("failed to create wallet");
+    signer
+        .init_sequence(&provider)
+        .await
+        .expect("failed to init sequence");
+    (provider, signer)
+}
@@ -0,0 +1,51 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::str::FromStr;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use recall_provider::{
+    fvm_shared::{address::Address, econ::TokenAmount},
+    message::{GasParams, Message, RawBytes},
+};
+use recall_signer::{key::random_secretkey, AccountKind, Signer, SubnetID, Wallet};
+
+mod common;
+
+fn bench_sign(c: &mut Criterion) {
+    let cfg = common::get_network();
+    let signer = Wallet::new_secp256k1(random_secretkey(), AccountKind::Ethereum, cfg.subnet_id)
+        .expect("failed to create wallet");
+    let gas_params = GasParams::default();
+    let message = Message {
+        version: Default::default(),
+        from: signer.address(),
+        to: Address::new_id(1000),
+        sequence: 0,
+        value: TokenAmount::from_atto(1),
+        method_num: 0,
+        params: RawBytes::new(vec![0; 256]),
+        gas_limit: 10_000_000,
+        gas_fee_cap: gas_params.gas_fee_cap,
+        gas_premium: gas_params.gas_premium,
+    };
+
+    let mut group = c.benchmark_group("signer");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("sign_message", |b| {
+        b.iter(|| signer.sign_message(message.clone()).unwrap())
+    });
+    let signed = signer.sign_message(message.clone()).unwrap();
+    group.bench_function("verify_message", |b| {
+        b.iter(|| signer.verify_message(&message, &signed.signature).unwrap())
+    });
+    group.finish();
+
+    // Faux subnet IDs hash to a chain ID on every call; make sure that stays cheap.
+    let subnet_id = SubnetID::from_str("test").unwrap();
+    c.bench_function("subnet_id/chain_id", |b| b.iter(|| subnet_id.chain_id()));
+}
+
+criterion_group!(benches, bench_sign);
+criterion_main!(benches);
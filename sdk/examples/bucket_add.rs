@@ -16,6 +16,7 @@ use recall_sdk::{
         Machine,
     },
     network::Network,
+    range::Range,
 };
 use recall_signer::{key::parse_secret_key, AccountKind, Signer, Wallet};
 
@@ -98,13 +99,17 @@ async fn main() -> anyhow::Result<()> {
         let key = core::str::from_utf8(&key_bytes).unwrap_or_default();
         println!("Query result for key {}: {}", key, object.hash);
     }
+    for prefix_bytes in list.common_prefixes {
+        let prefix = core::str::from_utf8(&prefix_bytes).unwrap_or_default();
+        println!("Query result for common prefix {}", prefix);
+    }
 
     // Download the actual object at `foo/my_file`
     let obj_file = async_tempfile::TempFile::new().await?;
     let obj_path = obj_file.file_path().to_owned();
     println!("Downloading object to {}", obj_path.display());
     let options = GetOptions {
-        range: Some("0-99".to_string()), // Get the first 100 bytes
+        range: Some(Range::Bounded(0, 99)), // Get the first 100 bytes
         ..Default::default()
     };
     {
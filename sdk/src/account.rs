@@ -10,13 +10,14 @@ pub use fendermint_actor_blobs_shared::state::TtlStatus;
 use recall_provider::{
     fvm_ipld_encoding::RawBytes,
     fvm_shared::{address::Address, econ::TokenAmount},
+    json_rpc::JsonRpcProvider,
     message::GasParams,
     query::{FvmQueryHeight, QueryProvider},
     response::decode_empty,
     tx::{BroadcastMode, TxResult},
     Client, Provider,
 };
-use recall_signer::{Signer, SubnetID};
+use recall_signer::{Signer, SubnetID, Wallet};
 
 pub use crate::ipc::{manager::EvmManager, subnet::EVMSubnet};
 pub use ethers::prelude::TransactionReceipt;
@@ -45,6 +46,22 @@ pub struct SetStatusOptions {
 pub struct Account {}
 
 impl Account {
+    /// Prepares a provider and a signer ahead of the first transaction, so it doesn't absorb
+    /// connection setup and sequence lookup latency.
+    ///
+    /// This warms up the provider's connections (see [`JsonRpcProvider::warm_up`]) and syncs
+    /// the signer's sequence with its on-chain state.
+    pub async fn warm_up<C>(
+        provider: &JsonRpcProvider<C>,
+        signer: &mut Wallet,
+    ) -> anyhow::Result<()>
+    where
+        C: Client + Send + Sync,
+    {
+        provider.warm_up().await?;
+        signer.init_sequence(provider).await
+    }
+
     /// Get the sequence (nonce) for a [`Signer`] at the given height.
     pub async fn sequence(
         provider: &impl QueryProvider,
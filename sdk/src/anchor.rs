@@ -0,0 +1,217 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Anchoring of timehub roots to a parent chain.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use ethers::prelude::TransactionReceipt;
+use futures::stream::{self, Stream};
+
+use recall_provider::{
+    fvm_ipld_encoding,
+    fvm_shared::address::Address,
+    query::{FvmQueryHeight, QueryProvider},
+};
+use recall_signer::Signer;
+
+use crate::{
+    clock::{clock_or_system, Clock},
+    ipc::{manager::EvmManager, subnet::EVMSubnet},
+    machine::{
+        timehub::{RootAt, Timehub},
+        Machine,
+    },
+};
+
+/// Prefix of the calldata of an anchor transaction, so anchors can be told apart from other
+/// transactions.
+pub const ANCHOR_DOMAIN: &[u8] = b"recall-timehub-anchor-v1:";
+
+/// Default time between root checks when anchoring continuously.
+const DEFAULT_ANCHOR_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Root anchoring options.
+#[derive(Clone, Debug)]
+pub struct AnchorOptions {
+    /// The parent chain account the anchor transactions are sent to.
+    /// If not specified, they're sent to the signer itself, which needs no contract.
+    pub to: Option<Address>,
+    /// Time between root checks when anchoring continuously (see [`Anchor::run`]).
+    pub interval: Duration,
+    /// Query block height for the root.
+    pub height: FvmQueryHeight,
+    /// Optional clock that the interval is timed with.
+    /// If not specified, the system clock is used.
+    pub clock: Option<Arc<dyn Clock>>,
+}
+
+impl Default for AnchorOptions {
+    fn default() -> Self {
+        AnchorOptions {
+            to: None,
+            interval: DEFAULT_ANCHOR_INTERVAL,
+            height: FvmQueryHeight::Committed,
+            clock: None,
+        }
+    }
+}
+
+/// A timehub root posted to the parent chain.
+#[derive(Clone, Debug)]
+pub struct RootAnchor {
+    /// The root and the child chain height it was read at.
+    pub root: RootAt,
+    /// The receipt of the parent chain transaction that carries the root.
+    pub receipt: TransactionReceipt,
+}
+
+/// A static wrapper around anchoring timehub roots to a parent chain.
+///
+/// An anchor is a parent chain transaction whose calldata is [`ANCHOR_DOMAIN`] followed by
+/// the CBOR encoding of the tuple `(timehub address, child chain height, root CID)`. Once
+/// the transaction is included, the parent block timestamps every leaf under the root,
+/// without trusting the child chain's block times.
+///
+/// Anchors aren't posted through the parent's `SubnetManager` contract, which has no method
+/// for recording arbitrary data. They're plain transactions to [`AnchorOptions::to`], or to the
+/// signer itself, so verifying an anchor means finding the transaction on the parent chain
+/// and decoding its calldata with [`Anchor::decode`].
+pub struct Anchor {}
+
+impl Anchor {
+    /// Read a timehub root and post it to the parent chain.
+    ///
+    /// The signer must be configured for the parent subnet, as for
+    /// [`Account::deposit`](crate::account::Account::deposit).
+    pub async fn post(
+        provider: &impl QueryProvider,
+        signer: &impl Signer,
+        timehub: &Timehub,
+        parent: EVMSubnet,
+        options: AnchorOptions,
+    ) -> anyhow::Result<RootAnchor> {
+        let root = timehub.root_at(provider, options.height).await?;
+        Self::post_root(signer, timehub, parent, root, options.to).await
+    }
+
+    /// Returns a stream that posts the timehub root to the parent chain every
+    /// `options.interval`, yielding each anchor.
+    ///
+    /// A root is only posted if it changed since the last anchor, so an idle timehub doesn't
+    /// spend parent chain gas. Errors reading or posting a root, e.g., from a transient RPC
+    /// failure, are yielded, and anchoring carries on at the next interval, retrying a root
+    /// that failed to post. The stream never ends on its own; drop it to stop anchoring.
+    pub fn run<'a>(
+        provider: &'a impl QueryProvider,
+        signer: &'a impl Signer,
+        timehub: &'a Timehub,
+        parent: EVMSubnet,
+        options: AnchorOptions,
+    ) -> impl Stream<Item = anyhow::Result<RootAnchor>> + 'a {
+        let clock = clock_or_system(&options.clock);
+        // The state is the last anchored root and whether to wait before the next check
+        stream::unfold(
+            (None, false),
+            move |(last, wait): (Option<cid::Cid>, bool)| {
+                let (parent, options, clock) = (parent.clone(), options.clone(), clock.clone());
+                async move {
+                    if wait {
+                        clock.sleep(options.interval).await;
+                    }
+                    loop {
+                        let root = match timehub.root_at(provider, options.height).await {
+                            Ok(root) => root,
+                            Err(e) => return Some((Err(e), (last, true))),
+                        };
+                        if last == Some(root.root.0) {
+                            clock.sleep(options.interval).await;
+                            continue;
+                        }
+                        let cid = root.root.0;
+                        let result =
+                            Self::post_root(signer, timehub, parent, root, options.to).await;
+                        let last = if result.is_ok() { Some(cid) } else { last };
+                        return Some((result, (last, true)));
+                    }
+                }
+            },
+        )
+    }
+
+    /// Returns the calldata that anchors a timehub root.
+    pub fn encode(timehub: Address, root: &RootAt) -> anyhow::Result<Vec<u8>> {
+        let mut data = ANCHOR_DOMAIN.to_vec();
+        data.extend(fvm_ipld_encoding::to_vec(&(
+            timehub,
+            root.height,
+            root.root.0,
+        ))?);
+        Ok(data)
+    }
+
+    /// Decodes the timehub address and root from the calldata of an anchor transaction.
+    pub fn decode(data: &[u8]) -> anyhow::Result<(Address, RootAt)> {
+        let payload = data
+            .strip_prefix(ANCHOR_DOMAIN)
+            .ok_or_else(|| anyhow!("calldata isn't a timehub anchor"))?;
+        let (timehub, height, root): (Address, u64, cid::Cid) =
+            fvm_ipld_encoding::from_slice(payload)
+                .map_err(|e| anyhow!("error parsing timehub anchor: {e}"))?;
+        Ok((
+            timehub,
+            RootAt {
+                root: root.into(),
+                height,
+            },
+        ))
+    }
+
+    /// Posts a root to the parent chain, to `to` or the signer itself.
+    async fn post_root(
+        signer: &impl Signer,
+        timehub: &Timehub,
+        parent: EVMSubnet,
+        root: RootAt,
+        to: Option<Address>,
+    ) -> anyhow::Result<RootAnchor> {
+        let data = Self::encode(timehub.address(), &root)?;
+        let to = to.unwrap_or_else(|| signer.address());
+        let receipt = EvmManager::send_data(signer, to, parent, data).await?;
+        tracing::debug!(
+            timehub = %timehub.address(),
+            height = root.height,
+            tx = ?receipt.transaction_hash,
+            "anchored timehub root"
+        );
+        Ok(RootAnchor { root, receipt })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cid::multihash::Multihash;
+
+    use super::*;
+
+    #[test]
+    fn test_anchor_encoding() {
+        let timehub = Address::new_id(1000);
+        let hash = Multihash::wrap(0x1e, &[7; 32]).unwrap();
+        let root = RootAt {
+            root: cid::Cid::new_v1(0x71, hash).into(),
+            height: 8761,
+        };
+        let data = Anchor::encode(timehub, &root).unwrap();
+        assert!(data.starts_with(ANCHOR_DOMAIN));
+
+        let (decoded_timehub, decoded) = Anchor::decode(&data).unwrap();
+        assert_eq!(decoded_timehub, timehub);
+        assert_eq!(decoded.root.0, root.root.0);
+        assert_eq!(decoded.height, root.height);
+
+        assert!(Anchor::decode(&data[1..]).is_err());
+    }
+}
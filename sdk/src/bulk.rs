@@ -0,0 +1,239 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use serde::Serialize;
+
+use recall_provider::{
+    endpoint::ObjectApiUnavailableError, error::ErrorCode, tx::TxResult, util::NetworkMismatchError,
+};
+
+use crate::{
+    cost::InsufficientResourceError,
+    key_policy::InvalidKeyError,
+    machine::bucket::{display_key, ImmutableObjectError, IntegrityError, PreconditionFailedError},
+    quota::QuotaExceededError,
+    root_cache::RootEquivocationError,
+};
+
+/// Error messages that indicate a failure unrelated to the operation itself.
+const TRANSIENT_ERROR_PATTERNS: &[&str] = &[
+    "timed out",
+    "timeout",
+    "connection",
+    "temporarily unavailable",
+    "mempool is full",
+    "sequence mismatch",
+    "invalid nonce",
+];
+
+/// The class of error that failed a bulk item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorClass {
+    /// A network, node, or sequence error. The item can be requeued as is.
+    Transient,
+    /// The operation was rejected, e.g., by the actor or a quota.
+    /// Requeuing the item won't help without changing it.
+    Rejected,
+}
+
+impl ErrorClass {
+    /// Classify an error.
+    pub fn of(err: &anyhow::Error) -> Self {
+        if err.downcast_ref::<QuotaExceededError>().is_some() {
+            return Self::Rejected;
+        }
+        if err.downcast_ref::<ObjectApiUnavailableError>().is_some() {
+            return Self::Transient;
+        }
+        if let Some(e) = err.downcast_ref::<reqwest::Error>() {
+            if e.is_timeout() || e.is_connect() {
+                return Self::Transient;
+            }
+        }
+        let message = format!("{:#}", err).to_lowercase();
+        if TRANSIENT_ERROR_PATTERNS
+            .iter()
+            .any(|pattern| message.contains(pattern))
+        {
+            Self::Transient
+        } else {
+            Self::Rejected
+        }
+    }
+}
+
+/// Returns the stable code of the first error in `err`'s chain that has one
+/// (see [`ErrorCode`]).
+pub fn error_code(err: &anyhow::Error) -> Option<&'static str> {
+    err.chain().find_map(|cause| {
+        if let Some(e) = cause.downcast_ref::<QuotaExceededError>() {
+            Some(e.code())
+        } else if let Some(e) = cause.downcast_ref::<IntegrityError>() {
+            Some(e.code())
+        } else if let Some(e) = cause.downcast_ref::<ObjectApiUnavailableError>() {
+            Some(e.code())
+        } else if let Some(e) = cause.downcast_ref::<InsufficientResourceError>() {
+            Some(e.code())
+        } else if let Some(e) = cause.downcast_ref::<PreconditionFailedError>() {
+            Some(e.code())
+        } else if let Some(e) = cause.downcast_ref::<InvalidKeyError>() {
+            Some(e.code())
+        } else if let Some(e) = cause.downcast_ref::<ImmutableObjectError>() {
+            Some(e.code())
+        } else if let Some(e) = cause.downcast_ref::<RootEquivocationError>() {
+            Some(e.code())
+        } else {
+            cause
+                .downcast_ref::<NetworkMismatchError>()
+                .map(|e| e.code())
+        }
+    })
+}
+
+/// The outcome of one item in a bulk operation.
+#[derive(Clone, Debug, Serialize)]
+pub struct BulkItemResult {
+    /// The object key, escaped if it isn't valid UTF-8 (see [`display_key`]).
+    pub key: String,
+    /// The number of attempts made.
+    pub attempts: u32,
+    /// The transaction hash, if the item succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    /// The final error, if the item failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The class of the final error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_class: Option<ErrorClass>,
+    /// The stable code of the final error, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<&'static str>,
+}
+
+impl BulkItemResult {
+    /// Returns whether the item succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Per-item results of a bulk operation.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BulkReport {
+    /// Item results in the order the items were processed.
+    pub items: Vec<BulkItemResult>,
+}
+
+impl BulkReport {
+    /// Returns the items that succeeded.
+    pub fn succeeded(&self) -> impl Iterator<Item = &BulkItemResult> {
+        self.items.iter().filter(|item| item.is_ok())
+    }
+
+    /// Returns the items that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &BulkItemResult> {
+        self.items.iter().filter(|item| !item.is_ok())
+    }
+
+    /// Returns the keys of items that failed with a transient error and can be requeued.
+    pub fn requeue(&self) -> Vec<&str> {
+        self.failed()
+            .filter(|item| item.error_class == Some(ErrorClass::Transient))
+            .map(|item| item.key.as_str())
+            .collect()
+    }
+}
+
+/// Tracks the attempts of a bulk item, retrying transient errors up to `retries` times.
+///
+/// Callers loop on the operation until [`ItemAttempts::record`] returns a result.
+pub(crate) struct ItemAttempts<'a> {
+    key: &'a [u8],
+    retries: u32,
+    attempts: u32,
+}
+
+impl<'a> ItemAttempts<'a> {
+    pub(crate) fn new(key: &'a [u8], retries: u32) -> Self {
+        Self {
+            key,
+            retries,
+            attempts: 0,
+        }
+    }
+
+    /// Record the result of an attempt, returning the item result if it's final.
+    pub(crate) fn record<T>(
+        &mut self,
+        result: anyhow::Result<TxResult<T>>,
+    ) -> Option<BulkItemResult> {
+        self.attempts += 1;
+        let (error, error_class, error_code) = match result {
+            Ok(tx) => {
+                return Some(BulkItemResult {
+                    key: display_key(self.key),
+                    attempts: self.attempts,
+                    tx_hash: Some(format!("{:#x}", tx.hash())),
+                    error: None,
+                    error_class: None,
+                    error_code: None,
+                })
+            }
+            Err(e) => (e.to_string(), ErrorClass::of(&e), error_code(&e)),
+        };
+        if error_class == ErrorClass::Transient && self.attempts <= self.retries {
+            tracing::debug!(
+                key = %display_key(self.key),
+                attempts = self.attempts,
+                error = %error,
+                "retrying bulk item"
+            );
+            return None;
+        }
+        Some(BulkItemResult {
+            key: display_key(self.key),
+            attempts: self.attempts,
+            tx_hash: None,
+            error: Some(error),
+            error_class: Some(error_class),
+            error_code,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use recall_provider::fvm_shared::address::Network;
+
+    use super::*;
+
+    #[test]
+    fn test_error_class() {
+        assert_eq!(
+            ErrorClass::of(&anyhow!("broadcast failed: connection reset")),
+            ErrorClass::Transient
+        );
+        assert_eq!(
+            ErrorClass::of(&anyhow!("actor error: object not found")),
+            ErrorClass::Rejected
+        );
+    }
+
+    #[test]
+    fn test_error_code() {
+        let err = anyhow::Error::new(NetworkMismatchError {
+            expected: Network::Mainnet,
+            actual: Network::Testnet,
+        })
+        .context("invalid address");
+        assert_eq!(error_code(&err), Some("RECALL-E-NET-001"));
+        assert!(err
+            .root_cause()
+            .to_string()
+            .starts_with("RECALL-E-NET-001: "));
+        assert_eq!(error_code(&anyhow!("object not found")), None);
+    }
+}
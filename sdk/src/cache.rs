@@ -0,0 +1,230 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use recall_provider::fvm_shared::address::Address;
+
+/// Prefix of files that are still being written into the cache directory.
+const TEMP_FILE_PREFIX: &str = ".tmp-";
+
+/// A size-bounded, on-disk cache of object contents keyed by content hash.
+///
+/// Entries are evicted least recently used first once the total size exceeds the limit.
+/// The cache also remembers which hash each bucket key resolved to, and drops the old
+/// contents when a key is found to point at a new hash.
+/// Cloning the cache is cheap, and clones share the same entries.
+#[derive(Clone)]
+pub struct ObjectCache {
+    dir: PathBuf,
+    max_size: u64,
+    state: Arc<Mutex<CacheState>>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    keys: HashMap<(Address, Vec<u8>), String>,
+    total_size: u64,
+    clock: u64,
+}
+
+struct CacheEntry {
+    size: u64,
+    last_used: u64,
+}
+
+impl fmt::Debug for ObjectCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectCache")
+            .field("dir", &self.dir)
+            .field("max_size", &self.max_size)
+            .finish()
+    }
+}
+
+impl ObjectCache {
+    /// Open a cache in `dir` holding at most `max_size` bytes, creating the directory if needed.
+    ///
+    /// Entries left by a previous process are kept, ordered by their modification time.
+    pub fn open(dir: impl AsRef<Path>, max_size: u64) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+
+        let mut existing = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with(TEMP_FILE_PREFIX) {
+                // Left over from an interrupted download
+                let _ = std::fs::remove_file(entry.path());
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                existing.push((metadata.modified()?, name, metadata.len()));
+            }
+        }
+        existing.sort();
+
+        let mut state = CacheState::default();
+        for (_, hash, size) in existing {
+            state.clock += 1;
+            state.total_size += size;
+            state.entries.insert(
+                hash,
+                CacheEntry {
+                    size,
+                    last_used: state.clock,
+                },
+            );
+        }
+        let cache = Self {
+            dir,
+            max_size,
+            state: Arc::new(Mutex::new(state)),
+        };
+        cache.evict(&mut cache.lock(), None);
+        Ok(cache)
+    }
+
+    /// Returns the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the total size of the cached contents in bytes.
+    pub fn size(&self) -> u64 {
+        self.lock().total_size
+    }
+
+    /// Record that `key` in the bucket at `address` currently resolves to `hash`.
+    ///
+    /// If the key previously resolved to a different hash that no other key uses,
+    /// the stale contents are removed.
+    pub(crate) fn track(&self, address: Address, key: &[u8], hash: &str) {
+        let mut state = self.lock();
+        let previous = state.keys.insert((address, key.to_vec()), hash.to_string());
+        if let Some(previous) = previous.filter(|p| p != hash) {
+            if !state.keys.values().any(|h| *h == previous) {
+                self.remove(&mut state, &previous);
+            }
+        }
+    }
+
+    /// Open the cached contents for `hash`, if present.
+    pub(crate) fn open_entry(&self, hash: &str) -> Option<std::fs::File> {
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        let entry = state.entries.get_mut(hash)?;
+        entry.last_used = clock;
+        match std::fs::File::open(self.dir.join(hash)) {
+            Ok(file) => Some(file),
+            Err(_) => {
+                // The file was removed outside the cache
+                self.remove(&mut state, hash);
+                None
+            }
+        }
+    }
+
+    /// Returns a new path for writing contents that will be inserted with [`Self::insert`].
+    pub(crate) fn temp_path(&self) -> PathBuf {
+        self.dir.join(format!(
+            "{}{:016x}",
+            TEMP_FILE_PREFIX,
+            rand::random::<u64>()
+        ))
+    }
+
+    /// Move the file at `temp_path` into the cache as the contents for `hash`,
+    /// evicting older entries as needed.
+    pub(crate) fn insert(&self, hash: &str, temp_path: &Path) -> anyhow::Result<()> {
+        let size = std::fs::metadata(temp_path)?.len();
+        let mut state = self.lock();
+        std::fs::rename(temp_path, self.dir.join(hash))?;
+        state.clock += 1;
+        let entry = CacheEntry {
+            size,
+            last_used: state.clock,
+        };
+        if let Some(old) = state.entries.insert(hash.to_string(), entry) {
+            state.total_size -= old.size;
+        }
+        state.total_size += size;
+        self.evict(&mut state, Some(hash));
+        if size > self.max_size {
+            // Too large to ever fit; open handles can still read it
+            self.remove(&mut state, hash);
+        }
+        Ok(())
+    }
+
+    fn evict(&self, state: &mut CacheState, keep: Option<&str>) {
+        while state.total_size > self.max_size {
+            let oldest = state
+                .entries
+                .iter()
+                .filter(|(hash, _)| Some(hash.as_str()) != keep)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(hash, _)| hash.clone());
+            match oldest {
+                Some(hash) => self.remove(state, &hash),
+                None => return,
+            }
+        }
+    }
+
+    fn remove(&self, state: &mut CacheState, hash: &str) {
+        if let Some(entry) = state.entries.remove(hash) {
+            state.total_size -= entry.size;
+            let _ = std::fs::remove_file(self.dir.join(hash));
+            tracing::debug!(hash, size = entry.size, "evicted cached object");
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(cache: &ObjectCache, hash: &str, len: usize) {
+        let path = cache.temp_path();
+        std::fs::write(&path, vec![0; len]).unwrap();
+        cache.insert(hash, &path).unwrap();
+    }
+
+    #[test]
+    fn test_object_cache_eviction() {
+        let dir = std::env::temp_dir().join(format!("recall-cache-{}", rand::random::<u64>()));
+        let cache = ObjectCache::open(&dir, 10).unwrap();
+        insert(&cache, "a", 4);
+        insert(&cache, "b", 4);
+        assert!(cache.open_entry("a").is_some());
+        insert(&cache, "c", 4);
+        // "b" is the least recently used
+        assert!(cache.open_entry("b").is_none());
+        assert!(cache.open_entry("a").is_some());
+        assert_eq!(cache.size(), 8);
+
+        // A key moving to new contents drops the old contents
+        let address = Address::new_id(1);
+        cache.track(address, b"key", "a");
+        cache.track(address, b"key", "c");
+        assert!(cache.open_entry("a").is_none());
+        assert!(cache.open_entry("c").is_some());
+
+        // Entries survive reopening
+        let cache = ObjectCache::open(&dir, 10).unwrap();
+        assert_eq!(cache.size(), 4);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
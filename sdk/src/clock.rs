@@ -0,0 +1,18 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+pub(crate) use recall_provider::clock::{new_backoff_policy, retry};
+pub use recall_provider::clock::{Clock, ManualClock, Sleep, SystemClock};
+
+/// Returns the given clock, or the [`SystemClock`] if none is given.
+pub(crate) fn clock_or_system(clock: &Option<Arc<dyn Clock>>) -> Arc<dyn Clock> {
+    clock.clone().unwrap_or_else(|| Arc::new(SystemClock))
+}
+
+/// Returns the clock's wall-clock time in seconds since the Unix epoch.
+pub(crate) fn unix_seconds(clock: &dyn Clock) -> anyhow::Result<u64> {
+    Ok(clock.system_time().duration_since(UNIX_EPOCH)?.as_secs())
+}
@@ -0,0 +1,135 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use async_compression::tokio::{
+    bufread::{self, GzipEncoder, ZstdEncoder},
+    write::{GzipDecoder, ZstdDecoder},
+};
+use async_tempfile::TempFile;
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, BufReader};
+
+use crate::dedup::spool_and_hash;
+
+/// Object metadata key that records the codec an object was compressed with.
+pub const COMPRESSION_METADATA_KEY: &str = "compression";
+
+/// A codec for compressing objects before they're uploaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Gzip, widely supported by other tools.
+    Gzip,
+    /// Zstandard, faster and with better ratios than gzip.
+    Zstd,
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gzip => write!(f, "gzip"),
+            Self::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(anyhow!(
+                "unsupported compression codec '{}'; expected gzip or zstd",
+                s
+            )),
+        }
+    }
+}
+
+impl Compression {
+    /// Returns the codec recorded in object metadata, if any.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> anyhow::Result<Option<Self>> {
+        metadata
+            .get(COMPRESSION_METADATA_KEY)
+            .map(|codec| codec.parse())
+            .transpose()
+    }
+
+    /// Compress the reader's content into a temporary file, returning the file,
+    /// the BLAKE3 hash of the compressed content, and the compressed size.
+    pub(crate) async fn compress<R>(self, reader: R) -> anyhow::Result<(TempFile, [u8; 32], u64)>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        let reader = BufReader::new(reader);
+        let encoder: Pin<Box<dyn AsyncRead + Send + '_>> = match self {
+            Self::Gzip => Box::pin(GzipEncoder::new(reader)),
+            Self::Zstd => Box::pin(ZstdEncoder::new(reader)),
+        };
+        let (file, hash) = spool_and_hash(encoder).await?;
+        let size = file.metadata().await?.len();
+        Ok((file, hash, size))
+    }
+
+    /// Wrap a writer so that compressed content written to it is decompressed.
+    /// The returned writer must be shut down to write the end of the content.
+    pub(crate) fn decoder<'a, W>(self, writer: W) -> Pin<Box<dyn AsyncWrite + Send + 'a>>
+    where
+        W: AsyncWrite + Unpin + Send + 'a,
+    {
+        match self {
+            Self::Gzip => Box::pin(GzipDecoder::new(writer)),
+            Self::Zstd => Box::pin(ZstdDecoder::new(writer)),
+        }
+    }
+
+    /// Wrap a reader of compressed content so that reading from it yields decompressed content.
+    pub(crate) fn reader<'a, R>(self, reader: R) -> Pin<Box<dyn AsyncRead + Send + 'a>>
+    where
+        R: AsyncBufRead + Unpin + Send + 'a,
+    {
+        match self {
+            Self::Gzip => Box::pin(bufread::GzipDecoder::new(reader)),
+            Self::Zstd => Box::pin(bufread::ZstdDecoder::new(reader)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_compression_round_trip() {
+        let content = b"{\"level\":\"info\",\"msg\":\"hello\"}\n".repeat(1000);
+        for codec in [Compression::Gzip, Compression::Zstd] {
+            let (mut file, _, size) = codec.compress(&content[..]).await.unwrap();
+            assert!(size < content.len() as u64);
+
+            let mut compressed = Vec::new();
+            file.read_to_end(&mut compressed).await.unwrap();
+            let mut decompressed = Vec::new();
+            let mut decoder = codec.decoder(&mut decompressed);
+            decoder.write_all(&compressed).await.unwrap();
+            decoder.shutdown().await.unwrap();
+            drop(decoder);
+            assert_eq!(decompressed, content);
+
+            let mut decompressed = Vec::new();
+            codec
+                .reader(&compressed[..])
+                .read_to_end(&mut decompressed)
+                .await
+                .unwrap();
+            assert_eq!(decompressed, content);
+        }
+    }
+}
@@ -0,0 +1,375 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt;
+
+use anyhow::anyhow;
+use fendermint_actor_blobs_shared::params::GetAccountParams;
+use fendermint_actor_blobs_shared::state::{Account, Hash, PublicKey};
+use fendermint_actor_blobs_shared::Method::GetAccount;
+use fendermint_actor_bucket::{AddParams, Method::AddObject};
+use fendermint_vm_actor_interface::blobs::BLOBS_ACTOR_ADDR;
+use serde::Serialize;
+use tendermint::abci::response::DeliverTx;
+
+use recall_provider::{
+    error::{self, ErrorCode},
+    fvm_ipld_encoding::{self, RawBytes},
+    fvm_shared::{
+        address::Address, bigint::BigInt, clock::ChainEpoch, econ::TokenAmount, MethodNum,
+    },
+    message::{local_message, GasParams, Message},
+    query::{FvmQueryHeight, QueryProvider},
+    response::decode_bytes,
+    tx::{TxEstimate, TxResult, TxStatus},
+};
+
+use crate::{
+    credits::{Credit, Credits},
+    machine::{bucket::Bucket, Machine},
+    storage::Storage,
+    subnet::Subnet,
+};
+
+/// Options for estimating the cost of adding an object.
+#[derive(Clone, Default, Debug)]
+pub struct EstimateOptions {
+    /// Object time-to-live (TTL) duration.
+    /// If not set, the subnet's default TTL is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Gas params the transaction will be sent with.
+    /// If the gas limit is zero, it's estimated by the node.
+    pub gas_params: GasParams,
+    /// Query block height.
+    pub height: FvmQueryHeight,
+}
+
+/// The estimated cost of storing an object, before the transaction is sent.
+#[derive(Clone, Debug, Serialize)]
+pub struct CostEstimate {
+    /// The object size in bytes.
+    pub size: u64,
+    /// The TTL the object is stored for.
+    pub ttl: ChainEpoch,
+    /// Credits committed to storing the object for its TTL.
+    pub credits: String,
+    /// The token cost of the credits at the current token to credit rate.
+    pub tokens: String,
+    /// The transaction gas limit.
+    pub gas_limit: u64,
+    /// The maximum gas fee, i.e., the gas limit at the gas fee cap.
+    pub max_gas_fee: String,
+}
+
+impl From<CostEstimate> for TxEstimate {
+    fn from(estimate: CostEstimate) -> Self {
+        TxEstimate {
+            gas_limit: estimate.gas_limit,
+            max_gas_fee: estimate.max_gas_fee,
+            credits: Some(estimate.credits),
+            tokens: Some(estimate.tokens),
+        }
+    }
+}
+
+/// The actual gas cost of a committed transaction, taken from its receipt.
+#[derive(Clone, Debug, Serialize)]
+pub struct ActualCost {
+    /// Gas used by the transaction.
+    pub gas_used: String,
+    /// The effective price paid per unit of gas.
+    pub gas_price: String,
+    /// The total gas fee paid.
+    pub gas_fee: String,
+}
+
+impl ActualCost {
+    /// Returns the actual cost of a transaction, or `None` if it isn't committed yet.
+    pub fn from_tx<T>(tx: &TxResult<T>) -> Option<Self> {
+        match &tx.status {
+            TxStatus::Committed(receipt) => {
+                let gas_used = receipt.gas_used.unwrap_or_default();
+                let gas_price = receipt.effective_gas_price.unwrap_or_default();
+                Some(Self {
+                    gas_used: gas_used.to_string(),
+                    gas_price: gas_price.to_string(),
+                    gas_fee: gas_used.saturating_mul(gas_price).to_string(),
+                })
+            }
+            TxStatus::Pending(_) | TxStatus::DryRun(_) => None,
+        }
+    }
+}
+
+/// A transaction result annotated with its estimated and actual cost.
+#[derive(Clone, Debug, Serialize)]
+pub struct CostedTxResult<T>
+where
+    T: 'static,
+{
+    /// The transaction result.
+    pub tx: TxResult<T>,
+    /// The cost estimated before the transaction was sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<CostEstimate>,
+    /// The actual cost, if the transaction is committed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<ActualCost>,
+}
+
+/// The resource an add would run short of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdmissionResource {
+    /// Free storage capacity of the subnet, in bytes.
+    Capacity,
+    /// Free credit of the sending account.
+    Credit,
+}
+
+/// Error returned when an add can't be covered by the subnet's free capacity or the sender's
+/// credit (see [`Cost::check_admission`]).
+///
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InsufficientResourceError {
+    /// The resource that's short.
+    pub resource: AdmissionResource,
+    /// The amount the add requires.
+    pub required: String,
+    /// The amount available.
+    pub available: String,
+    /// The missing amount. For credit, the token cost of the missing credit is included.
+    pub shortfall: String,
+}
+
+impl fmt::Display for InsufficientResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: insufficient {:?}: required {} with {} available (shortfall={})",
+            self.code(),
+            self.resource,
+            self.required,
+            self.available,
+            self.shortfall
+        )
+    }
+}
+
+impl std::error::Error for InsufficientResourceError {}
+
+impl ErrorCode for InsufficientResourceError {
+    fn code(&self) -> &'static str {
+        error::INSUFFICIENT_RESOURCE
+    }
+}
+
+/// A static wrapper around storage cost estimation.
+pub struct Cost {}
+
+impl Cost {
+    /// Estimate the credit and gas cost of adding an object of the given size to a bucket.
+    ///
+    /// Pricing is queried from the subnet config and the blobs actor.
+    /// Gas is estimated with placeholder hashes, so the actual gas used may differ slightly.
+    pub async fn estimate_add(
+        provider: &impl QueryProvider,
+        from: Address,
+        bucket: &Bucket,
+        key: &str,
+        size: u64,
+        options: EstimateOptions,
+    ) -> anyhow::Result<CostEstimate> {
+        let ttl = Self::resolve_ttl(provider, options.ttl, options.height).await?;
+        let (credits, tokens) = Self::storage_cost(provider, size, ttl, options.height).await?;
+
+        let params = AddParams {
+            source: PublicKey([0; 32]),
+            key: key.into(),
+            hash: Hash([0; 32]),
+            recovery_hash: Hash([0; 32]),
+            size,
+            ttl: Some(ttl),
+            metadata: Default::default(),
+            overwrite: true,
+            from,
+        };
+        let gas = Self::estimate_tx(
+            provider,
+            from,
+            bucket.address(),
+            AddObject as u64,
+            RawBytes::serialize(params)?,
+            &options.gas_params,
+            options.height,
+        )
+        .await?;
+
+        Ok(CostEstimate {
+            size,
+            ttl,
+            credits: credits.to_string(),
+            tokens: tokens.to_string(),
+            gas_limit: gas.gas_limit,
+            max_gas_fee: gas.max_gas_fee,
+        })
+    }
+
+    /// Estimate the gas cost of a transaction without sending it.
+    /// If the gas limit in `gas_params` is zero, it's estimated by the node.
+    pub async fn estimate_tx(
+        provider: &impl QueryProvider,
+        from: Address,
+        to: Address,
+        method_num: MethodNum,
+        params: RawBytes,
+        gas_params: &GasParams,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<TxEstimate> {
+        let mut gas_limit = gas_params.gas_limit;
+        if gas_limit == 0 {
+            let message = Message {
+                version: Default::default(),
+                from,
+                to,
+                sequence: 0,
+                value: Default::default(),
+                method_num,
+                params,
+                gas_limit: 0,
+                gas_fee_cap: gas_params.gas_fee_cap.clone(),
+                gas_premium: gas_params.gas_premium.clone(),
+            };
+            gas_limit = provider.estimate_gas_limit(message, height).await?;
+        }
+        Ok(TxEstimate {
+            gas_limit,
+            max_gas_fee: (&gas_params.gas_fee_cap * gas_limit).to_string(),
+            credits: None,
+            tokens: None,
+        })
+    }
+
+    /// Estimate the credit cost of storing `size` bytes for `ttl` epochs, returning the
+    /// credits and their token cost.
+    pub async fn storage_cost(
+        provider: &impl QueryProvider,
+        size: u64,
+        ttl: ChainEpoch,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<(Credit, TokenAmount)> {
+        let stats = Credits::stats(provider, height).await?;
+        let credits = storage_credits(size, ttl);
+        let tokens = &credits / &stats.token_credit_rate;
+        Ok((credits, tokens))
+    }
+
+    /// Check that the subnet has free capacity for `size` bytes and that `from` has enough
+    /// free credit to store them for the TTL, failing with an [`InsufficientResourceError`]
+    /// otherwise. Use this before a large add to fail fast instead of after the upload.
+    ///
+    /// Tokens sent with the add to buy credit inline count toward the credit. The credit check
+    /// is skipped for accounts with a default sponsor, whose approvals aren't checked.
+    pub async fn check_admission(
+        provider: &impl QueryProvider,
+        from: Address,
+        size: u64,
+        ttl: Option<ChainEpoch>,
+        token_amount: Option<&TokenAmount>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<()> {
+        let stats = Storage::stats(provider, height).await?;
+        let capacity_free: u64 = stats
+            .capacity_free
+            .parse()
+            .map_err(|_| anyhow!("invalid free capacity '{}'", stats.capacity_free))?;
+        if size > capacity_free {
+            return Err(InsufficientResourceError {
+                resource: AdmissionResource::Capacity,
+                required: size.to_string(),
+                available: capacity_free.to_string(),
+                shortfall: (size - capacity_free).to_string(),
+            }
+            .into());
+        }
+
+        let params = RawBytes::serialize(GetAccountParams(from))?;
+        let message = local_message(BLOBS_ACTOR_ADDR, GetAccount as u64, params);
+        let account = provider.call(message, height, decode_account).await?.value;
+        let credit_free = match account {
+            Some(account) if account.credit_sponsor.is_some() => return Ok(()),
+            Some(account) => account.credit_free,
+            None => Credit::from_whole(0),
+        };
+        let ttl = Self::resolve_ttl(provider, ttl, height).await?;
+        let required = storage_credits(size, ttl);
+        if credit_free >= required {
+            return Ok(());
+        }
+        let shortfall = required.clone() - credit_free.clone();
+        let rate = Credits::stats(provider, height).await?.token_credit_rate;
+        let tokens = &shortfall / &rate;
+        if token_amount.is_some_and(|amount| *amount >= tokens) {
+            return Ok(());
+        }
+        Err(InsufficientResourceError {
+            resource: AdmissionResource::Credit,
+            required: required.to_string(),
+            available: credit_free.to_string(),
+            shortfall: format!("{} ({} tokens)", shortfall, tokens),
+        }
+        .into())
+    }
+
+    /// Annotate a transaction result with its estimated and actual cost.
+    pub fn annotate<T>(tx: TxResult<T>, estimate: Option<CostEstimate>) -> CostedTxResult<T> {
+        let actual = ActualCost::from_tx(&tx);
+        CostedTxResult {
+            tx,
+            estimate,
+            actual,
+        }
+    }
+
+    /// Returns the TTL the blobs actor will apply, rejecting TTLs below the subnet minimum.
+    async fn resolve_ttl(
+        provider: &impl QueryProvider,
+        ttl: Option<ChainEpoch>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<ChainEpoch> {
+        let config = Subnet::get_config(provider, height).await?;
+        let ttl = ttl.unwrap_or(config.blob_default_ttl);
+        if ttl < config.blob_min_ttl {
+            return Err(anyhow!(
+                "TTL {} is below the minimum of {} epochs",
+                ttl,
+                config.blob_min_ttl
+            ));
+        }
+        Ok(ttl)
+    }
+}
+
+fn decode_account(deliver_tx: &DeliverTx) -> anyhow::Result<Option<Account>> {
+    let data = decode_bytes(deliver_tx)?;
+    fvm_ipld_encoding::from_slice::<Option<Account>>(&data)
+        .map_err(|e| anyhow!("error parsing as Option<Account>: {e}"))
+}
+
+/// Returns the credits required to store `size` bytes for `ttl` epochs.
+/// Storage is charged one whole credit per byte per epoch.
+fn storage_credits(size: u64, ttl: ChainEpoch) -> Credit {
+    Credit::from_whole(BigInt::from(size) * ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_credits() {
+        assert_eq!(storage_credits(1024, 3600), Credit::from_whole(3_686_400));
+        assert_eq!(storage_credits(0, 3600), Credit::from_whole(0));
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_tempfile::TempFile;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::OnceCell;
+
+use recall_provider::object::UploadResponse;
+
+/// Shares uploads of identical content within a process.
+///
+/// Adds that carry the same [`UploadDedup`] (see `AddOptions::dedup`) and have the same
+/// content, identified by its BLAKE3 hash, only upload it once while the uploads overlap.
+/// Every add still issues its own add transaction.
+/// If the shared upload fails, the next waiting add retries it with its own content.
+/// Cloning is cheap, and clones share in-flight uploads.
+#[derive(Clone, Default)]
+pub struct UploadDedup {
+    uploads: Arc<Mutex<HashMap<[u8; 32], Arc<OnceCell<UploadResponse>>>>>,
+}
+
+impl fmt::Debug for UploadDedup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadDedup")
+            .field("in_flight", &self.lock().len())
+            .finish()
+    }
+}
+
+impl UploadDedup {
+    /// Create an empty upload deduplicator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `upload` for content with `hash`, unless an upload of the same content is already
+    /// in flight, in which case its response is shared.
+    pub(crate) async fn upload<F, Fut>(
+        &self,
+        hash: [u8; 32],
+        upload: F,
+    ) -> anyhow::Result<UploadResponse>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<UploadResponse>>,
+    {
+        let cell = self.lock().entry(hash).or_default().clone();
+        let result = cell.get_or_try_init(upload).await.cloned();
+        let mut uploads = self.lock();
+        // Only the map and this call hold the cell, so no other add is waiting on it
+        if Arc::strong_count(&cell) == 2 {
+            uploads.remove(&hash);
+        }
+        result
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<[u8; 32], Arc<OnceCell<UploadResponse>>>> {
+        self.uploads.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Copy a reader into a temporary file while hashing it.
+/// Returns the file, rewound to the start, and the BLAKE3 hash of its contents.
+pub(crate) async fn spool_and_hash<R>(mut reader: R) -> anyhow::Result<(TempFile, [u8; 32])>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut file = TempFile::new().await?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        file.write_all(&buffer[..n]).await?;
+    }
+    file.flush().await?;
+    file.rewind().await?;
+    Ok((file, *hasher.finalize().as_bytes()))
+}
+
+/// Returns the BLAKE3 hash of a file's contents, memory-mapping the file where possible.
+pub(crate) async fn hash_file(path: &Path) -> anyhow::Result<[u8; 32]> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_mmap(&path)?;
+        Ok(*hasher.finalize().as_bytes())
+    })
+    .await?
+}
@@ -20,10 +20,12 @@ use ethers_contract::ContractCall;
 use gateway_manager_facet::{FvmAddress, GatewayManagerFacet, SubnetID as GatewaySubnetID};
 use ipc_actors_abis::gateway_manager_facet;
 use ipc_api::evm::{fil_to_eth_amount, payload_to_evm_address};
-use num_traits::ToPrimitive;
 use reqwest::{header::HeaderValue, Client};
 
-use recall_provider::fvm_shared::{address::Address, econ::TokenAmount};
+use recall_provider::{
+    fvm_shared::{address::Address, econ::TokenAmount},
+    util::{polling_interval, TokenAmountExt, BLOCK_INTERVAL_SAMPLE_SIZE},
+};
 use recall_signer::{Signer, SubnetID};
 
 use crate::ipc::subnet::EVMSubnet;
@@ -34,14 +36,12 @@ type DefaultSignerMiddleware = SignerMiddleware<Provider<Http>, Wallet<SigningKe
 /// transactions and events. Default is 7, and for our child subnets we
 /// can reduce it to the block time (or potentially less)
 const ETH_PROVIDER_POLLING_TIME: Duration = Duration::from_secs(1);
-/// Maximum number of retries to fetch a transaction receipt.
-/// The number of retries should ensure that for the block time
-/// of the network the number of retries considering the polling
-/// time above waits enough time to get the transaction receipt.
-/// We currently support a low polling time and high number of
-/// retries so these numbers accommodate fast subnets with slow
-/// roots (like Calibration and mainnet).
-const TRANSACTION_RECEIPT_RETRIES: usize = 200;
+/// Maximum time to wait for a transaction receipt.
+/// The polling interval is adapted to the observed block time of the
+/// network (falling back to the polling time above), and the number of
+/// retries is derived from it, so this accommodates fast subnets with
+/// slow roots (like Calibration and mainnet).
+const TRANSACTION_RECEIPT_TIMEOUT: Duration = Duration::from_secs(200);
 
 // Generate ABI for `approval` method on ERC20
 abigen!(
@@ -145,7 +145,7 @@ async fn get_supply_source_balance(
     let balance = supply_source
         .balance_of(payload_to_evm_address(address.payload())?)
         .await?;
-    Ok(TokenAmount::from_atto(balance.as_u128()))
+    Ok(TokenAmount::from_u256(balance))
 }
 
 /// A static wrapper around common EVM subnet methods.
@@ -158,7 +158,7 @@ impl EvmManager {
         let balance = provider
             .get_balance(payload_to_evm_address(address.payload())?, None)
             .await?;
-        Ok(TokenAmount::from_atto(balance.as_u128()))
+        Ok(TokenAmount::from_u256(balance))
     }
 
     /// Get the balance of the supply source (ERC20) of an account in a subnet.
@@ -180,10 +180,7 @@ impl EvmManager {
     ) -> anyhow::Result<TransactionReceipt> {
         let gateway = get_gateway(signer, &subnet)?;
         let supply_source = get_supply_source(signer, &subnet)?;
-        let value = amount
-            .atto()
-            .to_u128()
-            .ok_or_else(|| anyhow!("invalid value to fund"))?;
+        let value = amount.to_atto_u128()?;
 
         let call = supply_source.approve(gateway.address(), value.into());
 
@@ -201,10 +198,7 @@ impl EvmManager {
         let gateway = get_gateway(signer, &from_subnet)?;
         let subnet_id = GatewaySubnetID::try_from(&to_subnet.inner())?;
 
-        let value = amount
-            .atto()
-            .to_u128()
-            .ok_or_else(|| anyhow!("invalid value to fund"))?;
+        let value = amount.to_atto_u128()?;
 
         let call = gateway.fund_with_token(subnet_id, FvmAddress::try_from(to)?, value.into());
 
@@ -220,10 +214,7 @@ impl EvmManager {
     ) -> anyhow::Result<TransactionReceipt> {
         let gateway = get_gateway(signer, &subnet)?;
 
-        let value = amount
-            .atto()
-            .to_u128()
-            .ok_or_else(|| anyhow!("invalid value to fund"))?;
+        let value = amount.to_atto_u128()?;
 
         let mut call = gateway.release(FvmAddress::try_from(to)?);
         call.tx.set_value(value);
@@ -252,6 +243,28 @@ impl EvmManager {
             .await?
             .ok_or(anyhow!("transfer did not return receipt"))
     }
+
+    /// Send a transaction with `data` as its calldata to an account in a subnet.
+    pub async fn send_data(
+        signer: &impl Signer,
+        to: Address,
+        subnet: EVMSubnet,
+        data: Vec<u8>,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let signer = Arc::new(get_eth_signer(signer, &subnet)?);
+
+        let (fee, fee_cap) = premium_estimation(signer.clone()).await?;
+        let tx = Eip1559TransactionRequest::new()
+            .to(payload_to_evm_address(to.payload())?)
+            .data(data)
+            .max_priority_fee_per_gas(fee)
+            .max_fee_per_gas(fee_cap);
+
+        let tx_pending = signer.send_transaction(tx, None).await?;
+        tx_pending
+            .await?
+            .ok_or(anyhow!("transaction did not return receipt"))
+    }
 }
 
 /// Sends a contract call with configured retries using the provided client.
@@ -259,9 +272,15 @@ async fn client_send<T: ethers::abi::Detokenize>(
     client: Arc<DefaultSignerMiddleware>,
     call: ContractCall<DefaultSignerMiddleware, T>,
 ) -> anyhow::Result<TransactionReceipt> {
+    let interval = match block_interval(client.clone()).await {
+        Ok(block_interval) => polling_interval(block_interval),
+        Err(_) => ETH_PROVIDER_POLLING_TIME,
+    };
+    let retries = (TRANSACTION_RECEIPT_TIMEOUT.as_millis() / interval.as_millis().max(1)) as usize;
+
     let call = call_with_premium_and_pending_block(client, call).await?;
     let tx = call.send().await?;
-    match tx.retries(TRANSACTION_RECEIPT_RETRIES).await? {
+    match tx.interval(interval).retries(retries).await? {
         Some(receipt) => Ok(receipt),
         None => Err(anyhow!(
             "txn sent to network, but receipt cannot be obtained, please check scanner"
@@ -269,6 +288,30 @@ async fn client_send<T: ethers::abi::Detokenize>(
     }
 }
 
+/// Returns the average block interval of an EVM subnet observed over recent blocks.
+async fn block_interval(signer: Arc<DefaultSignerMiddleware>) -> anyhow::Result<Duration> {
+    let latest = signer
+        .get_block(ethers::types::BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| anyhow!("Latest block not found"))?;
+    let latest_number = latest
+        .number
+        .ok_or_else(|| anyhow!("Latest block is pending"))?
+        .as_u64();
+    let earliest_number = latest_number.saturating_sub(BLOCK_INTERVAL_SAMPLE_SIZE);
+    if earliest_number == latest_number {
+        return Err(anyhow!("not enough blocks to measure the block interval"));
+    }
+    let earliest = signer
+        .get_block(earliest_number)
+        .await?
+        .ok_or_else(|| anyhow!("block {} not found", earliest_number))?;
+
+    // Block timestamps are in seconds.
+    let elapsed = latest.timestamp.saturating_sub(earliest.timestamp).as_u64();
+    Ok(Duration::from_secs(elapsed) / (latest_number - earliest_number) as u32)
+}
+
 /// Takes a `FunctionCall` input and returns a new instance with an estimated optimal `gas_premium`.
 /// The function also uses the pending block number to help retrieve the latest nonce
 /// via `get_transaction_count` with the `pending` parameter.
@@ -0,0 +1,192 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Validation and normalization of object keys.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use recall_provider::error::{self, ErrorCode};
+
+use crate::machine::bucket::display_key;
+
+/// Default maximum key length in bytes.
+const DEFAULT_MAX_KEY_LEN: usize = 1024;
+
+/// Characters allowed in [`Charset::Safe`] keys, besides ASCII letters and digits.
+const SAFE_KEY_CHARS: &str = "!-_.*'()/";
+
+/// Error returned when a key isn't allowed by a [`KeyPolicy`].
+///
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidKeyError {
+    /// The key, escaped if it isn't valid UTF-8 (see [`display_key`]).
+    pub key: String,
+    /// Why the key isn't allowed.
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: invalid key '{}': {}",
+            self.code(),
+            self.key,
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for InvalidKeyError {}
+
+impl ErrorCode for InvalidKeyError {
+    fn code(&self) -> &'static str {
+        error::OBJECT_KEY_INVALID
+    }
+}
+
+/// Validates and normalizes object keys before a bucket uses them.
+///
+/// A policy is applied to the keys passed to bucket adds, gets, and deletes, and to query
+/// prefixes, so an application embedding user-supplied keys can't create entries it can't
+/// read back, or distinct entries for keys that mean the same thing.
+/// See [`BucketBuilder::key_policy`](crate::machine::bucket::BucketBuilder::key_policy).
+pub trait KeyPolicy: fmt::Debug + Send + Sync {
+    /// Returns the key to use in place of `key`, or an [`InvalidKeyError`] if the key isn't
+    /// allowed. Applying a policy to a key it returned must return the same key.
+    fn apply<'a>(&self, key: &'a [u8]) -> anyhow::Result<Cow<'a, [u8]>>;
+}
+
+/// The characters a [`StandardKeyPolicy`] allows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Charset {
+    /// Any valid UTF-8 without control characters.
+    #[default]
+    Printable,
+    /// ASCII letters, digits, and `!-_.*'()/`, which never need escaping in URLs or paths.
+    Safe,
+}
+
+/// A [`KeyPolicy`] that limits key length and characters, and normalizes path-like keys.
+///
+/// The default policy allows printable keys of up to 1024 bytes and normalizes them.
+#[derive(Clone, Debug)]
+pub struct StandardKeyPolicy {
+    /// Maximum key length in bytes, after normalization.
+    pub max_len: usize,
+    /// The characters allowed in keys.
+    pub charset: Charset,
+    /// Whether to normalize keys as paths: leading and repeated `/` are collapsed, `.`
+    /// segments are dropped, and `..` segments remove the segment before them.
+    /// Keys with a `..` that climbs above the root are rejected.
+    pub normalize: bool,
+}
+
+impl Default for StandardKeyPolicy {
+    fn default() -> Self {
+        StandardKeyPolicy {
+            max_len: DEFAULT_MAX_KEY_LEN,
+            charset: Default::default(),
+            normalize: true,
+        }
+    }
+}
+
+impl KeyPolicy for StandardKeyPolicy {
+    fn apply<'a>(&self, key: &'a [u8]) -> anyhow::Result<Cow<'a, [u8]>> {
+        let invalid = |reason: String| InvalidKeyError {
+            key: display_key(key),
+            reason,
+        };
+        let Ok(s) = std::str::from_utf8(key) else {
+            return Err(invalid("key isn't valid UTF-8".into()).into());
+        };
+        let allowed = |c: char| match self.charset {
+            Charset::Printable => !c.is_control(),
+            Charset::Safe => c.is_ascii_alphanumeric() || SAFE_KEY_CHARS.contains(c),
+        };
+        if let Some(c) = s.chars().find(|c| !allowed(*c)) {
+            return Err(invalid(format!("character {:?} isn't allowed", c)).into());
+        }
+
+        let key = if self.normalize {
+            match normalize(s) {
+                Some(normalized) if normalized == s => Cow::Borrowed(key),
+                Some(normalized) => Cow::Owned(normalized.into_bytes()),
+                None => return Err(invalid("'..' climbs above the root".into()).into()),
+            }
+        } else {
+            Cow::Borrowed(key)
+        };
+        if key.is_empty() {
+            return Err(invalid("key is empty".into()).into());
+        }
+        if key.len() > self.max_len {
+            return Err(invalid(format!(
+                "key is {} bytes; the maximum is {}",
+                key.len(),
+                self.max_len
+            ))
+            .into());
+        }
+        Ok(key)
+    }
+}
+
+/// Normalizes a path-like key, or returns `None` if a `..` segment climbs above the root.
+/// A trailing `/` is kept, so prefixes stay prefixes.
+fn normalize(key: &str) -> Option<String> {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in key.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let mut normalized = segments.join("/");
+    let is_dir = key.ends_with('/') || key.ends_with("/.") || key.ends_with("/..");
+    if is_dir && !normalized.is_empty() {
+        normalized.push('/');
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_key_policy() {
+        let policy = StandardKeyPolicy::default();
+        let apply = |key: &str| {
+            policy
+                .apply(key.as_bytes())
+                .map(|key| String::from_utf8(key.into_owned()).unwrap())
+        };
+        assert_eq!(apply("logs/2024/error.log").unwrap(), "logs/2024/error.log");
+        assert_eq!(
+            apply("/logs//./2024/../error.log").unwrap(),
+            "logs/error.log"
+        );
+        assert_eq!(apply("logs//").unwrap(), "logs/");
+        assert!(apply("../secret").is_err());
+        assert!(apply("a\nb").is_err());
+        assert!(apply("/").is_err());
+        assert!(policy.apply(&[0xff]).is_err());
+
+        let policy = StandardKeyPolicy {
+            max_len: 8,
+            charset: Charset::Safe,
+            normalize: false,
+        };
+        assert!(policy.apply(b"a//b").is_ok());
+        assert!(policy.apply(b"a b").is_err());
+        let err = policy.apply(b"123456789").unwrap_err();
+        assert!(err.to_string().starts_with("RECALL-E-OBJ-004: "));
+    }
+}
@@ -8,13 +8,32 @@
 use recall_provider::message::GasParams;
 
 pub mod account;
+pub mod anchor;
+pub mod bulk;
+pub mod cache;
+pub mod clock;
+pub mod compression;
+pub mod cost;
 pub mod credits;
+pub mod dedup;
 pub mod ipc;
+pub mod key_policy;
+pub mod lifecycle;
 pub mod machine;
+pub mod manifest;
+pub mod namespace;
 pub mod network;
+pub mod p2p;
+pub mod pattern;
 pub mod progress;
+pub mod provenance;
+pub mod quota;
+pub mod range;
+pub mod root_cache;
 pub mod storage;
 pub mod subnet;
+pub mod testing;
+mod throttle;
 
 /// Arguments common to transactions.
 #[derive(Clone, Default, Debug)]
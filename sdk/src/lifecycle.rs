@@ -0,0 +1,407 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use anyhow::anyhow;
+
+use recall_provider::{
+    fvm_shared::{address::Address, clock::ChainEpoch},
+    message::GasParams,
+    query::{FvmQueryHeight, QueryProvider},
+    tx::BroadcastMode,
+    Client, Provider,
+};
+use recall_signer::Signer;
+
+use crate::{
+    bulk::{BulkReport, ItemAttempts},
+    clock::{clock_or_system, unix_seconds, Clock},
+    machine::{
+        bucket::{Bucket, CopyOptions, DeleteOptions, QueryOptions},
+        info, Machine,
+    },
+};
+
+/// Machine metadata key prefix under which lifecycle rules are stored.
+/// The rest of the key is the rule's object key prefix.
+pub const LIFECYCLE_METADATA_PREFIX: &str = "lifecycle:";
+
+/// Object metadata key holding the object's creation time in seconds since the Unix epoch.
+/// It's set by [`Bucket::add_reader`], [`Bucket::add_from_path`], and [`Bucket::add_many`]
+/// when [`AddOptions::stamp_created_at`](crate::machine::bucket::AddOptions::stamp_created_at)
+/// is set.
+/// Objects without it, e.g., those added without stamping or by other clients, are never expired
+/// or renewed by age.
+pub const CREATED_AT_METADATA_KEY: &str = "created-at";
+
+/// Object metadata key holding the time an object was last renewed by a lifecycle rule, in
+/// seconds since the Unix epoch.
+pub const RENEWED_AT_METADATA_KEY: &str = "renewed-at";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A lifecycle rule for objects under a key prefix.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LifecycleRule {
+    /// The key prefix the rule applies to.
+    pub prefix: String,
+    /// Delete objects older than this many days (see [`CREATED_AT_METADATA_KEY`]).
+    pub expire_after_days: Option<u64>,
+    /// Keep only the last N objects under the prefix in key order, deleting the rest.
+    /// This suits keys that sort chronologically, e.g., `backups/2025-01-31`.
+    pub keep_last: Option<usize>,
+    /// Renew objects that were created or last renewed (see [`RENEWED_AT_METADATA_KEY`]) at
+    /// least this many days ago, re-adding them with [`ApplyLifecycleOptions::ttl`].
+    /// Renewing references the existing content, so no data is uploaded.
+    /// Objects that are deleted by a rule aren't renewed, and immutable objects fail to renew.
+    pub renew_after_days: Option<u64>,
+}
+
+impl LifecycleRule {
+    /// Encode the rule as a machine metadata entry.
+    pub fn to_metadata(&self) -> (String, String) {
+        let mut parts = Vec::new();
+        if let Some(days) = self.expire_after_days {
+            parts.push(format!("expire_after_days={}", days));
+        }
+        if let Some(n) = self.keep_last {
+            parts.push(format!("keep_last={}", n));
+        }
+        if let Some(days) = self.renew_after_days {
+            parts.push(format!("renew_after_days={}", days));
+        }
+        (
+            format!("{}{}", LIFECYCLE_METADATA_PREFIX, self.prefix),
+            parts.join(";"),
+        )
+    }
+
+    /// Decode a rule from a machine metadata entry.
+    /// Returns `None` if the entry isn't a lifecycle rule.
+    pub fn from_metadata(key: &str, value: &str) -> anyhow::Result<Option<Self>> {
+        let Some(prefix) = key.strip_prefix(LIFECYCLE_METADATA_PREFIX) else {
+            return Ok(None);
+        };
+        let mut rule = LifecycleRule {
+            prefix: prefix.into(),
+            ..Default::default()
+        };
+        for part in value.split(';').filter(|p| !p.is_empty()) {
+            let (name, val) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("invalid lifecycle rule '{}' for '{}'", part, prefix))?;
+            match name {
+                "expire_after_days" => rule.expire_after_days = Some(val.parse()?),
+                "keep_last" => rule.keep_last = Some(val.parse()?),
+                "renew_after_days" => rule.renew_after_days = Some(val.parse()?),
+                _ => {
+                    return Err(anyhow!(
+                        "unknown lifecycle rule '{}' for '{}'",
+                        name,
+                        prefix
+                    ))
+                }
+            }
+        }
+        Ok(Some(rule))
+    }
+
+    /// Returns the keys of objects that the rule says should be deleted.
+    ///
+    /// `objects` must be in key order, as returned by a bucket query.
+    fn evaluate(&self, objects: &[(Vec<u8>, HashMap<String, String>)], now: u64) -> Vec<Vec<u8>> {
+        let mut keys = Vec::new();
+        if let Some(days) = self.expire_after_days {
+            keys.extend(older_than(objects, &[CREATED_AT_METADATA_KEY], days, now));
+        }
+        if let Some(n) = self.keep_last {
+            let excess = objects.len().saturating_sub(n);
+            keys.extend(objects[..excess].iter().map(|(key, _)| key.clone()));
+        }
+        keys
+    }
+
+    /// Returns the keys of objects that the rule says should be renewed.
+    fn evaluate_renewals(
+        &self,
+        objects: &[(Vec<u8>, HashMap<String, String>)],
+        now: u64,
+    ) -> Vec<Vec<u8>> {
+        let Some(days) = self.renew_after_days else {
+            return Vec::new();
+        };
+        let keys = [RENEWED_AT_METADATA_KEY, CREATED_AT_METADATA_KEY];
+        older_than(objects, &keys, days, now).collect()
+    }
+}
+
+/// Returns the keys of objects whose time under the first of `time_keys` that's set is at
+/// least `days` before `now`.
+fn older_than<'a>(
+    objects: &'a [(Vec<u8>, HashMap<String, String>)],
+    time_keys: &'a [&str],
+    days: u64,
+    now: u64,
+) -> impl Iterator<Item = Vec<u8>> + 'a {
+    let max_age = days.saturating_mul(SECONDS_PER_DAY);
+    objects.iter().filter_map(move |(key, metadata)| {
+        let time = time_keys
+            .iter()
+            .find_map(|k| metadata.get(*k))
+            .and_then(|v| v.parse::<u64>().ok())?;
+        (now.saturating_sub(time) >= max_age).then(|| key.clone())
+    })
+}
+
+/// Encode lifecycle rules as machine metadata, e.g., for [`Machine::new`].
+pub fn rules_to_metadata(rules: &[LifecycleRule]) -> HashMap<String, String> {
+    rules.iter().map(|r| r.to_metadata()).collect()
+}
+
+/// Load the lifecycle rules stored in a machine's metadata.
+pub async fn load_rules(
+    provider: &impl QueryProvider,
+    address: Address,
+    height: FvmQueryHeight,
+) -> anyhow::Result<Vec<LifecycleRule>> {
+    let metadata = info(provider, address, height).await?;
+    let mut rules = Vec::new();
+    for (key, value) in &metadata.metadata {
+        if let Some(rule) = LifecycleRule::from_metadata(key, value)? {
+            rules.push(rule);
+        }
+    }
+    rules.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+    Ok(rules)
+}
+
+/// Lifecycle apply options.
+#[derive(Clone, Default, Debug)]
+pub struct ApplyLifecycleOptions {
+    /// Only report the objects that would be deleted.
+    pub dry_run: bool,
+    /// Query block height for listing objects.
+    pub height: FvmQueryHeight,
+    /// Broadcast mode for the delete and renewal transactions.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the delete and renewal transactions.
+    pub gas_params: GasParams,
+    /// Number of times to retry a delete or renewal that fails with a transient error.
+    pub retries: u32,
+    /// Object time-to-live (TTL) duration that renewed objects are re-added with.
+    /// If not specified, the current default TTL from the config actor is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Optional clock that object ages and renewal times are measured with.
+    /// If not specified, the system clock is used.
+    pub clock: Option<Arc<dyn Clock>>,
+}
+
+/// The outcome of applying lifecycle rules.
+#[derive(Clone, Debug, Default)]
+pub struct LifecycleReport {
+    /// Number of objects evaluated against the rules.
+    pub evaluated: u64,
+    /// Keys of the objects that were deleted (or would be, in a dry run).
+    pub deleted: Vec<Vec<u8>>,
+    /// Keys of the objects that were renewed (or would be, in a dry run).
+    pub renewed: Vec<Vec<u8>>,
+    /// Per-object results of the delete and renewal transactions. Empty in a dry run.
+    pub results: BulkReport,
+}
+
+/// Evaluate lifecycle rules against a bucket's objects, deleting and renewing the objects
+/// they select.
+///
+/// Objects matched by more than one rule are deleted or renewed once, and objects selected
+/// for deletion by any rule aren't renewed. Objects that no rule selects are left untouched.
+/// A renewal re-adds the object at its own key with the same content and a new TTL,
+/// recording the time under [`RENEWED_AT_METADATA_KEY`].
+/// A failed transaction doesn't stop the others; see [`LifecycleReport::results`].
+pub async fn apply_lifecycle<C>(
+    provider: &impl Provider<C>,
+    signer: &mut impl Signer,
+    from: Address,
+    bucket: &Bucket,
+    rules: &[LifecycleRule],
+    options: ApplyLifecycleOptions,
+) -> anyhow::Result<LifecycleReport>
+where
+    C: Client + Send + Sync,
+{
+    let now = unix_seconds(clock_or_system(&options.clock).as_ref())?;
+    let mut report = LifecycleReport::default();
+    let mut to_delete = BTreeSet::new();
+    let mut to_renew = BTreeSet::new();
+    for rule in rules {
+        let mut objects = Vec::new();
+        let query = QueryOptions {
+            prefix: rule.prefix.clone(),
+            delimiter: "".into(),
+            height: options.height,
+            ..Default::default()
+        };
+        bucket
+            .query_pages(provider, query, |page| {
+                objects.extend(
+                    page.objects
+                        .into_iter()
+                        .map(|(key, object)| (key, object.metadata)),
+                );
+                Ok(())
+            })
+            .await?;
+        report.evaluated += objects.len() as u64;
+        to_delete.extend(rule.evaluate(&objects, now));
+        to_renew.extend(rule.evaluate_renewals(&objects, now));
+    }
+
+    for key in to_delete {
+        if options.dry_run {
+            report.deleted.push(key);
+            continue;
+        }
+        let mut attempts = ItemAttempts::new(&key, options.retries);
+        let result = loop {
+            let tx = bucket
+                .delete(
+                    provider,
+                    signer,
+                    from,
+                    &key,
+                    DeleteOptions {
+                        broadcast_mode: options.broadcast_mode,
+                        gas_params: options.gas_params.clone(),
+                        ..Default::default()
+                    },
+                )
+                .await;
+            if let Some(result) = attempts.record(tx) {
+                break result;
+            }
+        };
+        if result.is_ok() {
+            report.deleted.push(key);
+        }
+        report.results.items.push(result);
+    }
+
+    for key in to_renew.difference(&to_delete) {
+        if options.dry_run {
+            report.renewed.push(key.clone());
+            continue;
+        }
+        let mut attempts = ItemAttempts::new(key, options.retries);
+        let result = loop {
+            let tx = bucket
+                .copy(
+                    provider,
+                    signer,
+                    from,
+                    key,
+                    key,
+                    CopyOptions {
+                        ttl: options.ttl,
+                        metadata: HashMap::from([(
+                            RENEWED_AT_METADATA_KEY.to_string(),
+                            now.to_string(),
+                        )]),
+                        overwrite: true,
+                        height: options.height,
+                        broadcast_mode: options.broadcast_mode,
+                        gas_params: options.gas_params.clone(),
+                    },
+                )
+                .await;
+            if let Some(result) = attempts.record(tx) {
+                break result;
+            }
+        };
+        if result.is_ok() {
+            report.renewed.push(key.clone());
+        }
+        report.results.items.push(result);
+    }
+    tracing::debug!(
+        address = %bucket.address(),
+        evaluated = report.evaluated,
+        deleted = report.deleted.len(),
+        renewed = report.renewed.len(),
+        failed = report.results.failed().count(),
+        dry_run = options.dry_run,
+        "applied lifecycle rules"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifecycle_rule_metadata() {
+        let rule = LifecycleRule {
+            prefix: "logs/".into(),
+            expire_after_days: Some(30),
+            keep_last: Some(5),
+            renew_after_days: Some(7),
+        };
+        let (key, value) = rule.to_metadata();
+        assert_eq!(key, "lifecycle:logs/");
+        assert_eq!(value, "expire_after_days=30;keep_last=5;renew_after_days=7");
+        assert_eq!(
+            LifecycleRule::from_metadata(&key, &value).unwrap(),
+            Some(rule)
+        );
+        assert_eq!(LifecycleRule::from_metadata("foo", "bar").unwrap(), None);
+        assert!(LifecycleRule::from_metadata("lifecycle:a/", "keep=1").is_err());
+    }
+
+    #[test]
+    fn test_lifecycle_rule_evaluate() {
+        let now = 100 * SECONDS_PER_DAY;
+        let object = |key: &str, created_days: Option<u64>| {
+            let mut metadata = HashMap::new();
+            if let Some(days) = created_days {
+                metadata.insert(
+                    CREATED_AT_METADATA_KEY.to_string(),
+                    (days * SECONDS_PER_DAY).to_string(),
+                );
+            }
+            (key.as_bytes().to_vec(), metadata)
+        };
+        let objects = vec![
+            object("a", Some(10)),
+            object("b", None),
+            object("c", Some(90)),
+            object("d", Some(99)),
+        ];
+
+        let rule = LifecycleRule {
+            expire_after_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(rule.evaluate(&objects, now), vec![b"a".to_vec()]);
+
+        let rule = LifecycleRule {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            rule.evaluate(&objects, now),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+
+        let mut objects = objects;
+        objects[0].1.insert(
+            RENEWED_AT_METADATA_KEY.to_string(),
+            (95 * SECONDS_PER_DAY).to_string(),
+        );
+        let rule = LifecycleRule {
+            renew_after_days: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(rule.evaluate_renewals(&objects, now), vec![b"c".to_vec()]);
+    }
+}
@@ -15,7 +15,7 @@ use tendermint::abci::response::DeliverTx;
 
 use recall_provider::{
     fvm_ipld_encoding::{self, RawBytes},
-    fvm_shared::address::Address,
+    fvm_shared::{address::Address, ActorID},
     message::{local_message, GasParams},
     query::{FvmQueryHeight, QueryProvider},
     response::decode_bytes,
@@ -26,6 +26,7 @@ use recall_provider::{
 use recall_signer::Signer;
 
 pub mod bucket;
+pub mod public_bucket;
 pub mod timehub;
 
 /// Trait implemented by different machine kinds.
@@ -41,7 +42,7 @@ pub trait Machine: Send + Sync + Sized {
         owner: Option<Address>,
         metadata: HashMap<String, String>,
         gas_params: GasParams,
-    ) -> anyhow::Result<(Self, TxResult<CreateExternalReturn>)>
+    ) -> anyhow::Result<(Self, TxResult<MachineCreated>)>
     where
         C: Client + Send + Sync;
 
@@ -86,6 +87,33 @@ pub trait Machine: Send + Sync + Sized {
     }
 }
 
+/// The identifiers and owner of a newly created machine, decoded from the creation transaction.
+#[derive(Clone, Debug)]
+pub struct MachineCreated {
+    /// The machine actor ID.
+    pub actor_id: ActorID,
+    /// The machine ID address.
+    pub address: Address,
+    /// The machine robust (actor-style t/f2) address, if one was assigned.
+    pub robust_address: Option<Address>,
+    /// The machine Ethereum-style (masked ID) address.
+    pub eth_address: EthAddress,
+    /// The machine owner.
+    pub owner: Address,
+}
+
+impl MachineCreated {
+    fn new(ret: CreateExternalReturn, owner: Address) -> Self {
+        MachineCreated {
+            actor_id: ret.actor_id,
+            address: Address::new_id(ret.actor_id),
+            robust_address: ret.robust_address,
+            eth_address: EthAddress::from_id(ret.actor_id),
+            owner,
+        }
+    }
+}
+
 /// Get machine info (the owner and machine kind).
 pub async fn info(
     provider: &impl QueryProvider,
@@ -105,12 +133,13 @@ async fn deploy_machine<C>(
     kind: Kind,
     metadata: HashMap<String, String>,
     gas_params: GasParams,
-) -> anyhow::Result<(Address, TxResult<CreateExternalReturn>)>
+) -> anyhow::Result<(Address, TxResult<MachineCreated>)>
 where
     C: Client + Send + Sync,
 {
+    let owner = owner.unwrap_or(signer.address());
     let params = CreateExternalParams {
-        owner: owner.unwrap_or(signer.address()),
+        owner,
         kind,
         metadata,
     };
@@ -125,13 +154,14 @@ where
             params,
             gas_params,
             BroadcastMode::Commit,
-            decode_create,
+            |deliver_tx: &DeliverTx| -> anyhow::Result<MachineCreated> {
+                Ok(MachineCreated::new(decode_create(deliver_tx)?, owner))
+            },
         )
         .await?;
 
     // In commit broadcast mode, if the data or address does not exist, something fatal happened.
-    let actor_id = tx.data.clone().expect("data exists").actor_id;
-    let address = Address::new_id(actor_id);
+    let address = tx.data.as_ref().expect("data exists").address;
 
     Ok((address, tx))
 }
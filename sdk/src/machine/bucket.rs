@@ -1,51 +1,223 @@
 // Copyright 2025 Recall Contributors
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::path::Path;
-use std::{cmp::min, collections::HashMap, str::FromStr};
+use std::borrow::Cow;
+use std::fmt;
+use std::future::Future;
+use std::io::{IoSlice, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{
+    cmp::min,
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use bytes::{Buf, Bytes};
 use fendermint_actor_blobs_shared::state::{Hash, PublicKey};
 use fendermint_actor_bucket::{
-    AddParams, DeleteParams, GetParams, ListObjectsReturn, ListParams,
+    AddParams, DeleteParams, GetParams, ListParams,
     Method::{AddObject, DeleteObject, GetObject, ListObjects, UpdateObjectMetadata},
     UpdateObjectMetadataParams, MAX_METADATA_KEY_SIZE, MAX_METADATA_VALUE_SIZE,
 };
-use fendermint_vm_actor_interface::adm::{CreateExternalReturn, Kind};
+use fendermint_vm_actor_interface::adm::Kind;
+use futures::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
 use indicatif::HumanDuration;
 use infer::Type;
 use iroh::blobs::Hash as IrohHash;
 use peekable::tokio::AsyncPeekable;
+use serde::{Deserialize, Serialize};
 use tendermint::abci::response::DeliverTx;
-use tokio::io::{AsyncRead, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+use tendermint_rpc::{event::EventData, query::EventType, SubscriptionClient, WebSocketClient};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::time::Instant;
-use tokio_stream::StreamExt;
-use tokio_util::io::ReaderStream;
+use tokio_util::io::{ReaderStream, StreamReader};
 
 use recall_provider::{
+    audit::{self, AuditEvent, AuditKind, AuditSink},
+    error::{self, ErrorCode},
     fvm_ipld_encoding,
     fvm_ipld_encoding::RawBytes,
     fvm_shared::{address::Address, clock::ChainEpoch, econ::TokenAmount},
-    message::{local_message, GasParams},
-    object::ObjectProvider,
-    query::{FvmQueryHeight, QueryProvider},
+    json_rpc::Url,
+    message::{local_message, ChainMessage, GasParams},
+    object::{object_url, NodeAddr, ObjectProvider, UploadResponse},
+    query::{FvmQueryHeight, QueryProvider, QueryResponse},
     response::{decode_as, decode_bytes},
     tx::{BroadcastMode, TxResult},
     Client, Provider,
 };
-use recall_signer::Signer;
+use recall_signer::{QueuedTransaction, Signer, Wallet};
 
 use crate::progress::{new_message_bar, new_multi_bar, SPARKLE};
 use crate::{
-    machine::{deploy_machine, Machine},
+    bulk::{BulkItemResult, BulkReport, ItemAttempts},
+    cache::ObjectCache,
+    clock::{clock_or_system, new_backoff_policy, retry, unix_seconds, Clock, SystemClock},
+    compression::{Compression, COMPRESSION_METADATA_KEY},
+    cost::{Cost, EstimateOptions},
+    dedup::{hash_file, spool_and_hash, UploadDedup},
+    key_policy::KeyPolicy,
+    lifecycle::CREATED_AT_METADATA_KEY,
+    machine::{deploy_machine, Machine, MachineCreated},
+    p2p::{self, Transport},
+    pattern::KeyPattern,
     progress::new_progress_bar,
+    quota::{Quota, QuotaExceededError},
+    range::Range,
+    throttle::throttle,
 };
-pub use fendermint_actor_bucket::{Object, ObjectState};
+pub use fendermint_actor_bucket::{ListObjectsReturn, Object, ObjectState};
 
 /// Maximum allowed object size in bytes.
 const MAX_OBJECT_LENGTH: u64 = 5_000_000_000; // 5GB
 
+/// Maximum number of concurrent object info queries when getting many objects.
+const MAX_CONCURRENT_QUERIES: usize = 16;
+
+/// Maximum number of downloaded chunks written to a file in a single vectored write.
+const MAX_WRITE_BATCH_LEN: usize = 64;
+
+/// Default maximum number of concurrent uploads in a batch add.
+const MAX_CONCURRENT_UPLOADS: usize = 8;
+
+/// Default maximum number of delete transactions pipelined in a batch.
+const MAX_PIPELINED_DELETES: usize = 64;
+
+/// Default number of objects fetched per page when walking a listing.
+const DEFAULT_PAGE_SIZE: u64 = 1000;
+
+/// Path of the snapshot that leads a bucket archive (see [`Bucket::export`]).
+const ARCHIVE_SNAPSHOT_PATH: &str = "snapshot.cbor";
+
+/// Default number of times to resume a download that fails mid-transfer
+/// (see [`GetOptions::retries`]).
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+
+/// Default time to wait before resuming an interrupted download.
+const DEFAULT_DOWNLOAD_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Maximum age of an upload checkpoint (see [`AddOptions::resume_from`]).
+/// The Object API drops staged uploads that aren't added to a bucket after a while, so older
+/// checkpoints may point at content that's gone, and the content is uploaded again instead.
+pub const MAX_UPLOAD_CHECKPOINT_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Metadata key that places an advisory immutability lock on an object
+/// (see [`AddOptions::immutable`]).
+pub const IMMUTABLE_METADATA_KEY: &str = "immutable";
+
+/// Error returned when downloaded content doesn't match the object hash.
+///
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityError {
+    /// The object key, escaped if it isn't valid UTF-8 (see [`display_key`]).
+    pub key: String,
+    /// The object hash.
+    pub expected: String,
+    /// The hash of the downloaded content.
+    pub actual: String,
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: downloaded content for key '{}' doesn't match the object hash (expected={}; actual={})",
+            self.code(),
+            self.key,
+            self.expected,
+            self.actual
+        )
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+impl ErrorCode for IntegrityError {
+    fn code(&self) -> &'static str {
+        error::OBJECT_INTEGRITY
+    }
+}
+
+/// Error returned when an add's [`Precondition`] isn't met.
+///
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreconditionFailedError {
+    /// The object key, escaped if it isn't valid UTF-8 (see [`display_key`]).
+    pub key: String,
+    /// The expected object hash, or `None` if no object was expected.
+    pub expected: Option<String>,
+    /// The hash of the existing object, or `None` if there's no object.
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for PreconditionFailedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |hash: &Option<String>| {
+            hash.as_ref()
+                .map_or("no object".to_string(), |hash| format!("hash={}", hash))
+        };
+        write!(
+            f,
+            "{}: precondition failed for key '{}': expected {}, found {}",
+            self.code(),
+            self.key,
+            describe(&self.expected),
+            describe(&self.actual)
+        )
+    }
+}
+
+impl std::error::Error for PreconditionFailedError {}
+
+impl ErrorCode for PreconditionFailedError {
+    fn code(&self) -> &'static str {
+        error::OBJECT_PRECONDITION
+    }
+}
+
+/// Error returned when an overwrite, delete, or metadata update targets an object with an
+/// advisory immutability lock (see [`AddOptions::immutable`]).
+///
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImmutableObjectError {
+    /// The object key, escaped if it isn't valid UTF-8 (see [`display_key`]).
+    pub key: String,
+}
+
+impl fmt::Display for ImmutableObjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: object '{}' is immutable", self.code(), self.key)
+    }
+}
+
+impl std::error::Error for ImmutableObjectError {}
+
+impl ErrorCode for ImmutableObjectError {
+    fn code(&self) -> &'static str {
+        error::OBJECT_IMMUTABLE
+    }
+}
+
+/// A condition the object at a key must meet for an add to replace it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Precondition {
+    /// No object exists at the key.
+    /// This is enforced by the bucket actor, since the add is sent without overwrite.
+    IfNotExists,
+    /// The object at the key has the given content hash.
+    /// This is checked before uploading and again before sending the transaction. The actor
+    /// can't compare hashes, so a write that lands between the last check and the add isn't
+    /// detected.
+    IfMatch(Hash),
+}
+
 /// Object add options.
 #[derive(Clone, Default, Debug)]
 pub struct AddOptions {
@@ -55,6 +227,13 @@ pub struct AddOptions {
     pub ttl: Option<ChainEpoch>,
     /// Metadata to add to the object.
     pub metadata: HashMap<String, String>,
+    /// Whether to record the add time under [`CREATED_AT_METADATA_KEY`], unless `metadata`
+    /// already sets it. Lifecycle rules that expire or renew objects by age
+    /// (see [`LifecycleRule`](crate::lifecycle::LifecycleRule)) need it.
+    pub stamp_created_at: bool,
+    /// Optional clock that the add time and the age of a `resume_from` checkpoint are taken
+    /// from. If not specified, the system clock is used.
+    pub clock: Option<Arc<dyn Clock>>,
     /// Overwrite the object if it already exists.
     pub overwrite: bool,
     /// Tokens to use for inline buying of credits
@@ -65,6 +244,123 @@ pub struct AddOptions {
     pub gas_params: GasParams,
     /// Whether to show progress-related output (useful for command-line interfaces).
     pub show_progress: bool,
+    /// Whether to allow adding a zero-length object, e.g., a marker or directory placeholder.
+    /// Empty objects are rejected by default because they're usually unintended (e.g., an empty pipe).
+    pub allow_empty: bool,
+    /// Optional deduplicator shared between concurrent adds.
+    /// Adds of identical content share a single upload. Content from a reader is hashed by
+    /// spooling it to a temporary file first.
+    pub dedup: Option<UploadDedup>,
+    /// Optional sink for recording the upload.
+    pub audit: Option<Arc<dyn AuditSink>>,
+    /// Optional checkpoint file for resuming an interrupted add.
+    /// Once the content is uploaded, the upload result is saved to the file, and a retried add
+    /// of the same content skips the upload and goes straight to the transaction.
+    /// The file is removed once the transaction succeeds.
+    /// Checkpoints older than [`MAX_UPLOAD_CHECKPOINT_AGE`], measured with `clock`, are
+    /// ignored, since the staged upload may have been dropped.
+    /// The Object API only accepts whole uploads, so an upload interrupted midway restarts.
+    pub resume_from: Option<PathBuf>,
+    /// Optional quota shared between adds.
+    /// Adds that would exceed it fail with a [`QuotaExceededError`] before uploading.
+    pub quota: Option<Quota>,
+    /// Optional codec to compress the content with before uploading.
+    /// The content is spooled to a temporary file to learn its compressed size, and the codec
+    /// is recorded in the object metadata so gets decompress it automatically.
+    /// The object size and hash are those of the compressed content.
+    pub compression: Option<Compression>,
+    /// Optional condition the existing object must meet, for optimistic concurrency control.
+    /// Adds whose condition isn't met fail with a [`PreconditionFailedError`].
+    /// [`Precondition::IfMatch`] implies `overwrite`, and [`Precondition::IfNotExists`]
+    /// overrides it.
+    pub precondition: Option<Precondition>,
+    /// Whether to check the subnet's free capacity and the sender's credit before uploading.
+    /// Adds that can't be covered fail with an `InsufficientResourceError` that includes the
+    /// shortfall (see [`Cost::check_admission`]).
+    pub check_admission: bool,
+    /// Whether to only estimate the add's cost, without uploading or broadcasting.
+    /// Preconditions and admission are still checked.
+    /// The result has status [`TxStatus::DryRun`](recall_provider::tx::TxStatus::DryRun)
+    /// with the estimated gas and credit cost.
+    pub dry_run: bool,
+    /// Optional upload rate limit in bytes per second.
+    pub rate_limit: Option<u64>,
+    /// Whether to place an advisory immutability lock on the object, e.g., to guard audit logs
+    /// and release artifacts against accidental changes.
+    /// The lock is recorded in the object metadata under [`IMMUTABLE_METADATA_KEY`], and
+    /// overwrites, deletes, and metadata updates of the object made with this client then fail
+    /// with an [`ImmutableObjectError`], even with `overwrite` set. Checking the lock costs an
+    /// extra query per overwrite, delete, and metadata update.
+    ///
+    /// This isn't write-once storage: the bucket actor doesn't know about the lock, so other
+    /// clients and direct actor calls can still change or delete the object, and the object
+    /// still expires with its TTL.
+    pub immutable: bool,
+}
+
+/// Data returned by a committed add (see [`Bucket::add_reader`]).
+#[derive(Clone, Debug)]
+pub struct AddReceipt {
+    /// The added object, decoded from the actor's return value.
+    pub object: Object,
+    /// The object the add overwrote, if any, including its content hash and size.
+    /// It's looked up just before the transaction is sent, so a write that lands in between
+    /// isn't reflected.
+    pub replaced: Option<Object>,
+}
+
+/// Data returned by a committed delete (see [`Bucket::delete`]).
+#[derive(Clone, Debug)]
+pub struct DeleteReceipt {
+    /// The deleted object, including its content hash and size.
+    /// It's looked up just before the transaction is sent, so a write that lands in between
+    /// isn't reflected.
+    pub deleted: Option<Object>,
+}
+
+/// Batch add options.
+#[derive(Clone, Debug)]
+pub struct AddManyOptions {
+    /// Object time-to-live (TTL) duration.
+    /// If not specified, the current default TTL from the config actor is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Metadata to add to every object, along with each object's detected content type.
+    pub metadata: HashMap<String, String>,
+    /// Whether to record the add time under [`CREATED_AT_METADATA_KEY`]
+    /// (see [`AddOptions::stamp_created_at`]).
+    pub stamp_created_at: bool,
+    /// Optional clock that the add time is taken from.
+    /// If not specified, the system clock is used.
+    pub clock: Option<Arc<dyn Clock>>,
+    /// Overwrite objects that already exist, unless they're immutable
+    /// (see [`AddOptions::immutable`]).
+    pub overwrite: bool,
+    /// Broadcast mode for the transactions.
+    /// Transactions are pipelined, so this must be [`BroadcastMode::Async`] or
+    /// [`BroadcastMode::Sync`].
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for each transaction.
+    pub gas_params: GasParams,
+    /// Maximum number of concurrent uploads.
+    pub concurrency: usize,
+    /// Whether to allow adding zero-length objects.
+    pub allow_empty: bool,
+}
+
+impl Default for AddManyOptions {
+    fn default() -> Self {
+        AddManyOptions {
+            ttl: Default::default(),
+            metadata: Default::default(),
+            stamp_created_at: Default::default(),
+            clock: Default::default(),
+            overwrite: Default::default(),
+            broadcast_mode: BroadcastMode::Sync,
+            gas_params: Default::default(),
+            concurrency: MAX_CONCURRENT_UPLOADS,
+            allow_empty: Default::default(),
+        }
+    }
 }
 
 /// Object delete options.
@@ -74,6 +370,49 @@ pub struct DeleteOptions {
     pub broadcast_mode: BroadcastMode,
     /// Gas params for the transaction.
     pub gas_params: GasParams,
+    /// Whether to only estimate the delete's gas cost, without broadcasting.
+    /// The result has status [`TxStatus::DryRun`](recall_provider::tx::TxStatus::DryRun).
+    pub dry_run: bool,
+}
+
+/// Prefix delete options.
+#[derive(Clone, Debug)]
+pub struct DeletePrefixOptions {
+    /// Only report the objects that would be deleted.
+    pub dry_run: bool,
+    /// Query block height for listing objects.
+    pub height: FvmQueryHeight,
+    /// Broadcast mode for the transactions.
+    /// Transactions are pipelined, so this must be [`BroadcastMode::Async`] or
+    /// [`BroadcastMode::Sync`].
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for each transaction.
+    pub gas_params: GasParams,
+    /// Maximum number of delete transactions broadcasted back-to-back in a batch.
+    pub concurrency: usize,
+}
+
+impl Default for DeletePrefixOptions {
+    fn default() -> Self {
+        DeletePrefixOptions {
+            dry_run: Default::default(),
+            height: Default::default(),
+            broadcast_mode: BroadcastMode::Sync,
+            gas_params: Default::default(),
+            concurrency: MAX_PIPELINED_DELETES,
+        }
+    }
+}
+
+/// The outcome of deleting objects with [`Bucket::delete_prefix`].
+#[derive(Clone, Debug, Default)]
+pub struct DeletePrefixReport {
+    /// Keys of objects that were deleted (or would be, in a dry run).
+    pub deleted: Vec<Vec<u8>>,
+    /// Total size in bytes of the deleted objects.
+    pub size: u64,
+    /// Per-object results of the delete transactions. Empty in a dry run.
+    pub results: BulkReport,
 }
 
 /// Update object metadata options.
@@ -86,18 +425,77 @@ pub struct UpdateObjectMetadataOptions {
 }
 
 /// Object get options.
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Debug)]
 pub struct GetOptions {
     /// Optional range of bytes to get from the object.
-    /// Format: "start-end" (inclusive).
-    /// Example: "0-99" (first 100 bytes).
-    /// This follows the HTTP range header format:
-    /// `<https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Range>`
-    pub range: Option<String>,
+    /// Example: `Range::Bounded(0, 99)` (first 100 bytes).
+    pub range: Option<Range>,
     /// Query block height.
     pub height: FvmQueryHeight,
     /// Whether to show progress-related output (useful for command-line interfaces).
     pub show_progress: bool,
+    /// Maximum time to wait for the object to be resolved before downloading it.
+    /// Resolution status is polled with an exponential backoff until the timeout.
+    /// If not specified, getting an object that isn't resolved yet results in an error.
+    pub resolve_timeout: Option<Duration>,
+    /// Optional clock that resolution polling is timed with.
+    /// If not specified, the system clock is used.
+    pub clock: Option<Arc<dyn Clock>>,
+    /// Optional local cache for object contents.
+    /// Whole-object gets are served from the cache when the object's hash is cached,
+    /// and populate it otherwise. Range gets bypass the cache.
+    pub cache: Option<ObjectCache>,
+    /// Optional sink for recording downloads.
+    pub audit: Option<Arc<dyn AuditSink>>,
+    /// Whether to skip decompressing objects added with a compression codec.
+    /// Range gets are never decompressed, since a range of compressed content can't be
+    /// decompressed on its own.
+    pub raw: bool,
+    /// Whether to skip checking downloaded content against the object's BLAKE3 hash.
+    /// Range gets are never checked, since the hash covers the whole object.
+    /// Content served from a cache was checked when it was cached.
+    pub skip_verify: bool,
+    /// Whether [`Bucket::get_to_path`] resumes into an existing partial file.
+    /// If the file is shorter than the object, only the missing bytes are downloaded with a
+    /// range request and written after the existing content. The whole file is then checked
+    /// against the object hash, unless `skip_verify` is set.
+    /// Ignored for range gets and for objects that are decompressed or served from a cache.
+    pub resume: bool,
+    /// Optional download rate limit in bytes per second.
+    /// Content served from a cache isn't limited.
+    pub rate_limit: Option<u64>,
+    /// Number of times to resume a download that fails mid-transfer.
+    /// Each attempt reissues a range request starting from the last received byte.
+    /// Defaults to [`DEFAULT_DOWNLOAD_RETRIES`].
+    pub retries: u32,
+    /// Time to wait before the first resume attempt, doubled for each attempt after it.
+    /// Defaults to one second.
+    pub retry_backoff: Option<Duration>,
+    /// How the content is downloaded.
+    /// With [`Transport::P2p`], content is fetched from the Object API's iroh node, which
+    /// holds uploaded content whether or not it's resolved, so `resolve_timeout` is ignored.
+    pub transport: Transport,
+}
+
+impl Default for GetOptions {
+    fn default() -> Self {
+        GetOptions {
+            range: Default::default(),
+            height: Default::default(),
+            show_progress: Default::default(),
+            resolve_timeout: Default::default(),
+            clock: Default::default(),
+            cache: Default::default(),
+            audit: Default::default(),
+            raw: Default::default(),
+            skip_verify: Default::default(),
+            resume: Default::default(),
+            rate_limit: Default::default(),
+            retries: DEFAULT_DOWNLOAD_RETRIES,
+            retry_backoff: Default::default(),
+            transport: Default::default(),
+        }
+    }
 }
 
 /// Object query options.
@@ -106,6 +504,9 @@ pub struct QueryOptions {
     /// The prefix to filter objects by.
     pub prefix: String,
     /// The delimiter used to define object hierarchy.
+    /// Keys under the prefix that contain the delimiter are grouped into common prefixes
+    /// ("directories") instead of being listed as objects.
+    /// An empty delimiter disables grouping, listing all keys under the prefix as objects.
     pub delimiter: String,
     /// The key to start listing objects from.
     pub start_key: Option<Vec<u8>>,
@@ -113,6 +514,12 @@ pub struct QueryOptions {
     pub limit: u64,
     /// Query block height.
     pub height: FvmQueryHeight,
+    /// Optional glob or regex pattern that listed object keys must match.
+    /// Objects are filtered client-side, so a page may have fewer than `limit` objects even
+    /// if there are more matches; follow `next_key` or use [`Bucket::query_stream`] to get
+    /// them all. Common prefixes aren't filtered.
+    /// With an empty delimiter, the literal prefix of a glob narrows `prefix` server-side.
+    pub pattern: Option<KeyPattern>,
 }
 
 impl Default for QueryOptions {
@@ -123,282 +530,2554 @@ impl Default for QueryOptions {
             start_key: Default::default(),
             limit: Default::default(),
             height: Default::default(),
+            pattern: Default::default(),
         }
     }
 }
 
-/// A machine for S3-like object storage.
-pub struct Bucket {
-    address: Address,
+/// Sharded object listing options.
+#[derive(Clone, Debug)]
+pub struct ShardedQueryOptions {
+    /// The prefix to list objects under.
+    pub prefix: String,
+    /// Key prefixes to list in parallel.
+    /// Shards should be disjoint and start with `prefix`; objects under `prefix` that aren't
+    /// in any shard aren't listed.
+    /// If empty, shards are derived from the common prefixes at the first `delimiter`
+    /// after `prefix`.
+    pub shards: Vec<String>,
+    /// The delimiter used to derive shards.
+    pub delimiter: String,
+    /// Maximum number of shards listed at once.
+    pub concurrency: usize,
+    /// Query block height.
+    pub height: FvmQueryHeight,
 }
 
-#[async_trait]
-impl Machine for Bucket {
-    const KIND: Kind = Kind::Bucket;
-
-    async fn new<C>(
-        provider: &impl Provider<C>,
-        signer: &mut impl Signer,
-        owner: Option<Address>,
-        metadata: HashMap<String, String>,
-        gas_params: GasParams,
-    ) -> anyhow::Result<(Self, TxResult<CreateExternalReturn>)>
-    where
-        C: Client + Send + Sync,
-    {
-        let (address, tx) =
-            deploy_machine(provider, signer, owner, Kind::Bucket, metadata, gas_params).await?;
-        let this = Self::attach(address).await?;
-        Ok((this, tx))
+impl Default for ShardedQueryOptions {
+    fn default() -> Self {
+        ShardedQueryOptions {
+            prefix: Default::default(),
+            shards: Default::default(),
+            delimiter: "/".into(),
+            concurrency: MAX_CONCURRENT_QUERIES,
+            height: Default::default(),
+        }
     }
+}
 
-    async fn attach(address: Address) -> anyhow::Result<Self> {
-        Ok(Bucket { address })
+/// A point-in-time record of a bucket's objects, for rolling back with [`Bucket::restore`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BucketSnapshot {
+    /// The bucket address.
+    pub address: Address,
+    /// The block height the snapshot was taken at.
+    pub height: u64,
+    /// The objects in key order.
+    pub objects: Vec<SnapshotObject>,
+}
+
+impl BucketSnapshot {
+    /// Encode the snapshot, e.g., to store it as an object.
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(fvm_ipld_encoding::to_vec(self)?)
     }
 
-    fn address(&self) -> Address {
-        self.address
+    /// Decode a snapshot encoded with [`BucketSnapshot::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(fvm_ipld_encoding::from_slice(bytes)?)
     }
 }
 
-impl Bucket {
-    /// Add an object into the bucket with a reader.
-    ///
-    /// Use [`Bucket::add_from_path`] for files.
-    #[allow(clippy::too_many_arguments)]
-    pub async fn add_reader<C, R>(
-        &self,
-        provider: &impl Provider<C>,
-        signer: &mut impl Signer,
-        from: Address,
-        key: &str,
-        reader: R,
-        size: u64,
-        options: AddOptions,
-    ) -> anyhow::Result<TxResult<Object>>
-    where
-        C: Client + Send + Sync,
-        R: AsyncRead + Unpin + Send + 'static,
-    {
-        let mut reader = AsyncPeekable::from(reader);
-        let mut buffer = [0u8; 40]; // 40 bytes is enough to detect the mime type
-        reader.peek(&mut buffer).await?;
-        let content_type = infer::get(&buffer[..]);
+/// An object recorded in a [`BucketSnapshot`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SnapshotObject {
+    /// The object key.
+    pub key: Vec<u8>,
+    /// The object content hash.
+    pub hash: Hash,
+    /// The object metadata hash used for recovery.
+    pub recovery_hash: Hash,
+    /// The object size in bytes.
+    pub size: u64,
+    /// The object metadata.
+    pub metadata: HashMap<String, String>,
+}
 
-        validate_metadata(&options.metadata)?;
-        let options = self.add_content_type_to_metadata(options, content_type);
+/// Snapshot restore options.
+#[derive(Clone, Default, Debug)]
+pub struct RestoreOptions {
+    /// Object time-to-live (TTL) duration for restored objects.
+    /// If not specified, the current default TTL from the config actor is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Delete objects that aren't in the snapshot.
+    pub delete_extra: bool,
+    /// Broadcast mode for the transactions.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transactions.
+    pub gas_params: GasParams,
+    /// Number of times to retry a transaction that fails with a transient error.
+    pub retries: u32,
+}
 
-        let started = Instant::now();
-        let bars = new_multi_bar(!options.show_progress);
-        let msg_bar = bars.add(new_message_bar());
-        let pro_bar = bars.add(new_progress_bar(size));
-        let upload_progress = pro_bar.clone();
+/// The outcome of restoring a [`BucketSnapshot`].
+#[derive(Clone, Debug, Default)]
+pub struct RestoreReport {
+    /// Keys that were re-added because they were missing or pointed at different content.
+    pub restored: Vec<Vec<u8>>,
+    /// Keys that were deleted because they weren't in the snapshot.
+    pub deleted: Vec<Vec<u8>>,
+    /// Per-object results of the add and delete transactions.
+    pub results: BulkReport,
+}
 
-        msg_bar.set_prefix("[1/2]");
-        msg_bar.set_message("Starting upload to server...");
+/// Archive import options.
+#[derive(Clone, Default, Debug)]
+pub struct ImportOptions {
+    /// Object time-to-live (TTL) duration for imported objects.
+    /// If not specified, the current default TTL from the config actor is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Overwrite objects that already exist.
+    pub overwrite: bool,
+    /// Broadcast mode for the transactions.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transactions.
+    pub gas_params: GasParams,
+}
 
-        let stream = ReaderStream::with_capacity(reader, 64 * 1024).map(move |result| {
-            result.inspect(|chunk| {
-                upload_progress.inc(chunk.len() as u64);
-            })
-        });
+/// The outcome of importing an archive with [`Bucket::import`].
+#[derive(Clone, Debug, Default)]
+pub struct ImportReport {
+    /// Keys of objects that were added.
+    pub imported: Vec<Vec<u8>>,
+    /// Per-object results of the add transactions.
+    pub results: BulkReport,
+}
 
-        let upload_response = provider
-            .upload(reqwest::Body::wrap_stream(stream), size)
-            .await?;
+/// Object copy options.
+#[derive(Clone, Default, Debug)]
+pub struct CopyOptions {
+    /// Object time-to-live (TTL) duration for the copy.
+    /// If not specified, the current default TTL from the config actor is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Metadata entries to set on the copy, replacing the source object's entries with the
+    /// same keys.
+    pub metadata: HashMap<String, String>,
+    /// Overwrite the destination object if it already exists.
+    pub overwrite: bool,
+    /// Query block height for getting the source object.
+    pub height: FvmQueryHeight,
+    /// Broadcast mode for the transaction.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transaction.
+    pub gas_params: GasParams,
+}
 
-        pro_bar.finish_and_clear();
-        msg_bar.set_message("Upload completed, processing response...");
+/// Directory sync options.
+#[derive(Clone, Default, Debug)]
+pub struct SyncOptions {
+    /// Delete objects under the prefix that have no corresponding local file.
+    pub delete: bool,
+    /// Only report the changes that would be made.
+    pub dry_run: bool,
+    /// Object time-to-live (TTL) duration for uploaded objects.
+    /// If not specified, the current default TTL from the config actor is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Query block height for listing objects.
+    pub height: FvmQueryHeight,
+    /// Broadcast mode for the transactions.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transactions.
+    pub gas_params: GasParams,
+    /// Number of times to retry a transaction that fails with a transient error.
+    pub retries: u32,
+}
 
-        let metadata_hash = IrohHash::from_str(&upload_response.metadata_hash)
-            .map_err(|_| anyhow!("Invalid metadata hash from server"))?;
-        let object_hash = IrohHash::from_str(&upload_response.hash)
-            .map_err(|_| anyhow!("Invalid object hash from server"))?;
+/// The outcome of syncing a directory with [`Bucket::sync`].
+#[derive(Clone, Debug, Default)]
+pub struct SyncReport {
+    /// Keys of files that were uploaded (or would be, in a dry run) because they were new or changed.
+    pub uploaded: Vec<String>,
+    /// Keys of objects that were deleted (or would be, in a dry run).
+    pub deleted: Vec<Vec<u8>>,
+    /// Number of files that matched their object and were skipped.
+    pub unchanged: u64,
+    /// Per-object results of the add and delete transactions. Empty in a dry run.
+    pub results: BulkReport,
+}
 
-        msg_bar.set_prefix("[2/2]");
-        msg_bar.set_message("Broadcasting transaction...");
+/// The difference between the objects in a bucket at two heights, returned by [`Bucket::diff`].
+///
+/// Keys are in ascending order.
+#[derive(Clone, Debug, Default)]
+pub struct BucketDiff {
+    /// Keys of objects that only exist at the later height.
+    pub added: Vec<Vec<u8>>,
+    /// Keys of objects that only exist at the earlier height.
+    pub removed: Vec<Vec<u8>>,
+    /// Keys of objects whose content hash or metadata changed.
+    pub changed: Vec<Vec<u8>>,
+}
 
-        let node_addr = provider.node_addr().await?;
-        let params = AddParams {
-            source: PublicKey(*node_addr.node_id.as_bytes()),
-            key: key.into(),
-            hash: Hash(*object_hash.as_bytes()),
-            recovery_hash: Hash(*metadata_hash.as_bytes()),
-            size,
-            ttl: options.ttl,
-            metadata: options.metadata,
-            overwrite: options.overwrite,
-            from,
-        };
+/// An object change in a bucket, yielded by [`Bucket::watch`].
+#[derive(Clone, Debug)]
+pub enum BucketEvent {
+    /// An object was added, or overwritten.
+    Added {
+        /// The object key.
+        key: Vec<u8>,
+        /// The object's content hash.
+        hash: Hash,
+        /// The object size in bytes.
+        size: u64,
+        /// User-defined object metadata.
+        metadata: HashMap<String, String>,
+        /// The height of the block that included the change.
+        height: u64,
+    },
+    /// An object was deleted.
+    Deleted {
+        /// The object key.
+        key: Vec<u8>,
+        /// The height of the block that included the change.
+        height: u64,
+    },
+}
 
-        let tx = signer
-            .send_transaction(
-                provider,
-                self.address,
-                options.token_amount.unwrap_or_default(),
-                AddObject as u64,
-                RawBytes::serialize(params)?,
-                options.gas_params,
-                options.broadcast_mode,
-                decode_as,
-            )
-            .await?;
+impl BucketEvent {
+    /// Returns the key of the changed object.
+    pub fn key(&self) -> &[u8] {
+        match self {
+            BucketEvent::Added { key, .. } | BucketEvent::Deleted { key, .. } => key,
+        }
+    }
 
-        msg_bar.println(format!(
-            "{} Added object in {} (hash={}; size={})",
-            SPARKLE,
-            HumanDuration(started.elapsed()),
-            object_hash,
-            size
-        ));
-        msg_bar.finish_and_clear();
-        Ok(tx)
+    /// Returns the height of the block that included the change.
+    pub fn height(&self) -> u64 {
+        match self {
+            BucketEvent::Added { height, .. } | BucketEvent::Deleted { height, .. } => *height,
+        }
     }
+}
 
-    /// Add an object into the bucket from a path.
-    pub async fn add_from_path<C>(
-        &self,
-        provider: &impl Provider<C>,
-        signer: &mut impl Signer,
-        from: Address,
-        key: &str,
-        path: impl AsRef<Path>,
-        options: AddOptions,
-    ) -> anyhow::Result<TxResult<Object>>
-    where
-        C: Client + Send + Sync,
-    {
+/// Object info returned by [`Bucket::head`].
+#[derive(Clone, Debug)]
+pub struct ObjectHead {
+    /// The object size in bytes, as stored (i.e., compressed if the object was compressed).
+    pub size: u64,
+    /// The content type detected when the object was added, if recorded.
+    pub content_type: Option<String>,
+    /// The codec the object was compressed with, if any.
+    pub compression: Option<Compression>,
+    /// Whether the object's content has been resolved and can be downloaded.
+    pub resolved: bool,
+}
+
+/// Object info returned by [`Bucket::stat`].
+///
+/// All objects are stored as blobs behind the Object API; there are no inline objects.
+#[derive(Clone, Debug)]
+pub struct ObjectInfo {
+    /// The object's content hash.
+    pub hash: Hash,
+    /// The hash of the object's erasure-coded recovery data.
+    pub recovery_hash: Hash,
+    /// The object size in bytes.
+    pub size: u64,
+    /// Whether the object's content has been resolved and can be downloaded.
+    pub resolved: bool,
+    /// User-defined object metadata.
+    pub metadata: HashMap<String, String>,
+}
+
+/// Defaults for the options of a bucket's operations (see [`BucketBuilder`]).
+///
+/// Every operation fills the fields of its options that are left at their default values
+/// with these, so options built with `Default::default()` pick them up.
+/// As a consequence, an option can't be explicitly set back to its default value when the
+/// bucket's default differs, e.g., [`BroadcastMode::Commit`] when the bucket defaults to
+/// [`BroadcastMode::Async`]. Start from [`Bucket::add_options`] and friends to see the
+/// effective values.
+#[derive(Clone, Default, Debug)]
+pub struct BucketDefaults {
+    /// Broadcast mode for transactions.
+    /// Operations that pipeline transactions only take it if it's [`BroadcastMode::Async`].
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for transactions.
+    pub gas_params: GasParams,
+    /// Whether to show progress-related output.
+    pub show_progress: bool,
+    /// Query block height for gets, listings, and copies.
+    pub height: FvmQueryHeight,
+    /// Optional sink for recording uploads and downloads.
+    pub audit: Option<Arc<dyn AuditSink>>,
+    /// How object content is downloaded.
+    pub transport: Transport,
+    /// Optional Object API endpoint that [`Bucket::download_url`] builds URLs with.
+    /// If not specified, the provider's primary endpoint is used.
+    /// Uploads and downloads always go through the provider's endpoints.
+    pub object_api_url: Option<Url>,
+    /// Optional iroh node that [`Transport::P2p`] downloads fetch content from.
+    /// If not specified, the Object API's iroh node is used.
+    pub node_addr: Option<NodeAddr>,
+}
+
+impl BucketDefaults {
+    /// Fill transaction options left at their default values.
+    fn fill_tx(&self, broadcast_mode: &mut BroadcastMode, gas_params: &mut GasParams) {
+        if *broadcast_mode == BroadcastMode::default() {
+            *broadcast_mode = self.broadcast_mode;
+        }
+        self.fill_gas_params(gas_params);
+    }
+
+    /// Fill options for pipelined transactions left at their default values.
+    /// Pipelined transactions default to [`BroadcastMode::Sync`] and can't be committed one
+    /// by one, so only [`BroadcastMode::Async`] is taken from the defaults.
+    fn fill_pipelined_tx(&self, broadcast_mode: &mut BroadcastMode, gas_params: &mut GasParams) {
+        if *broadcast_mode == BroadcastMode::Sync && self.broadcast_mode == BroadcastMode::Async {
+            *broadcast_mode = self.broadcast_mode;
+        }
+        self.fill_gas_params(gas_params);
+    }
+
+    fn fill_gas_params(&self, gas_params: &mut GasParams) {
+        if *gas_params == GasParams::default() {
+            *gas_params = self.gas_params.clone();
+        }
+    }
+
+    /// Fill a query height left at [`FvmQueryHeight::Committed`], the default.
+    fn fill_height(&self, height: &mut FvmQueryHeight) {
+        if matches!(height, FvmQueryHeight::Committed) {
+            *height = self.height;
+        }
+    }
+
+    /// Fill output options left at their default values.
+    fn fill_output(&self, show_progress: &mut bool, audit: &mut Option<Arc<dyn AuditSink>>) {
+        *show_progress |= self.show_progress;
+        if audit.is_none() {
+            audit.clone_from(&self.audit);
+        }
+    }
+}
+
+/// Options whose fields left at their default values are filled from a [`BucketDefaults`].
+trait WithDefaults {
+    fn with_defaults(self, defaults: &BucketDefaults) -> Self;
+}
+
+impl WithDefaults for AddOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        defaults.fill_output(&mut self.show_progress, &mut self.audit);
+        self
+    }
+}
+
+impl WithDefaults for AddManyOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_pipelined_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+impl WithDefaults for DeleteOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+impl WithDefaults for DeletePrefixOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_height(&mut self.height);
+        defaults.fill_pipelined_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+impl WithDefaults for UpdateObjectMetadataOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+impl WithDefaults for GetOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_height(&mut self.height);
+        defaults.fill_output(&mut self.show_progress, &mut self.audit);
+        if self.transport == Transport::default() {
+            self.transport = defaults.transport;
+        }
+        self
+    }
+}
+
+impl WithDefaults for RestoreOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+impl WithDefaults for ImportOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+impl WithDefaults for CopyOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_height(&mut self.height);
+        defaults.fill_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+impl WithDefaults for SyncOptions {
+    fn with_defaults(mut self, defaults: &BucketDefaults) -> Self {
+        defaults.fill_height(&mut self.height);
+        defaults.fill_tx(&mut self.broadcast_mode, &mut self.gas_params);
+        self
+    }
+}
+
+/// Builds a [`Bucket`] with defaults for its operations' options (see [`BucketDefaults`]).
+#[derive(Clone, Debug)]
+pub struct BucketBuilder {
+    address: Address,
+    defaults: BucketDefaults,
+    key_policy: Option<Arc<dyn KeyPolicy>>,
+}
+
+impl BucketBuilder {
+    /// Create a builder for the bucket at the given address.
+    pub fn new(address: Address) -> Self {
+        BucketBuilder {
+            address,
+            defaults: Default::default(),
+            key_policy: None,
+        }
+    }
+
+    /// Set the default broadcast mode for transactions.
+    pub fn broadcast_mode(mut self, broadcast_mode: BroadcastMode) -> Self {
+        self.defaults.broadcast_mode = broadcast_mode;
+        self
+    }
+
+    /// Set the default gas params for transactions.
+    pub fn gas_params(mut self, gas_params: GasParams) -> Self {
+        self.defaults.gas_params = gas_params;
+        self
+    }
+
+    /// Set whether to show progress-related output by default.
+    pub fn show_progress(mut self, show_progress: bool) -> Self {
+        self.defaults.show_progress = show_progress;
+        self
+    }
+
+    /// Set the default query block height for gets, listings, and copies.
+    pub fn height(mut self, height: FvmQueryHeight) -> Self {
+        self.defaults.height = height;
+        self
+    }
+
+    /// Set the default sink for recording uploads and downloads.
+    pub fn audit(mut self, audit: Arc<dyn AuditSink>) -> Self {
+        self.defaults.audit = Some(audit);
+        self
+    }
+
+    /// Set the default transport that object content is downloaded with.
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.defaults.transport = transport;
+        self
+    }
+
+    /// Set the Object API endpoint that download URLs are built with.
+    pub fn object_api_url(mut self, url: Url) -> Self {
+        self.defaults.object_api_url = Some(url);
+        self
+    }
+
+    /// Set the iroh node that peer-to-peer downloads fetch content from.
+    pub fn node_addr(mut self, node_addr: NodeAddr) -> Self {
+        self.defaults.node_addr = Some(node_addr);
+        self
+    }
+
+    /// Set the policy that keys are validated and normalized with before they're used to add,
+    /// get, or delete objects, and that query prefixes are normalized with.
+    /// [`StandardKeyPolicy::default`](crate::key_policy::StandardKeyPolicy) is a sane choice
+    /// for applications that embed user-supplied keys.
+    /// If not set, keys are used as given.
+    pub fn key_policy(mut self, key_policy: Arc<dyn KeyPolicy>) -> Self {
+        self.key_policy = Some(key_policy);
+        self
+    }
+
+    /// Build the bucket.
+    pub fn build(self) -> Bucket {
+        Bucket {
+            address: self.address,
+            defaults: self.defaults,
+            key_policy: self.key_policy,
+        }
+    }
+}
+
+/// A machine for S3-like object storage.
+pub struct Bucket {
+    address: Address,
+    defaults: BucketDefaults,
+    key_policy: Option<Arc<dyn KeyPolicy>>,
+}
+
+#[async_trait]
+impl Machine for Bucket {
+    const KIND: Kind = Kind::Bucket;
+
+    async fn new<C>(
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        owner: Option<Address>,
+        metadata: HashMap<String, String>,
+        gas_params: GasParams,
+    ) -> anyhow::Result<(Self, TxResult<MachineCreated>)>
+    where
+        C: Client + Send + Sync,
+    {
+        let (address, tx) =
+            deploy_machine(provider, signer, owner, Kind::Bucket, metadata, gas_params).await?;
+        let this = Self::attach(address).await?;
+        Ok((this, tx))
+    }
+
+    async fn attach(address: Address) -> anyhow::Result<Self> {
+        Ok(BucketBuilder::new(address).build())
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+}
+
+impl Bucket {
+    /// Returns a builder for a bucket at the given address with defaults for its operations.
+    pub fn builder(address: Address) -> BucketBuilder {
+        BucketBuilder::new(address)
+    }
+
+    /// Returns the defaults for the options of the bucket's operations.
+    pub fn defaults(&self) -> &BucketDefaults {
+        &self.defaults
+    }
+
+    /// Returns add options with the bucket's defaults.
+    pub fn add_options(&self) -> AddOptions {
+        AddOptions::default().with_defaults(&self.defaults)
+    }
+
+    /// Returns delete options with the bucket's defaults.
+    pub fn delete_options(&self) -> DeleteOptions {
+        DeleteOptions::default().with_defaults(&self.defaults)
+    }
+
+    /// Returns get options with the bucket's defaults.
+    pub fn get_options(&self) -> GetOptions {
+        GetOptions::default().with_defaults(&self.defaults)
+    }
+
+    /// Add an object into the bucket with a reader.
+    ///
+    /// Use [`Bucket::add_from_path`] for files.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_reader<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: &str,
+        reader: R,
+        size: u64,
+        options: AddOptions,
+    ) -> anyhow::Result<TxResult<AddReceipt>>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut reader = AsyncPeekable::from(reader);
+        let mut buffer = [0u8; 40]; // 40 bytes is enough to detect the mime type
+        let peeked = reader.peek(&mut buffer).await?;
+        let content_type = infer::get(&buffer[..peeked]);
+
+        if let Some(codec) = options.compression {
+            // Compressed content is never empty, so check the original size
+            check_empty(size, &options)?;
+            let (file, hash, size) = codec.compress(reader).await?;
+            self.add_upload(
+                provider,
+                signer,
+                from,
+                key,
+                file,
+                size,
+                content_type,
+                options,
+                Some(hash),
+            )
+            .await
+        } else if options.dedup.is_some() || options.resume_from.is_some() {
+            let (file, hash) = spool_and_hash(reader).await?;
+            self.add_upload(
+                provider,
+                signer,
+                from,
+                key,
+                file,
+                size,
+                content_type,
+                options,
+                Some(hash),
+            )
+            .await
+        } else {
+            self.add_upload(
+                provider,
+                signer,
+                from,
+                key,
+                reader,
+                size,
+                content_type,
+                options,
+                None,
+            )
+            .await
+        }
+    }
+
+    /// Upload an object and broadcast the add transaction.
+    /// `content_hash` is required to deduplicate or checkpoint the upload.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(
+        name = "add",
+        skip_all,
+        fields(bucket = %self.address, key = %key, size = size)
+    )]
+    async fn add_upload<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: &str,
+        reader: R,
+        size: u64,
+        content_type: Option<Type>,
+        mut options: AddOptions,
+        content_hash: Option<[u8; 32]>,
+    ) -> anyhow::Result<TxResult<AddReceipt>>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        options = options.with_defaults(&self.defaults);
+        let key = self.check_key_str(key)?;
+        let key = key.as_ref();
+        check_empty(size, &options)?;
+        if let Some(codec) = options.compression {
+            options
+                .metadata
+                .insert(COMPRESSION_METADATA_KEY.into(), codec.to_string());
+        }
+        if options.immutable {
+            options
+                .metadata
+                .insert(IMMUTABLE_METADATA_KEY.into(), true.to_string());
+        }
+        if options.stamp_created_at {
+            let now = unix_seconds(clock_or_system(&options.clock).as_ref())?;
+            options
+                .metadata
+                .entry(CREATED_AT_METADATA_KEY.into())
+                .or_insert_with(|| now.to_string());
+        }
+        validate_metadata(&options.metadata)?;
+        if let Some(precondition) = &options.precondition {
+            self.check_precondition(provider, key, precondition).await?;
+        }
+        let overwrites = match &options.precondition {
+            Some(Precondition::IfMatch(_)) => true,
+            Some(Precondition::IfNotExists) => false,
+            None => options.overwrite,
+        };
+        if overwrites {
+            self.check_mutable(provider, key.as_bytes()).await?;
+        }
+        if options.check_admission {
+            Cost::check_admission(
+                provider,
+                from,
+                size,
+                options.ttl,
+                options.token_amount.as_ref(),
+                FvmQueryHeight::Committed,
+            )
+            .await?;
+        }
+        if options.dry_run {
+            let estimate = Cost::estimate_add(
+                provider,
+                from,
+                self,
+                key,
+                size,
+                EstimateOptions {
+                    ttl: options.ttl,
+                    gas_params: options.gas_params,
+                    height: FvmQueryHeight::Committed,
+                },
+            )
+            .await?;
+            return Ok(TxResult::dry_run(estimate.into()));
+        }
+        let quota_charge = match &options.quota {
+            Some(quota) => {
+                Some(quota.charge(size, 1, options.token_amount.clone().unwrap_or_default())?)
+            }
+            None => None,
+        };
+        let options = self.add_content_type_to_metadata(options, content_type);
+
+        let started = Instant::now();
+        let bars = new_multi_bar(!options.show_progress);
+        let msg_bar = bars.add(new_message_bar());
+        let pro_bar = bars.add(new_progress_bar(size));
+        let upload_progress = pro_bar.clone();
+
+        msg_bar.set_prefix("[1/2]");
+        msg_bar.set_message("Starting upload to server...");
+
+        let stream = ReaderStream::with_capacity(reader, 64 * 1024).map(move |result| {
+            result.inspect(|chunk| {
+                upload_progress.inc(chunk.len() as u64);
+            })
+        });
+        let stream = throttle(stream, options.rate_limit, Arc::new(SystemClock));
+
+        let now = unix_seconds(clock_or_system(&options.clock).as_ref())?;
+        let checkpoint = match (&options.resume_from, content_hash) {
+            (Some(path), Some(hash)) => load_upload_checkpoint(path, &hash, size, now).await,
+            _ => None,
+        };
+        let resumed = checkpoint.is_some();
+        let body = reqwest::Body::wrap_stream(stream);
+        let upload_result = match (checkpoint, options.dedup.clone(), content_hash) {
+            (Some(response), _, _) => {
+                tracing::debug!(key, "resuming add from upload checkpoint");
+                Ok(response)
+            }
+            (None, Some(dedup), Some(hash)) => {
+                dedup
+                    .upload(hash, move || provider.upload(body, size))
+                    .await
+            }
+            _ => provider.upload(body, size).await,
+        };
+        let mut event = AuditEvent::transfer(
+            AuditKind::Upload,
+            self.address,
+            key.into(),
+            upload_result.as_ref().ok().map(|r| r.hash.clone()),
+            &upload_result
+                .as_ref()
+                .map(|_| size)
+                .map_err(|e| anyhow!("{}", e)),
+        );
+        event.from = Some(from.to_string());
+        audit::record(options.audit.as_deref(), event).await;
+        let upload_response = upload_result?;
+        if let (Some(path), Some(hash), false) = (&options.resume_from, content_hash, resumed) {
+            save_upload_checkpoint(path, &hash, size, now, &upload_response).await?;
+        }
+
+        pro_bar.finish_and_clear();
+        msg_bar.set_message("Upload completed, processing response...");
+        tracing::debug!(elapsed = ?started.elapsed(), size, "uploaded object");
+
+        let metadata_hash = IrohHash::from_str(&upload_response.metadata_hash)
+            .map_err(|_| anyhow!("Invalid metadata hash from server"))?;
+        let object_hash = IrohHash::from_str(&upload_response.hash)
+            .map_err(|_| anyhow!("Invalid object hash from server"))?;
+
+        msg_bar.set_prefix("[2/2]");
+        msg_bar.set_message("Broadcasting transaction...");
+
+        let overwrite = match &options.precondition {
+            Some(precondition @ Precondition::IfMatch(_)) => {
+                self.check_precondition(provider, key, precondition).await?;
+                true
+            }
+            Some(Precondition::IfNotExists) => false,
+            None => options.overwrite,
+        };
+        // The actor doesn't return the object an add replaces, so look it up beforehand
+        let replaced = if overwrite {
+            self.check_mutable(provider, key.as_bytes()).await?
+        } else {
+            None
+        };
+        let node_addr = provider.node_addr().await?;
+        let params = AddParams {
+            source: PublicKey(*node_addr.node_id.as_bytes()),
+            key: key.into(),
+            hash: Hash(*object_hash.as_bytes()),
+            recovery_hash: Hash(*metadata_hash.as_bytes()),
+            size,
+            ttl: options.ttl,
+            metadata: options.metadata,
+            overwrite,
+            from,
+        };
+
+        let tx = signer
+            .send_transaction(
+                provider,
+                self.address,
+                options.token_amount.unwrap_or_default(),
+                AddObject as u64,
+                RawBytes::serialize(params)?,
+                options.gas_params,
+                options.broadcast_mode,
+                move |deliver_tx: &DeliverTx| -> anyhow::Result<AddReceipt> {
+                    Ok(AddReceipt {
+                        object: decode_as(deliver_tx)?,
+                        replaced,
+                    })
+                },
+            )
+            .await?;
+        tracing::debug!(elapsed = ?started.elapsed(), key, "broadcasted add object transaction");
+        if let Some(charge) = quota_charge {
+            charge.commit();
+        }
+        if let Some(path) = &options.resume_from {
+            match tokio::fs::remove_file(path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        msg_bar.println(format!(
+            "{} Added object in {} (hash={}; size={})",
+            SPARKLE,
+            HumanDuration(started.elapsed()),
+            object_hash,
+            size
+        ));
+        msg_bar.finish_and_clear();
+        Ok(tx)
+    }
+
+    /// Add an object into the bucket from a path.
+    ///
+    /// Unlike [`Bucket::add_reader`], the size and content type are read from the file
+    /// directly, and the content is hashed by memory-mapping the file when deduplicating
+    /// or checkpointing the upload.
+    pub async fn add_from_path<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: &str,
+        path: impl AsRef<Path>,
+        options: AddOptions,
+    ) -> anyhow::Result<TxResult<AddReceipt>>
+    where
+        C: Client + Send + Sync,
+    {
         let path = path
             .as_ref()
             .canonicalize()
             .map_err(|e| anyhow!("failed to resolve path: {}", e))?;
 
-        let mut file = tokio::fs::File::open(&path).await?;
+        // The size is known from the file metadata, so the file is never seeked or rewound
+        let total_size = tokio::fs::metadata(&path).await?.len();
+        if total_size > MAX_OBJECT_LENGTH {
+            return Err(anyhow!("file exceeds maximum allowed size of 5 GB"));
+        }
+        let content_type = infer::get_from_path(&path)?;
+
+        if let Some(codec) = options.compression {
+            // Compressed content is never empty, so check the original size
+            check_empty(total_size, &options)?;
+            let file = tokio::fs::File::open(&path).await?;
+            let (file, hash, size) = codec.compress(file).await?;
+            return self
+                .add_upload(
+                    provider,
+                    signer,
+                    from,
+                    key,
+                    file,
+                    size,
+                    content_type,
+                    options,
+                    Some(hash),
+                )
+                .await;
+        }
+
+        // Files don't need spooling to be hashed for deduplication or checkpoints
+        let content_hash = if options.dedup.is_some() || options.resume_from.is_some() {
+            Some(hash_file(&path).await?)
+        } else {
+            None
+        };
+
+        let file = tokio::fs::File::open(&path).await?;
+        self.add_upload(
+            provider,
+            signer,
+            from,
+            key,
+            file,
+            total_size,
+            content_type,
+            options,
+            content_hash,
+        )
+        .await
+    }
+
+    /// Rewrite an existing object with content from a reader added to its end.
+    ///
+    /// This is a full rewrite, not an append: objects are content-addressed blobs, and neither
+    /// the bucket actor nor the Object API can extend one or write a range of one in place. The
+    /// existing content is streamed back from the Object API and re-uploaded ahead of the new
+    /// bytes, so each call transfers the whole object twice and costs O(object size). It doesn't
+    /// suit log-style workloads that write often; write each record to its own key instead.
+    ///
+    /// The content isn't buffered locally. The new object is added with
+    /// [`Precondition::IfMatch`] on the existing hash, so a rewrite that races another write
+    /// fails with a [`PreconditionFailedError`] instead of losing it.
+    /// The existing metadata is kept, extended with `options.metadata`.
+    /// Compressed objects can't be rewritten this way.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn rewrite_with_suffix<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: &str,
+        reader: R,
+        size: u64,
+        mut options: AddOptions,
+    ) -> anyhow::Result<TxResult<AddReceipt>>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let key = self.check_key_str(key)?;
+        let key = key.as_ref();
+        let object = self
+            .get_object(provider, key.as_bytes(), FvmQueryHeight::Committed)
+            .await?
+            .value
+            .ok_or_else(|| anyhow!("object not found for key '{}'", key))?;
+        if Compression::from_metadata(&object.metadata)?.is_some() || options.compression.is_some()
+        {
+            return Err(anyhow!("can't rewrite compressed object '{}'", key));
+        }
+
+        // The combined reader must be 'static, so the existing content is streamed without
+        // resuming, which would borrow the provider
+        let get_options = GetOptions {
+            raw: true,
+            rate_limit: options.rate_limit,
+            ..Default::default()
+        };
+        let chunks = self
+            .open_chunks(provider, key.as_bytes(), &object, &get_options)
+            .await?;
+        let existing = decode_chunks(
+            chunks,
+            new_verifier(&get_options),
+            None,
+            key.as_bytes().to_vec(),
+            object.clone(),
+        );
+        let existing =
+            StreamReader::new(existing.map_err(|e| std::io::Error::other(format!("{:#}", e))));
+
+        let mut metadata = object.metadata;
+        metadata.extend(options.metadata);
+        options.metadata = metadata;
+        options.precondition = Some(Precondition::IfMatch(object.hash));
+        tracing::debug!(
+            key,
+            existing = object.size,
+            suffix = size,
+            "rewriting object with suffix"
+        );
+        self.add_reader(
+            provider,
+            signer,
+            from,
+            key,
+            existing.chain(reader),
+            object.size + size,
+            options,
+        )
+        .await
+    }
+
+    /// Add many objects into the bucket with readers of `(key, reader, size)`.
+    ///
+    /// Objects are uploaded concurrently, and the add transactions are then signed and
+    /// broadcasted back-to-back (see [`Wallet::send_pipelined`]), so adding many small objects
+    /// doesn't wait on a block per object.
+    /// Results are reported per object, in the order of `objects`. Objects that fail to upload
+    /// are reported as failed, and the rest are still added. If a transaction fails, it and the
+    /// objects after it are reported as failed, and the objects before it as added.
+    pub async fn add_many<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut Wallet,
+        from: Address,
+        objects: Vec<(String, R, u64)>,
+        mut options: AddManyOptions,
+    ) -> anyhow::Result<BulkReport>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        options = options.with_defaults(&self.defaults);
+        if options.stamp_created_at {
+            let now = unix_seconds(clock_or_system(&options.clock).as_ref())?;
+            options
+                .metadata
+                .entry(CREATED_AT_METADATA_KEY.into())
+                .or_insert_with(|| now.to_string());
+        }
+        validate_metadata(&options.metadata)?;
+        let objects = objects
+            .into_iter()
+            .map(|(key, reader, size)| {
+                let key = self.check_key_str(&key)?.into_owned();
+                anyhow::Ok((key, reader, size))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if !options.allow_empty {
+            if let Some((key, _, _)) = objects.iter().find(|(_, _, size)| *size == 0) {
+                return Err(anyhow!(
+                    "object '{}' is empty; set allow_empty to add zero-length objects",
+                    key
+                ));
+            }
+        }
+
+        let started = Instant::now();
+        let count = objects.len();
+        let overwrite = options.overwrite;
+        let node_addr = provider.node_addr().await?;
+        let uploads: Vec<_> = stream::iter(objects)
+            .map(|(key, reader, size)| async move {
+                let upload = async {
+                    if overwrite {
+                        self.check_mutable(provider, key.as_bytes()).await?;
+                    }
+                    let mut reader = AsyncPeekable::from(reader);
+                    let mut buffer = [0u8; 40]; // 40 bytes is enough to detect the mime type
+                    let peeked = reader.peek(&mut buffer).await?;
+                    let content_type = infer::get(&buffer[..peeked]);
+
+                    let body =
+                        reqwest::Body::wrap_stream(ReaderStream::with_capacity(reader, 64 * 1024));
+                    let response = provider
+                        .upload(body, size)
+                        .await
+                        .map_err(|e| anyhow!("failed to upload object '{}': {}", key, e))?;
+                    anyhow::Ok((content_type, response))
+                };
+                let upload = upload.await;
+                (key, size, upload)
+            })
+            .buffered(options.concurrency.max(1))
+            .collect()
+            .await;
+        tracing::debug!(elapsed = ?started.elapsed(), count, "uploaded objects");
+
+        // Results are filled in object order; queued holds the object index of each transaction
+        let mut items: Vec<Option<BulkItemResult>> = vec![None; uploads.len()];
+        let mut keys = Vec::with_capacity(uploads.len());
+        let mut queued = Vec::with_capacity(uploads.len());
+        let mut txs = Vec::with_capacity(uploads.len());
+        for (i, (key, size, upload)) in uploads.into_iter().enumerate() {
+            let tx = upload.and_then(|(content_type, response)| {
+                let hash = IrohHash::from_str(&response.hash)
+                    .map_err(|_| anyhow!("Invalid object hash from server"))?;
+                let metadata_hash = IrohHash::from_str(&response.metadata_hash)
+                    .map_err(|_| anyhow!("Invalid metadata hash from server"))?;
+                let mut metadata = options.metadata.clone();
+                metadata.insert(
+                    "content-type".into(),
+                    content_type
+                        .map_or("application/octet-stream".into(), |t| t.mime_type().into()),
+                );
+                let params = AddParams {
+                    source: PublicKey(*node_addr.node_id.as_bytes()),
+                    key: key.as_str().into(),
+                    hash: Hash(*hash.as_bytes()),
+                    recovery_hash: Hash(*metadata_hash.as_bytes()),
+                    size,
+                    ttl: options.ttl,
+                    metadata,
+                    overwrite: options.overwrite,
+                    from,
+                };
+                anyhow::Ok(QueuedTransaction {
+                    to: self.address,
+                    value: Default::default(),
+                    method_num: AddObject as u64,
+                    params: RawBytes::serialize(params)?,
+                    gas_params: options.gas_params.clone(),
+                })
+            });
+            match tx {
+                Ok(tx) => {
+                    queued.push(i);
+                    txs.push(tx);
+                }
+                Err(e) => {
+                    items[i] = ItemAttempts::new(key.as_bytes(), 0).record::<()>(Err(e));
+                }
+            }
+            keys.push(key);
+        }
+
+        let (results, mut failed) = if txs.is_empty() {
+            (Vec::new(), None)
+        } else {
+            match signer
+                .send_pipelined(provider, txs, options.broadcast_mode)
+                .await
+            {
+                Ok(report) => (report.results, report.failed.map(|(_, e)| e)),
+                Err(e) => (Vec::new(), Some(e)),
+            }
+        };
+        let sent = results.len();
+        for (&i, tx) in queued.iter().zip(results) {
+            items[i] = ItemAttempts::new(keys[i].as_bytes(), 0).record(Ok(tx));
+        }
+        // The failed transaction is the first one that wasn't sent
+        for &i in queued.iter().skip(sent) {
+            let err = failed.take().unwrap_or_else(|| {
+                anyhow!("not sent because an earlier transaction in the batch failed")
+            });
+            items[i] = ItemAttempts::new(keys[i].as_bytes(), 0).record::<()>(Err(err));
+        }
+        tracing::debug!(elapsed = ?started.elapsed(), count, "broadcasted add object transactions");
+        Ok(BulkReport {
+            items: items
+                .into_iter()
+                .map(|item| item.expect("a final result"))
+                .collect(),
+        })
+    }
+
+    /// Delete an object.
+    #[tracing::instrument(
+        name = "delete",
+        skip_all,
+        fields(bucket = %self.address, key = %display_key(key.as_ref()))
+    )]
+    pub async fn delete<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: impl AsRef<[u8]>,
+        options: DeleteOptions,
+    ) -> anyhow::Result<TxResult<DeleteReceipt>>
+    where
+        C: Client + Send + Sync,
+    {
+        let options = options.with_defaults(&self.defaults);
+        let key = self.check_key(key.as_ref())?;
+        // The actor doesn't return the object a delete removes, so look it up beforehand
+        let deleted = self.check_mutable(provider, &key).await?;
+        let params = DeleteParams {
+            key: key.as_ref().into(),
+            from,
+        };
+        let params = RawBytes::serialize(params)?;
+        if options.dry_run {
+            let estimate = Cost::estimate_tx(
+                provider,
+                from,
+                self.address,
+                DeleteObject as u64,
+                params,
+                &options.gas_params,
+                FvmQueryHeight::Committed,
+            )
+            .await?;
+            return Ok(TxResult::dry_run(estimate));
+        }
+        signer
+            .send_transaction(
+                provider,
+                self.address,
+                Default::default(),
+                DeleteObject as u64,
+                params,
+                options.gas_params,
+                options.broadcast_mode,
+                move |_: &DeliverTx| -> anyhow::Result<DeleteReceipt> {
+                    Ok(DeleteReceipt { deleted })
+                },
+            )
+            .await
+    }
+
+    /// Delete all objects under a prefix, like `aws s3 rm --recursive`.
+    ///
+    /// All keys under the prefix are listed first, at a single height, and then deleted in
+    /// batches of at most `options.concurrency` transactions that are signed and broadcasted
+    /// back-to-back (see [`Wallet::send_pipelined`]).
+    /// If a transaction in a batch fails, the keys before it are reported as deleted, and it and
+    /// the rest of the batch are reported as failed. Deleting the prefix again picks up what's
+    /// left.
+    /// An empty prefix deletes every object in the bucket.
+    /// Immutable objects aren't deleted, and are reported as failed.
+    pub async fn delete_prefix<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut Wallet,
+        from: Address,
+        prefix: &str,
+        options: DeletePrefixOptions,
+    ) -> anyhow::Result<DeletePrefixReport>
+    where
+        C: Client + Send + Sync,
+    {
+        let options = options.with_defaults(&self.defaults);
+        let started = Instant::now();
+        let objects = self.list_all(provider, prefix, options.height).await?;
+
+        let mut report = DeletePrefixReport::default();
+        let (immutable, objects): (Vec<_>, Vec<_>) = objects
+            .into_iter()
+            .partition(|(_, object)| is_immutable(&object.metadata));
+        for (key, _) in immutable {
+            let err = ImmutableObjectError {
+                key: display_key(&key),
+            };
+            let result = ItemAttempts::new(&key, 0)
+                .record::<()>(Err(err.into()))
+                .expect("a final result");
+            report.results.items.push(result);
+        }
+        if options.dry_run {
+            for (key, object) in objects {
+                report.size += object.size;
+                report.deleted.push(key);
+            }
+            return Ok(report);
+        }
+
+        for batch in objects.chunks(options.concurrency.max(1)) {
+            let mut txs = Vec::with_capacity(batch.len());
+            for (key, _) in batch {
+                let params = DeleteParams {
+                    key: key.as_slice().into(),
+                    from,
+                };
+                txs.push(QueuedTransaction {
+                    to: self.address,
+                    value: Default::default(),
+                    method_num: DeleteObject as u64,
+                    params: RawBytes::serialize(params)?,
+                    gas_params: options.gas_params.clone(),
+                });
+            }
+            let (results, mut failed) = match signer
+                .send_pipelined(provider, txs, options.broadcast_mode)
+                .await
+            {
+                Ok(report) => (report.results, report.failed.map(|(_, e)| e)),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            let sent = results.len();
+            for ((key, object), tx) in batch.iter().zip(results) {
+                let result = ItemAttempts::new(key, 0)
+                    .record(Ok(tx))
+                    .expect("a final result");
+                report.size += object.size;
+                report.deleted.push(key.clone());
+                report.results.items.push(result);
+            }
+            // The failed transaction is the first one that wasn't sent
+            for (key, _) in batch.iter().skip(sent) {
+                let err = failed.take().unwrap_or_else(|| {
+                    anyhow!("not sent because an earlier transaction in the batch failed")
+                });
+                let result = ItemAttempts::new(key, 0)
+                    .record::<()>(Err(err))
+                    .expect("a final result");
+                report.results.items.push(result);
+            }
+        }
+        tracing::debug!(
+            elapsed = ?started.elapsed(),
+            prefix,
+            deleted = report.deleted.len(),
+            failed = report.results.failed().count(),
+            "deleted objects under prefix"
+        );
+        Ok(report)
+    }
+
+    /// Get an object's info, including its user-defined metadata and whether its content has
+    /// been resolved, without downloading it.
+    ///
+    /// The object's info is queried from the bucket, and resolution is checked with a HEAD
+    /// request to the Object API, which doesn't transfer any content.
+    /// Returns `None` if the object doesn't exist.
+    pub async fn stat(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<ObjectInfo>> {
+        let key = self.check_key(key.as_ref())?;
+        let key = key.as_ref();
+        let Some(object) = self.find_object(provider, key, height).await? else {
+            return Ok(None);
+        };
+        let resolved = self.is_resolved(provider, key, height).await?;
+        Ok(Some(ObjectInfo {
+            hash: object.hash,
+            recovery_hash: object.recovery_hash,
+            size: object.size,
+            resolved,
+            metadata: object.metadata,
+        }))
+    }
+
+    /// Returns whether an object exists at the given key.
+    ///
+    /// Only the object's info is queried; its content isn't checked to be resolved
+    /// (see [`Bucket::stat`]).
+    pub async fn exists(
+        &self,
+        provider: &impl QueryProvider,
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<bool> {
+        Ok(self.find_object(provider, key, height).await?.is_some())
+    }
+
+    /// Get an object's size, kind, and resolution status without downloading it.
+    ///
+    /// A summary of [`Bucket::stat`], with the content type and compression codec decoded
+    /// from the object's metadata.
+    /// Returns `None` if the object doesn't exist.
+    pub async fn head(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<ObjectHead>> {
+        let Some(info) = self.stat(provider, key, height).await? else {
+            return Ok(None);
+        };
+        Ok(Some(ObjectHead {
+            size: info.size,
+            content_type: info.metadata.get("content-type").cloned(),
+            compression: Compression::from_metadata(&info.metadata)?,
+            resolved: info.resolved,
+        }))
+    }
+
+    /// Returns a URL that the object at the given key can be downloaded from.
+    ///
+    /// Objects are publicly readable, so the URL can be handed out to give read access to a
+    /// single object without sharing keys. Set a specific `height` to pin the URL to the object
+    /// as of that height; otherwise, the URL serves the latest version.
+    /// This only partly stands in for presigned URLs: the Object API supports neither signed
+    /// nor expiring URLs, so the URL can't be revoked and grants no more than the object's
+    /// address and key already do. The URL isn't checked to exist.
+    /// URLs point at [`BucketDefaults::object_api_url`] if it's set.
+    pub fn download_url(
+        &self,
+        provider: &impl ObjectProvider,
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<reqwest::Url> {
+        let key = self.check_key(key.as_ref())?;
+        match &self.defaults.object_api_url {
+            Some(endpoint) => Ok(reqwest::Url::parse(&object_url(
+                endpoint,
+                self.address,
+                &key,
+                height.into(),
+            ))?),
+            None => provider.download_url(self.address, &key, height.into()),
+        }
+    }
+
+    /// Get an object at the given key, range, and height.
+    #[tracing::instrument(
+        name = "get",
+        skip_all,
+        fields(bucket = %self.address, key = %display_key(key.as_ref()))
+    )]
+    pub async fn get<W>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        writer: W,
+        options: GetOptions,
+    ) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let options = options.with_defaults(&self.defaults);
+        let key = self.check_key(key.as_ref())?;
+        let key = key.as_ref();
+        let started = Instant::now();
+        let bars = new_multi_bar(!options.show_progress);
+        let msg_bar = bars.add(new_message_bar());
+
+        msg_bar.set_prefix("[1/2]");
+        msg_bar.set_message("Getting object info...");
+        let response = self.get_object(provider, key, options.height).await?;
+        let object = response
+            .value
+            .ok_or_else(|| anyhow!("object not found for key '{}'", display_key(key)))?;
+        tracing::debug!(elapsed = ?started.elapsed(), key = %display_key(key), "fetched object info");
+
+        msg_bar.set_prefix("[2/2]");
+        msg_bar.set_message(format!(
+            "Downloading object (hash={}; size={})",
+            object.hash, object.size
+        ));
+
+        let len = options
+            .range
+            .map_or(object.size, |range| range.len(object.size));
+        let pro_bar = bars.add(new_progress_bar(len));
+        let progress = self
+            .download(provider, key, &object, writer, &options, |p| {
+                pro_bar.set_position(p)
+            })
+            .await?;
+        pro_bar.finish_and_clear();
+        tracing::debug!(elapsed = ?started.elapsed(), key = %display_key(key), size = progress, "downloaded object");
+        msg_bar.println(format!(
+            "{} Downloaded object in {} (hash={}; size={})",
+            SPARKLE,
+            HumanDuration(started.elapsed()),
+            object.hash,
+            object.size
+        ));
+
+        msg_bar.finish_and_clear();
+        Ok(())
+    }
+
+    /// Get an object at the given key, range, and height as a stream of bytes.
+    ///
+    /// The stream yields the same content [`Bucket::get`] writes: objects added with a
+    /// compression codec are decompressed, and the content is checked against the object hash
+    /// unless the options say otherwise. A hash mismatch is yielded as the last item.
+    /// A download that fails mid-transfer is resumed as for [`Bucket::get`].
+    /// Streams bypass the cache and audit sink in the options.
+    #[tracing::instrument(
+        name = "get_stream",
+        skip_all,
+        fields(bucket = %self.address, key = %display_key(key.as_ref()))
+    )]
+    pub async fn get_stream<'a, P>(
+        &'a self,
+        provider: &'a P,
+        key: impl AsRef<[u8]>,
+        options: GetOptions,
+    ) -> anyhow::Result<BoxStream<'a, anyhow::Result<Bytes>>>
+    where
+        P: QueryProvider + ObjectProvider,
+    {
+        let options = options.with_defaults(&self.defaults);
+        let key = self.check_key(key.as_ref())?.into_owned();
+        let object = self
+            .get_object(provider, &key, options.height)
+            .await?
+            .value
+            .ok_or_else(|| anyhow!("object not found for key '{}'", display_key(&key)))?;
+        let codec = decompression(&object, &options)?;
+        let hasher = new_verifier(&options);
+        let download =
+            ResumableDownload::open(self, provider, key.clone(), object.clone(), options).await?;
+        let chunks = stream::unfold(download, |mut download| async move {
+            let chunk = download.next().await?;
+            Some((chunk, download))
+        })
+        .boxed();
+        Ok(decode_chunks(chunks, hasher, codec, key, object))
+    }
+
+    /// Get an object at the given key, range, and height, and write it to a file at `path`.
+    ///
+    /// Downloaded chunks are written to the file with vectored writes directly from the
+    /// response buffers, avoiding the copies incurred through the generic [`AsyncWrite`] path.
+    /// When getting the whole object, the file is preallocated to the object size, and it's
+    /// trimmed to the content written so far if the download fails.
+    /// Set [`GetOptions::resume`] to continue an interrupted download into the same file.
+    #[tracing::instrument(
+        name = "get",
+        skip_all,
+        fields(bucket = %self.address, key = %display_key(key.as_ref()))
+    )]
+    pub async fn get_to_path(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        path: impl AsRef<Path>,
+        options: GetOptions,
+    ) -> anyhow::Result<()> {
+        let options = options.with_defaults(&self.defaults);
+        let key = self.check_key(key.as_ref())?;
+        let key = key.as_ref();
+        let started = Instant::now();
+        let bars = new_multi_bar(!options.show_progress);
+        let msg_bar = bars.add(new_message_bar());
+
+        msg_bar.set_prefix("[1/2]");
+        msg_bar.set_message("Getting object info...");
+        let response = self.get_object(provider, key, options.height).await?;
+        let object = response
+            .value
+            .ok_or_else(|| anyhow!("object not found for key '{}'", display_key(key)))?;
+        tracing::debug!(elapsed = ?started.elapsed(), key = %display_key(key), "fetched object info");
+
+        msg_bar.set_prefix("[2/2]");
+        msg_bar.set_message(format!(
+            "Downloading object (hash={}; size={})",
+            object.hash, object.size
+        ));
+
+        let path = path.as_ref();
+        let compressed = decompression(&object, &options)?.is_some();
+        let cached = options.cache.is_some() && options.range.is_none();
+        let offset = if options.resume && options.range.is_none() && !cached && !compressed {
+            partial_len(path, object.size).await
+        } else {
+            0
+        };
+        let file = if offset > 0 {
+            tracing::debug!(key = %display_key(key), offset, "resuming download into partial file");
+            tokio::fs::OpenOptions::new().write(true).open(path).await?
+        } else {
+            tokio::fs::File::create(path).await?
+        };
+        if cached || compressed {
+            let pro_bar = bars.add(new_progress_bar(object.size));
+            let written = self
+                .download(provider, key, &object, file, &options, |p| {
+                    pro_bar.set_position(p)
+                })
+                .await?;
+            pro_bar.finish_and_clear();
+            tracing::debug!(elapsed = ?started.elapsed(), key = %display_key(key), size = written, "downloaded object to file");
+            msg_bar.println(format!(
+                "{} Downloaded object in {} (hash={}; size={})",
+                SPARKLE,
+                HumanDuration(started.elapsed()),
+                object.hash,
+                object.size
+            ));
+            msg_bar.finish_and_clear();
+            return Ok(());
+        }
+        if options.range.is_none() {
+            file.set_len(object.size).await?;
+        }
+        let mut file = file.into_std().await;
+        file.seek(SeekFrom::Start(offset))?;
+
+        let pro_bar = bars.add(new_progress_bar(object.size));
+        pro_bar.set_position(offset);
+        // The length of the content written to the file so far
+        let mut flushed = offset;
+        let result = async {
+            let mut hasher = new_verifier(&options);
+            let mut download_options = options.clone();
+            if offset > 0 {
+                if let Some(hasher) = &mut hasher {
+                    *hasher = hash_prefix(path, offset).await?;
+                }
+                download_options.range = Some(Range::FromStart(offset));
+            }
+            let mut stream = ResumableDownload::open(
+                self,
+                provider,
+                key.to_vec(),
+                object.clone(),
+                download_options.clone(),
+            )
+            .await?;
+            let mut batch = Vec::with_capacity(MAX_WRITE_BATCH_LEN);
+            let mut written = offset;
+            while let Some(item) = stream.next().await {
+                let chunk = item?;
+                if chunk.is_empty() {
+                    continue;
+                }
+                if let Some(hasher) = &mut hasher {
+                    hasher.update(&chunk);
+                }
+                written += chunk.len() as u64;
+                batch.push(chunk);
+                if batch.len() == MAX_WRITE_BATCH_LEN {
+                    (file, batch) = write_batch(file, batch).await?;
+                    flushed = written;
+                    pro_bar.set_position(min(written, object.size));
+                }
+            }
+            (file, _) = write_batch(file, batch).await?;
+            flushed = written;
+            // Trim any preallocated space that wasn't written
+            file.set_len(written)?;
+            if let Some(hasher) = hasher {
+                verify_hash(key, &object, hasher)?;
+            }
+            anyhow::Ok(written)
+        }
+        .await;
+        if result.is_err() && options.range.is_none() {
+            // Trim the preallocated space so the download can be resumed from what was written
+            if let Err(e) = trim_file(path, flushed).await {
+                tracing::debug!(key = %display_key(key), error = %e, "failed to trim partial file");
+            }
+        }
+        self.record_download(key, &object, &options, &result).await;
+        let written = result?;
+        pro_bar.finish_and_clear();
+        tracing::debug!(elapsed = ?started.elapsed(), key = %display_key(key), size = written, "downloaded object to file");
+        msg_bar.println(format!(
+            "{} Downloaded object in {} (hash={}; size={})",
+            SPARKLE,
+            HumanDuration(started.elapsed()),
+            object.hash,
+            object.size
+        ));
+
+        msg_bar.finish_and_clear();
+        Ok(())
+    }
 
-        // Get total size using AsyncSeek
-        let total_size = file.seek(std::io::SeekFrom::End(0)).await?;
-        if total_size > MAX_OBJECT_LENGTH {
-            return Err(anyhow!("file exceeds maximum allowed size of 5 GB"));
+    /// Get many objects at the given keys, range, and height.
+    ///
+    /// Object info for all keys is resolved up front with concurrent queries at a single height,
+    /// and reused when downloading, so each object costs only one download request.
+    /// `f` is called with each key to open the writer for that object.
+    pub async fn get_many<K, W, F, Fut>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        keys: &[K],
+        mut f: F,
+        mut options: GetOptions,
+    ) -> anyhow::Result<()>
+    where
+        K: AsRef<[u8]>,
+        W: AsyncWrite + Unpin + Send + 'static,
+        F: FnMut(&[u8]) -> Fut,
+        Fut: Future<Output = anyhow::Result<W>>,
+    {
+        options = options.with_defaults(&self.defaults);
+        let keys = keys
+            .iter()
+            .map(|key| self.check_key(key.as_ref()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let Some((first, rest)) = keys.split_first() else {
+            return Ok(());
+        };
+
+        let started = Instant::now();
+        let bars = new_multi_bar(!options.show_progress);
+        let msg_bar = bars.add(new_message_bar());
+
+        msg_bar.set_prefix("[1/2]");
+        msg_bar.set_message(format!("Getting info for {} objects...", keys.len()));
+        // Pin the height to the first response so all objects are resolved from the same state
+        let response = self
+            .get_object(provider, first.as_ref(), options.height)
+            .await?;
+        options.height = FvmQueryHeight::Height(response.height.value());
+        let mut objects = Vec::with_capacity(keys.len());
+        objects.push(response.value);
+        let height = options.height;
+        let mut responses = stream::iter(rest)
+            .map(|key| self.get_object(provider, key.as_ref(), height))
+            .buffered(MAX_CONCURRENT_QUERIES);
+        while let Some(response) = responses.next().await {
+            objects.push(response?.value);
         }
+        let objects = keys
+            .iter()
+            .zip(objects)
+            .map(|(key, object)| {
+                let key = key.as_ref();
+                object
+                    .map(|object| (key, object))
+                    .ok_or_else(|| anyhow!("object not found for key '{}'", display_key(key)))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        tracing::debug!(elapsed = ?started.elapsed(), count = keys.len(), "fetched object infos");
 
-        // Reset to start for upload
-        file.seek(std::io::SeekFrom::Start(0)).await?;
+        msg_bar.set_prefix("[2/2]");
+        let total_size = objects.iter().map(|(_, object)| object.size).sum();
+        let pro_bar = bars.add(new_progress_bar(total_size));
+        let mut total = 0;
+        for (key, object) in &objects {
+            msg_bar.set_message(format!(
+                "Downloading object {} (hash={}; size={})",
+                display_key(key),
+                object.hash,
+                object.size
+            ));
+            let writer = f(key).await?;
+            let offset = total;
+            total += self
+                .download(provider, key, object, writer, &options, |p| {
+                    pro_bar.set_position(offset + p)
+                })
+                .await?;
+        }
+        pro_bar.finish_and_clear();
+        tracing::debug!(elapsed = ?started.elapsed(), count = keys.len(), size = total, "downloaded objects");
+        msg_bar.println(format!(
+            "{} Downloaded {} objects in {} (size={})",
+            SPARKLE,
+            objects.len(),
+            HumanDuration(started.elapsed()),
+            total_size
+        ));
 
-        self.add_reader(provider, signer, from, key, file, total_size, options)
-            .await
+        msg_bar.finish_and_clear();
+        Ok(())
     }
 
-    /// Delete an object.
-    pub async fn delete<C>(
+    /// Query for objects with params at the given height.
+    ///
+    /// Use [`QueryOptions`] for filtering and pagination.
+    /// When a delimiter is set, the result separates objects directly under the prefix from
+    /// common prefixes ("directories"), similar to S3 `ListObjectsV2`.
+    /// Each common prefix includes the query prefix and ends with the delimiter,
+    /// so it can be used as the prefix of a subsequent query to descend the hierarchy.
+    pub async fn query(
+        &self,
+        provider: &impl QueryProvider,
+        options: QueryOptions,
+    ) -> anyhow::Result<ListObjectsReturn> {
+        let response = self.list(provider, options).await?;
+        Ok(response.value)
+    }
+
+    /// Walk all objects matching the query options page by page, calling `f` with each page.
+    ///
+    /// Pages contain at most `options.limit` objects (or 1000 if the limit is zero),
+    /// so very large buckets can be listed without buffering and decoding the entire listing
+    /// in a single response.
+    /// All pages are queried at the height of the first page to get a consistent view.
+    /// Returns that height.
+    pub async fn query_pages<F>(
+        &self,
+        provider: &impl QueryProvider,
+        options: QueryOptions,
+        mut f: F,
+    ) -> anyhow::Result<u64>
+    where
+        F: FnMut(ListObjectsReturn) -> anyhow::Result<()>,
+    {
+        let mut pages = std::pin::pin!(self.pages(provider, options));
+        let mut height = 0;
+        while let Some((page_height, page)) = pages.try_next().await? {
+            height = page_height;
+            f(page)?;
+        }
+        Ok(height)
+    }
+
+    /// Returns a stream of all objects matching the query options, as `(key, state)` pairs.
+    ///
+    /// Pages are queried lazily as the stream is polled, walking `next_key` until the listing is
+    /// exhausted, so callers can iterate over any number of objects.
+    /// All pages are queried at the height of the first page to get a consistent view.
+    /// Common prefixes aren't included; use [`Bucket::query_pages`] to get them.
+    pub fn query_stream<'a>(
+        &'a self,
+        provider: &'a impl QueryProvider,
+        options: QueryOptions,
+    ) -> impl Stream<Item = anyhow::Result<(Vec<u8>, ObjectState)>> + 'a {
+        self.pages(provider, options)
+            .map_ok(|(_, page)| stream::iter(page.objects.into_iter().map(anyhow::Ok)))
+            .try_flatten()
+    }
+
+    /// Returns a stream of the pages of objects matching the query options, with the height
+    /// they were queried at.
+    ///
+    /// Pages are queried lazily, walking `next_key` until the listing is exhausted, and all
+    /// pages are queried at the height of the first page.
+    fn pages<'a>(
+        &'a self,
+        provider: &'a impl QueryProvider,
+        mut options: QueryOptions,
+    ) -> impl Stream<Item = anyhow::Result<(u64, ListObjectsReturn)>> + 'a {
+        if options.limit == 0 {
+            options.limit = DEFAULT_PAGE_SIZE;
+        }
+        stream::try_unfold(Some(options), move |options| async move {
+            let Some(mut options) = options else {
+                return Ok(None);
+            };
+            let response = self.list(provider, options.clone()).await?;
+            let height = response.height.value();
+            options.height = FvmQueryHeight::Height(height);
+
+            let page = response.value;
+            let next = page.next_key.clone().map(|key| {
+                options.start_key = Some(key);
+                options
+            });
+            anyhow::Ok(Some(((height, page), next)))
+        })
+    }
+
+    /// List all objects under a prefix by listing key-prefix shards in parallel.
+    ///
+    /// This is much faster than [`Bucket::query_pages`] for buckets with many objects spread
+    /// across a key hierarchy. All shards are listed at the same height.
+    /// Returns the objects in key order.
+    pub async fn query_sharded(
+        &self,
+        provider: &impl QueryProvider,
+        options: ShardedQueryOptions,
+    ) -> anyhow::Result<Vec<(Vec<u8>, ObjectState)>> {
+        let started = Instant::now();
+        let mut objects = Vec::new();
+        let mut height = options.height;
+        // Pin the height so discovery and shards are listed from the same state
+        if !matches!(height, FvmQueryHeight::Height(_)) {
+            let query = QueryOptions {
+                prefix: options.prefix.clone(),
+                limit: 1,
+                height,
+                ..Default::default()
+            };
+            let response = self.list(provider, query).await?;
+            height = FvmQueryHeight::Height(response.height.value());
+        }
+
+        let shards = if options.shards.is_empty() {
+            // Objects directly under the prefix are listed while discovering shards
+            let mut shards = Vec::new();
+            let query = QueryOptions {
+                prefix: options.prefix.clone(),
+                delimiter: options.delimiter.clone(),
+                height,
+                ..Default::default()
+            };
+            self.query_pages(provider, query, |page| {
+                objects.extend(page.objects);
+                for prefix in page.common_prefixes {
+                    let prefix = String::from_utf8(prefix).map_err(|e| {
+                        anyhow!(
+                            "shard prefix '{}' is not valid UTF-8",
+                            display_key(e.as_bytes())
+                        )
+                    })?;
+                    shards.push(prefix);
+                }
+                Ok(())
+            })
+            .await?;
+            shards
+        } else {
+            options.shards.clone()
+        };
+
+        let shard_count = shards.len();
+        let mut listings = stream::iter(shards)
+            .map(|shard| async move {
+                let mut objects = Vec::new();
+                let query = QueryOptions {
+                    prefix: shard,
+                    delimiter: "".into(),
+                    height,
+                    ..Default::default()
+                };
+                self.query_pages(provider, query, |page| {
+                    objects.extend(page.objects);
+                    Ok(())
+                })
+                .await?;
+                anyhow::Ok(objects)
+            })
+            .buffer_unordered(options.concurrency.max(1));
+        while let Some(listing) = listings.next().await {
+            objects.extend(listing?);
+        }
+
+        objects.sort_by(|a, b| a.0.cmp(&b.0));
+        objects.dedup_by(|a, b| a.0 == b.0);
+        tracing::debug!(
+            elapsed = ?started.elapsed(),
+            shards = shard_count,
+            count = objects.len(),
+            "listed objects by shard"
+        );
+        Ok(objects)
+    }
+
+    /// Capture the keys, content hashes, and metadata of all objects at the given height.
+    ///
+    /// The snapshot can be stored (see [`BucketSnapshot::to_bytes`]) and later passed to
+    /// [`Bucket::restore`] to roll the bucket back.
+    pub async fn snapshot(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<BucketSnapshot> {
+        self.snapshot_prefix(provider, "", height).await
+    }
+
+    /// Capture the objects under a prefix at the given height.
+    async fn snapshot_prefix(
+        &self,
+        provider: &impl QueryProvider,
+        prefix: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<BucketSnapshot> {
+        let query = QueryOptions {
+            prefix: prefix.into(),
+            delimiter: "".into(),
+            height,
+            ..Default::default()
+        };
+        let mut keys = Vec::new();
+        let height = self
+            .query_pages(provider, query, |page| {
+                keys.extend(page.objects.into_iter().map(|(key, _)| key));
+                Ok(())
+            })
+            .await?;
+
+        // The listing doesn't include recovery hashes, so get each object at the same height
+        let height_param = FvmQueryHeight::Height(height);
+        let mut responses = stream::iter(&keys)
+            .map(|key| self.get_object(provider, key, height_param))
+            .buffered(MAX_CONCURRENT_QUERIES);
+        let mut objects = Vec::with_capacity(keys.len());
+        let mut keys = keys.iter();
+        while let Some(response) = responses.next().await {
+            let key = keys.next().expect("a key for each response");
+            let object = response?
+                .value
+                .ok_or_else(|| anyhow!("object not found for key '{}'", display_key(key)))?;
+            objects.push(SnapshotObject {
+                key: key.clone(),
+                hash: object.hash,
+                recovery_hash: object.recovery_hash,
+                size: object.size,
+                metadata: object.metadata,
+            });
+        }
+        Ok(BucketSnapshot {
+            address: self.address,
+            height,
+            objects,
+        })
+    }
+
+    /// Roll the bucket back to a snapshot.
+    ///
+    /// Keys that are missing or point at different content are re-added with the recorded
+    /// content hash, so no data is uploaded. This only succeeds while the content is still
+    /// stored in the network, e.g., because another object references it.
+    /// A failed transaction doesn't stop the others; see [`RestoreReport::results`].
+    /// Immutable objects whose content changed aren't rolled back, and are reported as failed.
+    pub async fn restore<C>(
         &self,
         provider: &impl Provider<C>,
         signer: &mut impl Signer,
         from: Address,
-        key: &str,
-        options: DeleteOptions,
-    ) -> anyhow::Result<TxResult<()>>
+        snapshot: &BucketSnapshot,
+        options: RestoreOptions,
+    ) -> anyhow::Result<RestoreReport>
     where
         C: Client + Send + Sync,
     {
-        let params = DeleteParams {
-            key: key.into(),
+        let options = options.with_defaults(&self.defaults);
+        if snapshot.address != self.address {
+            return Err(anyhow!(
+                "snapshot is for bucket {}, not {}",
+                snapshot.address,
+                self.address
+            ));
+        }
+
+        let mut current = HashMap::new();
+        let query = QueryOptions {
+            delimiter: "".into(),
+            ..Default::default()
+        };
+        self.query_pages(provider, query, |page| {
+            current.extend(page.objects.into_iter().map(|(key, object)| {
+                let immutable = is_immutable(&object.metadata);
+                (key, (object.hash, immutable))
+            }));
+            Ok(())
+        })
+        .await?;
+
+        let node_addr = provider.node_addr().await?;
+        let mut report = RestoreReport::default();
+        for object in &snapshot.objects {
+            match current.remove(&object.key) {
+                Some((hash, _)) if hash == object.hash => continue,
+                Some((_, true)) => {
+                    let err = ImmutableObjectError {
+                        key: display_key(&object.key),
+                    };
+                    let result = ItemAttempts::new(&object.key, 0)
+                        .record::<()>(Err(err.into()))
+                        .expect("a final result");
+                    report.results.items.push(result);
+                    continue;
+                }
+                _ => {}
+            }
+            let params = AddParams {
+                source: PublicKey(*node_addr.node_id.as_bytes()),
+                key: object.key.clone(),
+                hash: object.hash,
+                recovery_hash: object.recovery_hash,
+                size: object.size,
+                ttl: options.ttl,
+                metadata: object.metadata.clone(),
+                overwrite: true,
+                from,
+            };
+            let params = RawBytes::serialize(params)?;
+            let mut attempts = ItemAttempts::new(&object.key, options.retries);
+            let result = loop {
+                let tx = signer
+                    .send_transaction(
+                        provider,
+                        self.address,
+                        Default::default(),
+                        AddObject as u64,
+                        params.clone(),
+                        options.gas_params.clone(),
+                        options.broadcast_mode,
+                        |_: &DeliverTx| -> anyhow::Result<()> { Ok(()) },
+                    )
+                    .await;
+                if let Some(result) = attempts.record(tx) {
+                    break result;
+                }
+            };
+            if result.is_ok() {
+                report.restored.push(object.key.clone());
+            }
+            report.results.items.push(result);
+        }
+
+        if options.delete_extra {
+            let mut extra: Vec<Vec<u8>> = current.into_keys().collect();
+            extra.sort();
+            for key in extra {
+                let mut attempts = ItemAttempts::new(&key, options.retries);
+                let result = loop {
+                    let tx = self
+                        .delete(
+                            provider,
+                            signer,
+                            from,
+                            &key,
+                            DeleteOptions {
+                                broadcast_mode: options.broadcast_mode,
+                                gas_params: options.gas_params.clone(),
+                                ..Default::default()
+                            },
+                        )
+                        .await;
+                    if let Some(result) = attempts.record(tx) {
+                        break result;
+                    }
+                };
+                if result.is_ok() {
+                    report.deleted.push(key);
+                }
+                report.results.items.push(result);
+            }
+        }
+        tracing::debug!(
+            restored = report.restored.len(),
+            deleted = report.deleted.len(),
+            failed = report.results.failed().count(),
+            height = snapshot.height,
+            "restored bucket snapshot"
+        );
+        Ok(report)
+    }
+
+    /// Write every object under a prefix into a tar archive, e.g., to back up the bucket or
+    /// migrate it to another bucket or network with [`Bucket::import`].
+    ///
+    /// The archive starts with a [`BucketSnapshot`] of the objects at `snapshot.cbor`,
+    /// followed by each object's content as stored (compressed objects aren't decompressed)
+    /// at `objects/<index>`, in snapshot order. All objects are read at the same height, and
+    /// their content is checked against the object hashes.
+    /// Returns the snapshot of the exported objects.
+    pub async fn export<W>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        prefix: &str,
+        height: FvmQueryHeight,
+        writer: W,
+    ) -> anyhow::Result<BucketSnapshot>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let started = Instant::now();
+        let snapshot = self.snapshot_prefix(provider, prefix, height).await?;
+        let mut archive = tokio_tar::Builder::new(writer);
+        let bytes = snapshot.to_bytes()?;
+        archive
+            .append_data(
+                &mut archive_header(bytes.len() as u64),
+                ARCHIVE_SNAPSHOT_PATH,
+                bytes.as_slice(),
+            )
+            .await?;
+
+        let options = GetOptions {
+            height: FvmQueryHeight::Height(snapshot.height),
+            raw: true,
+            ..Default::default()
+        };
+        for (index, object) in snapshot.objects.iter().enumerate() {
+            let stream = self
+                .get_stream(provider, &object.key, options.clone())
+                .await?;
+            let reader =
+                StreamReader::new(stream.map_err(|e| std::io::Error::other(format!("{:#}", e))));
+            archive
+                .append_data(
+                    &mut archive_header(object.size),
+                    archive_object_path(index),
+                    reader,
+                )
+                .await
+                .map_err(|e| {
+                    anyhow!(
+                        "failed to export object '{}': {}",
+                        display_key(&object.key),
+                        e
+                    )
+                })?;
+        }
+        let mut writer = archive.into_inner().await?;
+        writer.flush().await?;
+        tracing::debug!(
+            elapsed = ?started.elapsed(),
+            count = snapshot.objects.len(),
+            height = snapshot.height,
+            "exported bucket archive"
+        );
+        Ok(snapshot)
+    }
+
+    /// Add the objects in an archive written by [`Bucket::export`] to the bucket.
+    ///
+    /// Objects are uploaded as they're read from the archive and added with the keys and
+    /// metadata recorded in its snapshot, so the archive can come from another bucket or
+    /// network. Uploaded content is checked against the recorded hashes.
+    /// A failed object doesn't stop the others; see [`ImportReport::results`].
+    pub async fn import<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        reader: R,
+        options: ImportOptions,
+    ) -> anyhow::Result<ImportReport>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + Unpin + Send + Sync + 'static,
+    {
+        let options = options.with_defaults(&self.defaults);
+        let started = Instant::now();
+        let mut archive = tokio_tar::Archive::new(reader);
+        let mut entries = archive.entries()?;
+        let snapshot = match entries.next().await {
+            Some(entry) => {
+                let mut entry = entry?;
+                if entry.path()?.to_string_lossy() != ARCHIVE_SNAPSHOT_PATH {
+                    return Err(anyhow!(
+                        "archive doesn't start with a {}",
+                        ARCHIVE_SNAPSHOT_PATH
+                    ));
+                }
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes).await?;
+                BucketSnapshot::from_bytes(&bytes)?
+            }
+            None => return Err(anyhow!("archive is empty")),
+        };
+
+        let node_addr = provider.node_addr().await?;
+        let mut report = ImportReport::default();
+        let mut objects = snapshot.objects.iter().enumerate();
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let Some((index, object)) = objects.next() else {
+                return Err(anyhow!("unexpected archive entry '{}'", path));
+            };
+            if path != archive_object_path(index) {
+                return Err(anyhow!("unexpected archive entry '{}'", path));
+            }
+
+            let tx = async {
+                let key = self.check_key(&object.key)?.into_owned();
+                if options.overwrite {
+                    self.check_mutable(provider, &key).await?;
+                }
+                let body =
+                    reqwest::Body::wrap_stream(ReaderStream::with_capacity(entry, 64 * 1024));
+                let response = provider.upload(body, object.size).await?;
+                let hash = IrohHash::from_str(&response.hash)
+                    .map_err(|_| anyhow!("Invalid object hash from server"))?;
+                if *hash.as_bytes() != object.hash.0 {
+                    return Err(IntegrityError {
+                        key: display_key(&object.key),
+                        expected: object.hash.to_string(),
+                        actual: Hash(*hash.as_bytes()).to_string(),
+                    }
+                    .into());
+                }
+                let metadata_hash = IrohHash::from_str(&response.metadata_hash)
+                    .map_err(|_| anyhow!("Invalid metadata hash from server"))?;
+                let params = AddParams {
+                    source: PublicKey(*node_addr.node_id.as_bytes()),
+                    key,
+                    hash: object.hash,
+                    recovery_hash: Hash(*metadata_hash.as_bytes()),
+                    size: object.size,
+                    ttl: options.ttl,
+                    metadata: object.metadata.clone(),
+                    overwrite: options.overwrite,
+                    from,
+                };
+                signer
+                    .send_transaction(
+                        provider,
+                        self.address,
+                        Default::default(),
+                        AddObject as u64,
+                        RawBytes::serialize(params)?,
+                        options.gas_params.clone(),
+                        options.broadcast_mode,
+                        |_: &DeliverTx| -> anyhow::Result<()> { Ok(()) },
+                    )
+                    .await
+            }
+            .await;
+            let result = ItemAttempts::new(&object.key, 0)
+                .record(tx)
+                .expect("a final result");
+            if result.is_ok() {
+                report.imported.push(object.key.clone());
+            }
+            report.results.items.push(result);
+        }
+        if objects.next().is_some() {
+            return Err(anyhow!(
+                "archive is truncated; it has {} of {} objects",
+                report.results.items.len(),
+                snapshot.objects.len()
+            ));
+        }
+        tracing::debug!(
+            elapsed = ?started.elapsed(),
+            imported = report.imported.len(),
+            failed = report.results.failed().count(),
+            "imported bucket archive"
+        );
+        Ok(report)
+    }
+
+    /// Copy an object to another key in the bucket.
+    ///
+    /// The copy references the source object's content hash, so no data is uploaded.
+    /// The source object's metadata is copied, with [`CopyOptions::metadata`] applied on top.
+    /// Copying an object to its own key with `overwrite` renews it with a new TTL.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        src_key: impl AsRef<[u8]>,
+        dst_key: impl AsRef<[u8]>,
+        options: CopyOptions,
+    ) -> anyhow::Result<TxResult<Object>>
+    where
+        C: Client + Send + Sync,
+    {
+        let options = options.with_defaults(&self.defaults);
+        let src_key = self.check_key(src_key.as_ref())?;
+        let src_key = src_key.as_ref();
+        let dst_key = self.check_key(dst_key.as_ref())?;
+        if options.overwrite {
+            self.check_mutable(provider, &dst_key).await?;
+        }
+        let object = self
+            .get_object(provider, src_key, options.height)
+            .await?
+            .value
+            .ok_or_else(|| anyhow!("object not found for key '{}'", display_key(src_key)))?;
+        let mut metadata = object.metadata;
+        metadata.extend(options.metadata);
+        validate_metadata(&metadata)?;
+
+        let node_addr = provider.node_addr().await?;
+        let params = AddParams {
+            source: PublicKey(*node_addr.node_id.as_bytes()),
+            key: dst_key.into_owned(),
+            hash: object.hash,
+            recovery_hash: object.recovery_hash,
+            size: object.size,
+            ttl: options.ttl,
+            metadata,
+            overwrite: options.overwrite,
             from,
         };
-        let params = RawBytes::serialize(params)?;
         signer
             .send_transaction(
                 provider,
                 self.address,
                 Default::default(),
-                DeleteObject as u64,
-                params,
+                AddObject as u64,
+                RawBytes::serialize(params)?,
                 options.gas_params,
                 options.broadcast_mode,
-                |_: &DeliverTx| -> anyhow::Result<()> { Ok(()) },
+                decode_as,
             )
             .await
     }
 
-    /// Get an object at the given key, range, and height.
-    pub async fn get<W>(
+    /// Sync a local directory to the objects under a key prefix.
+    ///
+    /// Each regular file is keyed by `prefix` followed by its `/`-separated path relative to
+    /// `dir`. Files are uploaded unless an object with the same size and BLAKE3 content hash
+    /// already exists at the key. Symbolic links are skipped.
+    /// A failed transaction doesn't stop the others; see [`SyncReport::results`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sync<C>(
         &self,
-        provider: &(impl QueryProvider + ObjectProvider),
-        key: &str,
-        mut writer: W,
-        options: GetOptions,
-    ) -> anyhow::Result<()>
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        dir: impl AsRef<Path>,
+        prefix: &str,
+        options: SyncOptions,
+    ) -> anyhow::Result<SyncReport>
     where
-        W: AsyncWrite + Unpin + Send + 'static,
+        C: Client + Send + Sync,
     {
+        let options = options.with_defaults(&self.defaults);
         let started = Instant::now();
-        let bars = new_multi_bar(!options.show_progress);
-        let msg_bar = bars.add(new_message_bar());
-
-        msg_bar.set_prefix("[1/2]");
-        msg_bar.set_message("Getting object info...");
-        let params = GetParams(key.into());
-        let params = RawBytes::serialize(params)?;
-        let message = local_message(self.address, GetObject as u64, params);
-        let response = provider.call(message, options.height, decode_get).await?;
-        let object = response
-            .value
-            .ok_or_else(|| anyhow!("object not found for key '{}'", key))?;
+        let dir = dir
+            .as_ref()
+            .canonicalize()
+            .map_err(|e| anyhow!("failed to resolve path: {}", e))?;
+        let files = tokio::task::spawn_blocking(move || list_files(&dir)).await??;
 
-        msg_bar.set_prefix("[2/2]");
-        msg_bar.set_message(format!(
-            "Downloading object (hash={}; size={})",
-            object.hash, object.size
-        ));
+        let mut remote = self.list_all(provider, prefix, options.height).await?;
 
-        let pro_bar = bars.add(new_progress_bar(object.size));
-        let response = provider
-            .download(self.address, key, options.range, options.height.into())
-            .await?;
-        let mut stream = response.bytes_stream();
-        let mut progress = 0;
-        while let Some(item) = stream.next().await {
-            match item {
-                Ok(chunk) => {
-                    writer.write_all(&chunk).await?;
-                    progress = min(progress + chunk.len(), object.size as usize);
-                    pro_bar.set_position(progress as u64);
-                }
-                Err(e) => {
-                    return Err(anyhow!(e));
+        let mut report = SyncReport::default();
+        for (relative_key, path, size) in files {
+            let key = format!("{}{}", prefix, relative_key);
+            if let Some(object) = remote.remove(key.as_bytes()) {
+                if object.size == size && object.hash.0 == hash_file(&path).await? {
+                    report.unchanged += 1;
+                    continue;
                 }
             }
-        }
-        pro_bar.finish_and_clear();
-        msg_bar.println(format!(
-            "{} Downloaded object in {} (hash={}; size={})",
-            SPARKLE,
-            HumanDuration(started.elapsed()),
-            object.hash,
-            object.size
-        ));
+            if options.dry_run {
+                report.uploaded.push(key);
+                continue;
+            }
+            let mut attempts = ItemAttempts::new(key.as_bytes(), options.retries);
+            let result = loop {
+                let tx = self
+                    .add_from_path(
+                        provider,
+                        signer,
+                        from,
+                        &key,
+                        &path,
+                        AddOptions {
+                            ttl: options.ttl,
+                            overwrite: true,
+                            broadcast_mode: options.broadcast_mode,
+                            gas_params: options.gas_params.clone(),
+                            allow_empty: true,
+                            ..Default::default()
+                        },
+                    )
+                    .await;
+                if let Some(result) = attempts.record(tx) {
+                    break result;
+                }
+            };
+            if result.is_ok() {
+                report.uploaded.push(key);
+            }
+            report.results.items.push(result);
+        }
 
-        msg_bar.finish_and_clear();
-        Ok(())
+        if options.delete {
+            let mut removed: Vec<Vec<u8>> = remote.into_keys().collect();
+            removed.sort();
+            for key in removed {
+                if options.dry_run {
+                    report.deleted.push(key);
+                    continue;
+                }
+                let mut attempts = ItemAttempts::new(&key, options.retries);
+                let result = loop {
+                    let tx = self
+                        .delete(
+                            provider,
+                            signer,
+                            from,
+                            &key,
+                            DeleteOptions {
+                                broadcast_mode: options.broadcast_mode,
+                                gas_params: options.gas_params.clone(),
+                                ..Default::default()
+                            },
+                        )
+                        .await;
+                    if let Some(result) = attempts.record(tx) {
+                        break result;
+                    }
+                };
+                if result.is_ok() {
+                    report.deleted.push(key);
+                }
+                report.results.items.push(result);
+            }
+        }
+        tracing::debug!(
+            elapsed = ?started.elapsed(),
+            uploaded = report.uploaded.len(),
+            deleted = report.deleted.len(),
+            unchanged = report.unchanged,
+            failed = report.results.failed().count(),
+            dry_run = options.dry_run,
+            "synced directory"
+        );
+        Ok(report)
     }
 
-    /// Query for objects with params at the given height.
+    /// Compare the objects under a prefix at two block heights.
     ///
-    /// Use [`QueryOptions`] for filtering and pagination.
-    pub async fn query(
+    /// Both listings are queried from the chain's historical state and no content is
+    /// downloaded, so this is cheap enough to drive incremental backups and audits.
+    /// TTL changes aren't reported.
+    pub async fn diff(
         &self,
         provider: &impl QueryProvider,
-        options: QueryOptions,
-    ) -> anyhow::Result<ListObjectsReturn> {
-        let params = ListParams {
-            prefix: options.prefix.into(),
-            delimiter: options.delimiter.into(),
-            start_key: options.start_key,
-            limit: options.limit,
-        };
-        let params = RawBytes::serialize(params)?;
-        let message = local_message(self.address, ListObjects as u64, params);
-        let response = provider.call(message, options.height, decode_list).await?;
-        Ok(response.value)
+        prefix: &str,
+        from: FvmQueryHeight,
+        to: FvmQueryHeight,
+    ) -> anyhow::Result<BucketDiff> {
+        let mut before = self.list_all(provider, prefix, from).await?;
+        let after = self.list_all(provider, prefix, to).await?;
+
+        let mut diff = BucketDiff::default();
+        for (key, object) in after {
+            match before.remove(&key) {
+                None => diff.added.push(key),
+                Some(old) if old.hash != object.hash || old.metadata != object.metadata => {
+                    diff.changed.push(key)
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = before.into_keys().collect();
+        tracing::debug!(
+            added = diff.added.len(),
+            removed = diff.removed.len(),
+            changed = diff.changed.len(),
+            "diffed bucket listings"
+        );
+        Ok(diff)
+    }
+
+    /// Watch for objects being added to and deleted from the bucket under a prefix.
+    ///
+    /// Transactions are received over a WebSocket subscription (see [`ws_client`]), and adds
+    /// and deletes sent to this bucket are decoded into events, so indexers and caches can
+    /// follow a bucket without polling [`Bucket::query`].
+    /// The subscription doesn't report whether a transaction succeeded, so each change is
+    /// confirmed against the bucket's state at the transaction's height before it's yielded.
+    /// The stream ends when the subscription is closed.
+    ///
+    /// [`ws_client`]: recall_provider::json_rpc::ws_client
+    pub async fn watch<'a>(
+        &'a self,
+        client: &WebSocketClient,
+        provider: &'a impl QueryProvider,
+        prefix: &str,
+    ) -> anyhow::Result<BoxStream<'a, anyhow::Result<BucketEvent>>> {
+        let prefix = self.check_prefix(prefix)?.into_owned();
+        let subscription = client.subscribe(EventType::Tx.into()).await?;
+        tracing::debug!(address = %self.address, prefix = %prefix, "watching bucket");
+        let events = subscription
+            .map_err(anyhow::Error::from)
+            .try_filter_map(move |event| {
+                let change = match event.data {
+                    EventData::Tx { tx_result } => self
+                        .decode_change(&tx_result.tx, tx_result.height as u64)
+                        .filter(|change| change.key().starts_with(prefix.as_bytes())),
+                    _ => None,
+                };
+                async move {
+                    match change {
+                        Some(change) => self.confirm_change(provider, change).await,
+                        None => Ok(None),
+                    }
+                }
+            })
+            .boxed();
+        Ok(events)
     }
 
     /// Update object metadata.
@@ -409,17 +3088,20 @@ impl Bucket {
         provider: &impl Provider<C>,
         signer: &mut impl Signer,
         from: Address,
-        key: &str,
+        key: impl AsRef<[u8]>,
         metadata: HashMap<String, Option<String>>,
         options: UpdateObjectMetadataOptions,
     ) -> anyhow::Result<TxResult<()>>
     where
         C: Client + Send + Sync,
     {
+        let options = options.with_defaults(&self.defaults);
         validate_metadata_optional(&metadata)?;
+        let key = self.check_key(key.as_ref())?;
+        self.check_mutable(provider, &key).await?;
 
         let params = UpdateObjectMetadataParams {
-            key: key.into(),
+            key: key.as_ref().into(),
             metadata,
             from,
         };
@@ -438,6 +3120,471 @@ impl Bucket {
             .await
     }
 
+    pub(crate) async fn list(
+        &self,
+        provider: &impl QueryProvider,
+        options: QueryOptions,
+    ) -> anyhow::Result<QueryResponse<ListObjectsReturn>> {
+        let mut prefix = self.check_prefix(&options.prefix)?.into_owned();
+        if let Some(pattern) = &options.pattern {
+            // Only list keys that can match the pattern, unless that would change grouping
+            if options.delimiter.is_empty() && pattern.prefix().starts_with(&prefix) {
+                prefix = pattern.prefix().into();
+            }
+        }
+        let params = ListParams {
+            prefix: prefix.into(),
+            delimiter: options.delimiter.into(),
+            start_key: options.start_key,
+            limit: options.limit,
+        };
+        let params = RawBytes::serialize(params)?;
+        let message = local_message(self.address, ListObjects as u64, params);
+        let mut response = provider.call(message, options.height, decode_list).await?;
+        if let Some(pattern) = &options.pattern {
+            response
+                .value
+                .objects
+                .retain(|(key, _)| pattern.matches(key));
+        }
+        Ok(response)
+    }
+
+    /// Applies the bucket's key policy to a key.
+    fn check_key<'a>(&self, key: &'a [u8]) -> anyhow::Result<Cow<'a, [u8]>> {
+        match &self.key_policy {
+            Some(policy) => policy.apply(key),
+            None => Ok(Cow::Borrowed(key)),
+        }
+    }
+
+    /// Applies the bucket's key policy to a key that must stay valid UTF-8.
+    fn check_key_str<'a>(&self, key: &'a str) -> anyhow::Result<Cow<'a, str>> {
+        let invalid = || anyhow!("key policy returned a key that isn't valid UTF-8");
+        Ok(match self.check_key(key.as_bytes())? {
+            Cow::Borrowed(bytes) => {
+                Cow::Borrowed(std::str::from_utf8(bytes).map_err(|_| invalid())?)
+            }
+            Cow::Owned(bytes) => Cow::Owned(String::from_utf8(bytes).map_err(|_| invalid())?),
+        })
+    }
+
+    /// Applies the bucket's key policy to a query prefix. The empty prefix is left as is.
+    fn check_prefix<'a>(&self, prefix: &'a str) -> anyhow::Result<Cow<'a, str>> {
+        if prefix.is_empty() {
+            return Ok(Cow::Borrowed(prefix));
+        }
+        self.check_key_str(prefix)
+    }
+
+    /// Decodes an add or delete sent to this bucket from a raw transaction.
+    /// Returns `None` for any other transaction.
+    fn decode_change(&self, tx: &[u8], height: u64) -> Option<BucketEvent> {
+        let ChainMessage::Signed(signed) = fvm_ipld_encoding::from_slice(tx).ok()? else {
+            return None;
+        };
+        let message = signed.message;
+        if message.to != self.address {
+            return None;
+        }
+        if message.method_num == AddObject as u64 {
+            let params: AddParams = message.params.deserialize().ok()?;
+            Some(BucketEvent::Added {
+                key: params.key,
+                hash: params.hash,
+                size: params.size,
+                metadata: params.metadata,
+                height,
+            })
+        } else if message.method_num == DeleteObject as u64 {
+            let params: DeleteParams = message.params.deserialize().ok()?;
+            Some(BucketEvent::Deleted {
+                key: params.key,
+                height,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Confirms a decoded change against the bucket's state at the change's height.
+    /// Returns the change with the object's recorded metadata if it took effect, or `None`
+    /// if its transaction failed.
+    async fn confirm_change(
+        &self,
+        provider: &impl QueryProvider,
+        change: BucketEvent,
+    ) -> anyhow::Result<Option<BucketEvent>> {
+        let height = change.height();
+        let object = self
+            .get_object(provider, change.key(), FvmQueryHeight::Height(height))
+            .await?
+            .value;
+        let confirmed = match (change, object) {
+            (BucketEvent::Added { key, hash, .. }, Some(object)) if object.hash == hash => {
+                Some(BucketEvent::Added {
+                    key,
+                    hash,
+                    size: object.size,
+                    metadata: object.metadata,
+                    height,
+                })
+            }
+            // A delete only took effect if the object existed before the block
+            (change @ BucketEvent::Deleted { .. }, None) if height > 0 => self
+                .get_object(provider, change.key(), FvmQueryHeight::Height(height - 1))
+                .await?
+                .value
+                .map(|_| change),
+            (change, _) => {
+                tracing::debug!(
+                    key = display_key(change.key()),
+                    height,
+                    "skipped bucket change that didn't take effect"
+                );
+                None
+            }
+        };
+        Ok(confirmed)
+    }
+
+    /// List all objects under a prefix at a height, keyed by object key.
+    async fn list_all(
+        &self,
+        provider: &impl QueryProvider,
+        prefix: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<BTreeMap<Vec<u8>, ObjectState>> {
+        let mut objects = BTreeMap::new();
+        let query = QueryOptions {
+            prefix: prefix.into(),
+            delimiter: "".into(),
+            height,
+            ..Default::default()
+        };
+        self.query_pages(provider, query, |page| {
+            objects.extend(page.objects);
+            Ok(())
+        })
+        .await?;
+        Ok(objects)
+    }
+
+    async fn get_object(
+        &self,
+        provider: &impl QueryProvider,
+        key: &[u8],
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<QueryResponse<Option<Object>>> {
+        let params = GetParams(key.into());
+        let params = RawBytes::serialize(params)?;
+        let message = local_message(self.address, GetObject as u64, params);
+        provider.call(message, height, decode_get).await
+    }
+
+    /// Returns the object at the key, or `None` if it doesn't exist.
+    pub(crate) async fn find_object(
+        &self,
+        provider: &impl QueryProvider,
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<Object>> {
+        let key = self.check_key(key.as_ref())?;
+        let response = self.get_object(provider, &key, height).await?;
+        Ok(response.value)
+    }
+
+    /// Returns a [`PreconditionFailedError`] if the object at the key doesn't meet the
+    /// precondition.
+    async fn check_precondition(
+        &self,
+        provider: &impl QueryProvider,
+        key: &str,
+        precondition: &Precondition,
+    ) -> anyhow::Result<()> {
+        let actual = self
+            .find_object(provider, key, FvmQueryHeight::Committed)
+            .await?
+            .map(|object| object.hash);
+        let expected = match precondition {
+            Precondition::IfNotExists => None,
+            Precondition::IfMatch(hash) => Some(*hash),
+        };
+        if actual == expected {
+            return Ok(());
+        }
+        Err(PreconditionFailedError {
+            key: display_key(key.as_bytes()),
+            expected: expected.map(|hash| hash.to_string()),
+            actual: actual.map(|hash| hash.to_string()),
+        }
+        .into())
+    }
+
+    /// Returns the object at the key, or an [`ImmutableObjectError`] if it has an advisory
+    /// immutability lock.
+    async fn check_mutable(
+        &self,
+        provider: &impl QueryProvider,
+        key: &[u8],
+    ) -> anyhow::Result<Option<Object>> {
+        let object = self
+            .get_object(provider, key, FvmQueryHeight::Committed)
+            .await?
+            .value;
+        if object
+            .as_ref()
+            .is_some_and(|object| is_immutable(&object.metadata))
+        {
+            return Err(ImmutableObjectError {
+                key: display_key(key),
+            }
+            .into());
+        }
+        Ok(object)
+    }
+
+    /// Returns whether the object's content can be downloaded from the Object API.
+    async fn is_resolved(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &[u8],
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<bool> {
+        match provider.size(self.address, key, height.into()).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_not_resolved(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Start downloading an object's content with the transport in the options, limited to
+    /// the rate in the options.
+    async fn open_chunks(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &[u8],
+        object: &Object,
+        options: &GetOptions,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<Bytes>>> {
+        let chunks = match options.transport {
+            Transport::Http => {
+                let response = self.open_download(provider, key, options).await?;
+                response.bytes_stream().map_err(anyhow::Error::from).boxed()
+            }
+            Transport::P2p => {
+                let bounds = match options.range {
+                    Some(range) => Some(range.bounds(object.size).ok_or_else(|| {
+                        anyhow!(
+                            "range {} isn't satisfiable for object '{}' of size {}",
+                            range,
+                            display_key(key),
+                            object.size
+                        )
+                    })?),
+                    None => None,
+                };
+                let node = match &self.defaults.node_addr {
+                    Some(node) => node.clone(),
+                    None => provider.node_addr().await?,
+                };
+                p2p::download(node, IrohHash::from_bytes(object.hash.0), bounds).await?
+            }
+        };
+        Ok(throttle(
+            chunks,
+            options.rate_limit,
+            clock_or_system(&options.clock),
+        ))
+    }
+
+    /// Start downloading an object, waiting for it to be resolved if the options allow.
+    async fn open_download(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &[u8],
+        options: &GetOptions,
+    ) -> anyhow::Result<reqwest::Response> {
+        let Some(timeout) = options.resolve_timeout else {
+            return provider
+                .download(
+                    self.address,
+                    key,
+                    options.range.map(|range| range.to_string()),
+                    options.height.into(),
+                )
+                .await;
+        };
+
+        let policy = new_backoff_policy(clock_or_system(&options.clock), timeout);
+        retry(policy, || async {
+            provider
+                .download(
+                    self.address,
+                    key,
+                    options.range.map(|range| range.to_string()),
+                    options.height.into(),
+                )
+                .await
+                .map_err(|e| {
+                    if is_not_resolved(&e) {
+                        tracing::debug!(key = %display_key(key), "waiting for object to resolve");
+                        backoff::Error::transient(e)
+                    } else {
+                        backoff::Error::permanent(e)
+                    }
+                })
+        })
+        .await
+    }
+
+    /// Download a resolved object into the writer, reporting progress in bytes.
+    /// Objects added with a compression codec are decompressed unless the options say otherwise.
+    /// Returns the number of bytes downloaded.
+    async fn download<W>(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &[u8],
+        object: &Object,
+        writer: W,
+        options: &GetOptions,
+        progress_fn: impl Fn(u64),
+    ) -> anyhow::Result<u64>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let result = match decompression(object, options) {
+            Ok(Some(codec)) => {
+                let mut writer = codec.decoder(writer);
+                async {
+                    let written = self
+                        .download_cached(provider, key, object, &mut writer, options, progress_fn)
+                        .await?;
+                    // Shutting down the decoder checks that the content is complete
+                    writer.shutdown().await?;
+                    anyhow::Ok(written)
+                }
+                .await
+            }
+            Ok(None) => {
+                self.download_cached(provider, key, object, writer, options, progress_fn)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        self.record_download(key, object, options, &result).await;
+        result
+    }
+
+    /// Record a download to the audit sink in the options, if any.
+    async fn record_download(
+        &self,
+        key: &[u8],
+        object: &Object,
+        options: &GetOptions,
+        result: &anyhow::Result<u64>,
+    ) {
+        let event = AuditEvent::transfer(
+            AuditKind::Download,
+            self.address,
+            display_key(key),
+            Some(object.hash.to_string()),
+            result,
+        );
+        audit::record(options.audit.as_deref(), event).await;
+    }
+
+    /// Download a resolved object into the writer, serving it from the cache in the options
+    /// if possible.
+    async fn download_cached<W>(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &[u8],
+        object: &Object,
+        mut writer: W,
+        options: &GetOptions,
+        progress_fn: impl Fn(u64),
+    ) -> anyhow::Result<u64>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let Some(cache) = options.cache.as_ref().filter(|_| options.range.is_none()) else {
+            return self
+                .download_uncached(provider, key, object, writer, options, progress_fn)
+                .await;
+        };
+
+        let hash = object.hash.to_string();
+        cache.track(self.address, key, &hash);
+        let file = match cache.open_entry(&hash) {
+            Some(file) => {
+                tracing::debug!(key = %display_key(key), %hash, "serving object from cache");
+                file
+            }
+            None => {
+                let temp_path = cache.temp_path();
+                let result = async {
+                    let file = tokio::fs::File::create(&temp_path).await?;
+                    self.download_uncached(provider, key, object, file, options, &progress_fn)
+                        .await?;
+                    // Open before inserting so the contents stay readable even if evicted right away
+                    let file = std::fs::File::open(&temp_path)?;
+                    cache.insert(&hash, &temp_path)?;
+                    anyhow::Ok(file)
+                }
+                .await;
+                if result.is_err() {
+                    let _ = tokio::fs::remove_file(&temp_path).await;
+                }
+                result?
+            }
+        };
+        let mut file = tokio::fs::File::from_std(file);
+        let written = tokio::io::copy(&mut file, &mut writer).await?;
+        writer.flush().await?;
+        progress_fn(written);
+        Ok(written)
+    }
+
+    /// Download a resolved object from the network into the writer.
+    async fn download_uncached<W>(
+        &self,
+        provider: &impl ObjectProvider,
+        key: &[u8],
+        object: &Object,
+        mut writer: W,
+        options: &GetOptions,
+        progress_fn: impl Fn(u64),
+    ) -> anyhow::Result<u64>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let mut stream = ResumableDownload::open(
+            self,
+            provider,
+            key.to_vec(),
+            object.clone(),
+            options.clone(),
+        )
+        .await?;
+        let mut hasher = new_verifier(options);
+        let mut progress = 0;
+        while let Some(item) = stream.next().await {
+            let chunk = item?;
+            if let Some(hasher) = &mut hasher {
+                hasher.update(&chunk);
+            }
+            writer.write_all(&chunk).await?;
+            progress = min(progress + chunk.len() as u64, object.size);
+            progress_fn(progress);
+        }
+        if let Some(hasher) = hasher {
+            verify_hash(key, object, hasher)?;
+        }
+        writer.flush().await?;
+        Ok(progress)
+    }
+
     fn add_content_type_to_metadata(
         &self,
         options: AddOptions,
@@ -456,6 +3603,393 @@ impl Bucket {
     }
 }
 
+/// Upload state saved with [`AddOptions::resume_from`].
+#[derive(Serialize, Deserialize)]
+struct UploadCheckpoint {
+    content_hash: [u8; 32],
+    size: u64,
+    hash: String,
+    metadata_hash: String,
+    /// When the upload started, in seconds since the Unix epoch.
+    uploaded_at: u64,
+}
+
+/// Load the upload result from a checkpoint file if it was saved for the same content no
+/// longer than [`MAX_UPLOAD_CHECKPOINT_AGE`] before `now`, in seconds since the Unix epoch.
+async fn load_upload_checkpoint(
+    path: &Path,
+    content_hash: &[u8; 32],
+    size: u64,
+    now: u64,
+) -> Option<UploadResponse> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    let checkpoint: UploadCheckpoint = fvm_ipld_encoding::from_slice(&bytes).ok()?;
+    if checkpoint.content_hash != *content_hash || checkpoint.size != size {
+        return None;
+    }
+    let age = now.saturating_sub(checkpoint.uploaded_at);
+    if age >= MAX_UPLOAD_CHECKPOINT_AGE.as_secs() {
+        tracing::debug!(path = %path.display(), age, "ignoring expired upload checkpoint");
+        return None;
+    }
+    IrohHash::from_str(&checkpoint.hash).ok()?;
+    IrohHash::from_str(&checkpoint.metadata_hash).ok()?;
+    Some(UploadResponse {
+        hash: checkpoint.hash,
+        metadata_hash: checkpoint.metadata_hash,
+    })
+}
+
+/// Save the upload result to a checkpoint file.
+async fn save_upload_checkpoint(
+    path: &Path,
+    content_hash: &[u8; 32],
+    size: u64,
+    uploaded_at: u64,
+    response: &UploadResponse,
+) -> anyhow::Result<()> {
+    let checkpoint = UploadCheckpoint {
+        content_hash: *content_hash,
+        size,
+        hash: response.hash.clone(),
+        metadata_hash: response.metadata_hash.clone(),
+        uploaded_at,
+    };
+    tokio::fs::write(path, fvm_ipld_encoding::to_vec(&checkpoint)?).await?;
+    Ok(())
+}
+
+/// Write a batch of chunks to the file on a blocking thread, returning the file and the
+/// emptied batch for reuse.
+async fn write_batch(
+    mut file: std::fs::File,
+    mut batch: Vec<Bytes>,
+) -> anyhow::Result<(std::fs::File, Vec<Bytes>)> {
+    if batch.is_empty() {
+        return Ok((file, batch));
+    }
+    tokio::task::spawn_blocking(move || {
+        write_all_vectored(&mut file, &mut batch)?;
+        batch.clear();
+        Ok((file, batch))
+    })
+    .await?
+}
+
+/// Returns the length of a partially downloaded file at `path`, or zero if there's no file
+/// or it's empty or not shorter than the object.
+async fn partial_len(path: &Path, size: u64) -> u64 {
+    match tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.is_file() && metadata.len() < size => metadata.len(),
+        _ => 0,
+    }
+}
+
+/// Truncate the file at `path` to `len` bytes.
+async fn trim_file(path: &Path, len: u64) -> std::io::Result<()> {
+    let file = tokio::fs::OpenOptions::new().write(true).open(path).await?;
+    file.set_len(len).await
+}
+
+/// Returns a hasher that has hashed the first `len` bytes of the file at `path`.
+async fn hash_prefix(path: &Path, len: u64) -> anyhow::Result<blake3::Hasher> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut hasher = blake3::Hasher::new();
+        let mut reader = std::io::Read::take(std::fs::File::open(&path)?, len);
+        std::io::copy(&mut reader, &mut hasher)?;
+        Ok(hasher)
+    })
+    .await?
+}
+
+/// Write all chunks with vectored writes, advancing past partially written chunks.
+fn write_all_vectored(file: &mut std::fs::File, chunks: &mut [Bytes]) -> std::io::Result<()> {
+    let mut start = 0;
+    while start < chunks.len() {
+        let slices: Vec<IoSlice> = chunks[start..].iter().map(|c| IoSlice::new(c)).collect();
+        let mut n = file.write_vectored(&slices)?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+        while n > 0 {
+            let len = chunks[start].len();
+            if n >= len {
+                n -= len;
+                start += 1;
+            } else {
+                chunks[start].advance(n);
+                n = 0;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the regular files under `dir` as `(relative key, path, size)`, sorted by key.
+/// Symbolic links are skipped.
+fn list_files(dir: &Path) -> anyhow::Result<Vec<(String, PathBuf, u64)>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_file() {
+                let relative = path.strip_prefix(dir)?;
+                let mut components = Vec::new();
+                for component in relative.components() {
+                    let component = component.as_os_str().to_str().ok_or_else(|| {
+                        anyhow!("path '{}' is not valid UTF-8", relative.display())
+                    })?;
+                    components.push(component);
+                }
+                let size = entry.metadata()?.len();
+                files.push((components.join("/"), path, size));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// Returns a hasher for checking a download against the object hash, if the options allow.
+fn new_verifier(options: &GetOptions) -> Option<blake3::Hasher> {
+    (!options.skip_verify && options.range.is_none()).then(blake3::Hasher::new)
+}
+
+/// Returns the content of downloaded chunks, checked with `hasher` if there is one and
+/// decompressed with `codec` if there is one.
+/// A hash mismatch is yielded as the last item.
+fn decode_chunks<'a>(
+    chunks: BoxStream<'a, anyhow::Result<Bytes>>,
+    hasher: Option<blake3::Hasher>,
+    codec: Option<Compression>,
+    key: Vec<u8>,
+    object: Object,
+) -> BoxStream<'a, anyhow::Result<Bytes>> {
+    let state = (chunks, hasher, key, object);
+    let verified = stream::try_unfold(state, |(mut chunks, mut hasher, key, object)| async move {
+        match chunks.next().await {
+            Some(chunk) => {
+                let chunk = chunk?;
+                if let Some(hasher) = &mut hasher {
+                    hasher.update(&chunk);
+                }
+                anyhow::Ok(Some((chunk, (chunks, hasher, key, object))))
+            }
+            None => {
+                if let Some(hasher) = hasher.take() {
+                    verify_hash(&key, &object, hasher)?;
+                }
+                Ok(None)
+            }
+        }
+    });
+
+    let Some(codec) = codec else {
+        return verified.boxed();
+    };
+    // The decoder reads through an io::Error; keep the original error aside so an
+    // integrity error can still be downcast from the stream
+    let failure = Arc::new(Mutex::new(None));
+    let reader = StreamReader::new(verified.map_err({
+        let failure = failure.clone();
+        move |e: anyhow::Error| {
+            let err = std::io::Error::other(format!("{:#}", e));
+            *failure.lock().unwrap_or_else(|e| e.into_inner()) = Some(e);
+            err
+        }
+    }));
+    ReaderStream::new(codec.reader(reader))
+        .map_err(move |e| {
+            failure
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take()
+                .unwrap_or_else(|| anyhow!(e))
+        })
+        .boxed()
+}
+
+/// The chunks of a download that resumes from the last received byte if it fails mid-transfer.
+///
+/// After an error, a range request for the rest of the content is reissued up to
+/// `options.retries` times, backing off between attempts.
+struct ResumableDownload<'a, P> {
+    bucket: &'a Bucket,
+    provider: &'a P,
+    key: Vec<u8>,
+    object: Object,
+    options: GetOptions,
+    /// Inclusive offsets of the requested content, if there is any.
+    bounds: Option<(u64, u64)>,
+    received: u64,
+    attempts: u32,
+    chunks: BoxStream<'static, anyhow::Result<Bytes>>,
+}
+
+impl<'a, P: ObjectProvider> ResumableDownload<'a, P> {
+    /// Start downloading an object.
+    async fn open(
+        bucket: &'a Bucket,
+        provider: &'a P,
+        key: Vec<u8>,
+        object: Object,
+        options: GetOptions,
+    ) -> anyhow::Result<Self> {
+        let chunks = bucket
+            .open_chunks(provider, &key, &object, &options)
+            .await?;
+        let bounds = match options.range {
+            Some(range) => range.bounds(object.size),
+            None => object.size.checked_sub(1).map(|end| (0, end)),
+        };
+        Ok(ResumableDownload {
+            bucket,
+            provider,
+            key,
+            object,
+            options,
+            bounds,
+            received: 0,
+            attempts: 0,
+            chunks,
+        })
+    }
+
+    /// Returns the next chunk, resuming the download if the transfer failed.
+    async fn next(&mut self) -> Option<anyhow::Result<Bytes>> {
+        loop {
+            let err = match self.chunks.next().await? {
+                Ok(chunk) => {
+                    self.received += chunk.len() as u64;
+                    return Some(Ok(chunk));
+                }
+                Err(e) => e,
+            };
+            if let Err(err) = self.resume(err).await {
+                return Some(Err(err));
+            }
+        }
+    }
+
+    /// Reissue the download from the last received byte after an error, if attempts remain.
+    async fn resume(&mut self, mut err: anyhow::Error) -> anyhow::Result<()> {
+        let Some((start, end)) = self.bounds else {
+            return Err(err);
+        };
+        let offset = start + self.received;
+        if offset > end {
+            // Everything was received, so there's nothing left to resume
+            self.chunks = stream::empty().boxed();
+            return Ok(());
+        }
+
+        let clock = clock_or_system(&self.options.clock);
+        let backoff = self
+            .options
+            .retry_backoff
+            .unwrap_or(DEFAULT_DOWNLOAD_RETRY_BACKOFF);
+        let mut options = self.options.clone();
+        options.range = Some(Range::Bounded(offset, end));
+        while self.attempts < self.options.retries {
+            let delay = backoff.saturating_mul(1 << self.attempts.min(16));
+            self.attempts += 1;
+            tracing::debug!(
+                key = %display_key(&self.key),
+                offset,
+                attempt = self.attempts,
+                error = %err,
+                "resuming interrupted download"
+            );
+            clock.sleep(delay).await;
+            match self
+                .bucket
+                .open_chunks(self.provider, &self.key, &self.object, &options)
+                .await
+            {
+                Ok(chunks) => {
+                    self.chunks = chunks;
+                    return Ok(());
+                }
+                Err(e) => err = e,
+            }
+        }
+        Err(err)
+    }
+}
+
+/// Returns a header for a bucket archive entry of the given size.
+fn archive_header(size: u64) -> tokio_tar::Header {
+    let mut header = tokio_tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header
+}
+
+/// Returns the path of an object's content in a bucket archive.
+fn archive_object_path(index: usize) -> String {
+    format!("objects/{:08}", index)
+}
+
+/// Checks the hash of downloaded content against the object hash.
+fn verify_hash(key: &[u8], object: &Object, hasher: blake3::Hasher) -> anyhow::Result<()> {
+    let actual = hasher.finalize();
+    if *actual.as_bytes() != object.hash.0 {
+        return Err(IntegrityError {
+            key: display_key(key),
+            expected: object.hash.to_string(),
+            actual: Hash(*actual.as_bytes()).to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Returns the codec to decompress a download with, if any.
+fn decompression(object: &Object, options: &GetOptions) -> anyhow::Result<Option<Compression>> {
+    if options.raw || options.range.is_some() {
+        return Ok(None);
+    }
+    Compression::from_metadata(&object.metadata)
+}
+
+/// Rejects an empty object unless the options allow it.
+fn check_empty(size: u64, options: &AddOptions) -> anyhow::Result<()> {
+    if size == 0 && !options.allow_empty {
+        return Err(anyhow!(
+            "object is empty; set allow_empty to add a zero-length object"
+        ));
+    }
+    Ok(())
+}
+
+/// Returns a displayable form of an object key.
+///
+/// Keys are arbitrary bytes, so keys that aren't valid UTF-8 (e.g., written by other clients)
+/// are shown with the invalid bytes escaped as `\xNN`, rather than being dropped.
+pub fn display_key(key: &[u8]) -> String {
+    key.utf8_chunks()
+        .map(|chunk| format!("{}{}", chunk.valid(), chunk.invalid().escape_ascii()))
+        .collect()
+}
+
+/// Returns whether object metadata marks the object immutable.
+fn is_immutable(metadata: &HashMap<String, String>) -> bool {
+    metadata
+        .get(IMMUTABLE_METADATA_KEY)
+        .is_some_and(|value| value == "true")
+}
+
+/// Returns whether the download error was caused by the object not being resolved yet.
+fn is_not_resolved(err: &anyhow::Error) -> bool {
+    err.to_string().contains("not resolved")
+}
+
 fn decode_get(deliver_tx: &DeliverTx) -> anyhow::Result<Option<Object>> {
     let data = decode_bytes(deliver_tx)?;
     fvm_ipld_encoding::from_slice(&data)
@@ -509,3 +4043,55 @@ fn validate_metadata_optional(metadata: &HashMap<String, Option<String>>) -> any
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_defaults() {
+        let gas_params = GasParams {
+            gas_limit: 10_000_000,
+            ..Default::default()
+        };
+        let bucket = Bucket::builder(Address::new_id(1000))
+            .broadcast_mode(BroadcastMode::Async)
+            .gas_params(gas_params.clone())
+            .height(FvmQueryHeight::Pending)
+            .show_progress(true)
+            .transport(Transport::P2p)
+            .build();
+        let defaults = bucket.defaults();
+
+        // Default-valued fields are filled
+        let options = AddOptions::default().with_defaults(defaults);
+        assert_eq!(options.broadcast_mode, BroadcastMode::Async);
+        assert_eq!(options.gas_params, gas_params);
+        assert!(options.show_progress);
+        let options = GetOptions::default().with_defaults(defaults);
+        assert!(matches!(options.height, FvmQueryHeight::Pending));
+        assert_eq!(options.transport, Transport::P2p);
+
+        // Explicitly set fields are kept
+        let options = DeleteOptions {
+            broadcast_mode: BroadcastMode::Sync,
+            ..Default::default()
+        }
+        .with_defaults(defaults);
+        assert_eq!(options.broadcast_mode, BroadcastMode::Sync);
+        assert_eq!(options.gas_params, gas_params);
+        let options = CopyOptions {
+            height: FvmQueryHeight::Height(5),
+            ..Default::default()
+        }
+        .with_defaults(defaults);
+        assert!(matches!(options.height, FvmQueryHeight::Height(5)));
+
+        // Pipelined transactions only take an async broadcast mode
+        let options = AddManyOptions::default().with_defaults(defaults);
+        assert_eq!(options.broadcast_mode, BroadcastMode::Async);
+        let bucket = Bucket::builder(Address::new_id(1000)).build();
+        let options = DeletePrefixOptions::default().with_defaults(bucket.defaults());
+        assert_eq!(options.broadcast_mode, BroadcastMode::Sync);
+    }
+}
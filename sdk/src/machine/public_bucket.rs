@@ -0,0 +1,173 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::path::Path;
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use futures::stream::{BoxStream, Stream};
+use tendermint_rpc::WebSocketClient;
+use tokio::io::AsyncWrite;
+
+use recall_provider::{
+    fvm_shared::address::Address,
+    object::ObjectProvider,
+    query::{FvmQueryHeight, QueryProvider},
+};
+
+use crate::machine::{
+    self,
+    bucket::{
+        Bucket, BucketDiff, BucketEvent, GetOptions, ListObjectsReturn, ObjectHead, ObjectInfo,
+        ObjectState, QueryOptions,
+    },
+    Machine,
+};
+
+/// A read-only view of a bucket, e.g., one published by another account.
+///
+/// Reading objects doesn't need a signer, only a provider with a chain RPC endpoint and an
+/// Object API endpoint, so consumers such as websites and analytics jobs can read published
+/// data without holding keys. A public bucket only exposes reads.
+pub struct PublicBucket {
+    bucket: Bucket,
+}
+
+impl PublicBucket {
+    /// Attach to the bucket at the given address, checking that the machine is a bucket.
+    pub async fn attach(provider: &impl QueryProvider, address: Address) -> anyhow::Result<Self> {
+        let metadata = machine::info(provider, address, FvmQueryHeight::Committed).await?;
+        if metadata.kind.to_string() != Bucket::KIND.to_string() {
+            return Err(anyhow!(
+                "machine {} is a {}, not a bucket",
+                address,
+                metadata.kind
+            ));
+        }
+        Ok(PublicBucket {
+            bucket: Bucket::attach(address).await?,
+        })
+    }
+
+    /// Returns the bucket [`Address`].
+    pub fn address(&self) -> Address {
+        self.bucket.address()
+    }
+
+    /// Get object info for a key (see [`Bucket::stat`]).
+    pub async fn stat(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<ObjectInfo>> {
+        self.bucket.stat(provider, key, height).await
+    }
+
+    /// Returns whether an object exists at a key (see [`Bucket::exists`]).
+    pub async fn exists(
+        &self,
+        provider: &impl QueryProvider,
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<bool> {
+        self.bucket.exists(provider, key, height).await
+    }
+
+    /// Get an object's size, content type, and resolution status (see [`Bucket::head`]).
+    pub async fn head(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Option<ObjectHead>> {
+        self.bucket.head(provider, key, height).await
+    }
+
+    /// Returns an Object API URL an object can be downloaded from (see [`Bucket::download_url`]).
+    pub fn download_url(
+        &self,
+        provider: &impl ObjectProvider,
+        key: impl AsRef<[u8]>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<reqwest::Url> {
+        self.bucket.download_url(provider, key, height)
+    }
+
+    /// Get an object into a writer (see [`Bucket::get`]).
+    pub async fn get<W>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        writer: W,
+        options: GetOptions,
+    ) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        self.bucket.get(provider, key, writer, options).await
+    }
+
+    /// Get an object as a stream of bytes (see [`Bucket::get_stream`]).
+    pub async fn get_stream<'a, P>(
+        &'a self,
+        provider: &'a P,
+        key: impl AsRef<[u8]>,
+        options: GetOptions,
+    ) -> anyhow::Result<BoxStream<'a, anyhow::Result<Bytes>>>
+    where
+        P: QueryProvider + ObjectProvider,
+    {
+        self.bucket.get_stream(provider, key, options).await
+    }
+
+    /// Get an object into a file (see [`Bucket::get_to_path`]).
+    pub async fn get_to_path(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        path: impl AsRef<Path>,
+        options: GetOptions,
+    ) -> anyhow::Result<()> {
+        self.bucket.get_to_path(provider, key, path, options).await
+    }
+
+    /// Query for objects (see [`Bucket::query`]).
+    pub async fn query(
+        &self,
+        provider: &impl QueryProvider,
+        options: QueryOptions,
+    ) -> anyhow::Result<ListObjectsReturn> {
+        self.bucket.query(provider, options).await
+    }
+
+    /// Returns a stream of all objects matching the query options (see [`Bucket::query_stream`]).
+    pub fn query_stream<'a>(
+        &'a self,
+        provider: &'a impl QueryProvider,
+        options: QueryOptions,
+    ) -> impl Stream<Item = anyhow::Result<(Vec<u8>, ObjectState)>> + 'a {
+        self.bucket.query_stream(provider, options)
+    }
+
+    /// Compare the objects under a prefix at two block heights (see [`Bucket::diff`]).
+    pub async fn diff(
+        &self,
+        provider: &impl QueryProvider,
+        prefix: &str,
+        from: FvmQueryHeight,
+        to: FvmQueryHeight,
+    ) -> anyhow::Result<BucketDiff> {
+        self.bucket.diff(provider, prefix, from, to).await
+    }
+
+    /// Watch for objects being added to and deleted from the bucket (see [`Bucket::watch`]).
+    pub async fn watch<'a>(
+        &'a self,
+        client: &WebSocketClient,
+        provider: &'a impl QueryProvider,
+        prefix: &str,
+    ) -> anyhow::Result<BoxStream<'a, anyhow::Result<BucketEvent>>> {
+        self.bucket.watch(client, provider, prefix).await
+    }
+}
@@ -2,33 +2,62 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use std::collections::HashMap;
+use std::io::Cursor;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
 use bytes::Bytes;
+use cid::multihash::Multihash;
 use fendermint_actor_timehub::{
     Method::{Count, Get, Peaks, Push, Root},
     PushParams,
 };
-use fendermint_vm_actor_interface::adm::{CreateExternalReturn, Kind};
+use fendermint_vm_actor_interface::adm::Kind;
+use futures::{
+    future,
+    stream::{self, BoxStream, Stream, StreamExt, TryStreamExt},
+};
 use serde::{Deserialize, Serialize};
-use tendermint::abci::response::DeliverTx;
+use tendermint::{abci::response::DeliverTx, block::Height};
+use tendermint_rpc::{event::EventData, query::EventType, SubscriptionClient, WebSocketClient};
 
 use recall_provider::{
     fvm_ipld_encoding::{self, RawBytes},
     fvm_shared::address::Address,
-    message::{local_message, GasParams},
+    message::{local_message, ChainMessage, GasParams},
     query::{FvmQueryHeight, QueryProvider},
     response::{decode_bytes, Cid},
     tx::{BroadcastMode, TxResult},
-    Client, Provider,
+    Client, Provider, TendermintClient,
 };
-use recall_signer::Signer;
+use recall_signer::{QueuedTransaction, Signer, Wallet};
 
-use crate::machine::{deploy_machine, Machine};
+use crate::{
+    clock::{clock_or_system, Clock},
+    machine::{
+        bucket::{AddOptions, AddReceipt, Bucket},
+        deploy_machine, Machine, MachineCreated,
+    },
+    root_cache::RootCache,
+};
 
 const MAX_ACC_PAYLOAD_SIZE: usize = 1024 * 500;
 
+/// Multicodec code of raw binary content.
+pub(crate) const RAW_CODEC: u64 = 0x55;
+
+/// Multihash code of BLAKE3.
+pub(crate) const BLAKE3_CODE: u64 = 0x1e;
+
+/// Maximum number of concurrent leaf queries.
+const MAX_CONCURRENT_QUERIES: usize = 16;
+
+/// Time between checks for committed pushes when looking up batch push indices.
+const INDEX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Payload push options.
 #[derive(Clone, Default, Debug)]
 pub struct PushOptions {
@@ -38,6 +67,91 @@ pub struct PushOptions {
     pub gas_params: GasParams,
 }
 
+/// The result of [`Timehub::push_once`].
+#[derive(Clone, Debug)]
+pub enum PushOnceReturn {
+    /// The payload was pushed.
+    Pushed {
+        /// The CID that was pushed (see [`idempotent_cid`]).
+        cid: Cid,
+        /// The result of the push transaction.
+        tx: TxResult<PushReturn>,
+    },
+    /// The key and payload were found in a recent leaf, so they weren't pushed again.
+    Existing {
+        /// The CID that was found (see [`idempotent_cid`]).
+        cid: Cid,
+        /// The index of the most recent leaf with the CID.
+        /// It may be a pending leaf whose push isn't committed yet.
+        index: u64,
+    },
+}
+
+/// Batch push options.
+#[derive(Clone, Debug)]
+pub struct PushBatchOptions {
+    /// Broadcast mode for the transactions.
+    /// Transactions are pipelined, so this must be [`BroadcastMode::Async`] or
+    /// [`BroadcastMode::Sync`].
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for each transaction.
+    pub gas_params: GasParams,
+    /// Maximum time to wait for the pushes to be committed, to look up each payload's index.
+    /// If not specified, indices aren't looked up.
+    pub index_timeout: Option<Duration>,
+    /// Optional clock that waiting for indices is timed with.
+    /// If not specified, the system clock is used.
+    pub clock: Option<Arc<dyn Clock>>,
+}
+
+impl Default for PushBatchOptions {
+    fn default() -> Self {
+        PushBatchOptions {
+            broadcast_mode: BroadcastMode::Sync,
+            gas_params: Default::default(),
+            index_timeout: None,
+            clock: None,
+        }
+    }
+}
+
+/// Detached payload push options.
+#[derive(Clone, Default, Debug)]
+pub struct PushDetachedOptions {
+    /// Options for adding the payload to the bucket.
+    /// Compression isn't supported, since the anchored hash must be the hash of the stored
+    /// content.
+    pub add: AddOptions,
+    /// Options for the timehub push.
+    pub push: PushOptions,
+}
+
+/// The result of [`Timehub::push_detached`].
+#[derive(Clone, Debug)]
+pub struct PushDetachedReturn {
+    /// The CID that was pushed, which is a raw BLAKE3 CID of the payload.
+    pub cid: Cid,
+    /// The result of the bucket add transaction.
+    pub add: TxResult<AddReceipt>,
+    /// The result of the timehub push transaction.
+    pub push: TxResult<PushReturn>,
+}
+
+/// The result of [`Timehub::push_batch`].
+#[derive(Debug)]
+pub struct PushBatchReturn {
+    /// The result of each push transaction that was sent, in the order of the payloads.
+    pub results: Vec<TxResult<()>>,
+    /// The index and error of the payload whose push failed.
+    /// Payloads after it were not pushed.
+    pub failed: Option<(usize, anyhow::Error)>,
+    /// The leaf index of each pushed payload, in the order of the payloads.
+    /// Empty unless `index_timeout` was set.
+    /// Indices are matched by payload, so they're ambiguous for payloads that someone else
+    /// pushed concurrently (see [`Timehub::push_batch`]).
+    pub indices: Vec<u64>,
+}
+
 /// JSON serialization friendly version of [`fendermint_actor_timehub::PushReturn`].
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PushReturn {
@@ -73,9 +187,43 @@ impl From<fendermint_actor_timehub::Leaf> for Leaf {
     }
 }
 
+/// A timehub root and the block height it was read at, returned by [`Timehub::root_at`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RootAt {
+    /// The timehub root.
+    pub root: Cid,
+    /// The block height the root was read at.
+    pub height: u64,
+}
+
+/// A leaf pushed into a timehub, yielded by [`Timehub::subscribe`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PushEvent {
+    /// The leaf index.
+    pub index: u64,
+    /// The leaf, whose witnessed CID is the pushed payload.
+    pub leaf: Leaf,
+    /// The height of the block that included the push.
+    pub height: u64,
+}
+
+/// Timehub stats, returned by [`Timehub::stats`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimehubStats {
+    /// The number of leaves.
+    pub count: u64,
+    /// The timehub root.
+    pub root: Cid,
+    /// Timestamp of the last leaf in seconds since the UNIX epoch, if there are any leaves.
+    pub last_push_timestamp: Option<u64>,
+    /// The block height the stats were read at.
+    pub height: u64,
+}
+
 /// A machine for event stream accumulation.
 pub struct Timehub {
     address: Address,
+    root_cache: Option<RootCache>,
 }
 
 #[async_trait]
@@ -88,7 +236,7 @@ impl Machine for Timehub {
         owner: Option<Address>,
         metadata: HashMap<String, String>,
         gas_params: GasParams,
-    ) -> anyhow::Result<(Self, TxResult<CreateExternalReturn>)>
+    ) -> anyhow::Result<(Self, TxResult<MachineCreated>)>
     where
         C: Client + Send + Sync,
     {
@@ -98,7 +246,10 @@ impl Machine for Timehub {
     }
 
     async fn attach(address: Address) -> anyhow::Result<Self> {
-        Ok(Timehub { address })
+        Ok(Timehub {
+            address,
+            root_cache: None,
+        })
     }
 
     fn address(&self) -> Address {
@@ -107,6 +258,14 @@ impl Machine for Timehub {
 }
 
 impl Timehub {
+    /// Verify every root read with [`Timehub::root_at`] against a [`RootCache`], so a root
+    /// that's inconsistent with the roots seen before fails with a
+    /// [`RootEquivocationError`](crate::root_cache::RootEquivocationError).
+    pub fn with_root_cache(mut self, root_cache: RootCache) -> Self {
+        self.root_cache = Some(root_cache);
+        self
+    }
+
     /// Push a payload into the timehub.
     pub async fn push<C>(
         &self,
@@ -121,7 +280,7 @@ impl Timehub {
     {
         if payload.len() > MAX_ACC_PAYLOAD_SIZE {
             return Err(anyhow!(
-                "max payload size is {} bytes",
+                "max payload size is {} bytes; use a detached push for larger payloads",
                 MAX_ACC_PAYLOAD_SIZE
             ));
         }
@@ -143,6 +302,190 @@ impl Timehub {
             .await
     }
 
+    /// Push a payload into the timehub under an idempotency key, unless the same key and
+    /// payload are in one of the last `window` leaves.
+    ///
+    /// The pushed value is a CID derived from the key and the payload (see [`idempotent_cid`]),
+    /// so retrying a push whose result was lost, e.g., after a timeout, is skipped, while the
+    /// same payload can still be pushed again under a new key. Since only the CID is pushed,
+    /// the payload can be of any size.
+    ///
+    /// Leaves are checked in the pending state, which includes pushes the node has accepted
+    /// but not yet committed, so a retry whose first attempt is still pending is caught too.
+    /// Leaves don't record who pushed them, so a push by another account under the same key
+    /// and payload also counts.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn push_once<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: impl AsRef<[u8]>,
+        payload: Bytes,
+        window: u64,
+        options: PushOptions,
+    ) -> anyhow::Result<PushOnceReturn>
+    where
+        C: Client + Send + Sync,
+    {
+        let cid = idempotent_cid(key.as_ref(), &payload)?;
+        let cid_bytes = cid.0.to_bytes();
+
+        // Leaves are read at the pending height directly, since resolving it to a block height
+        // would drop the pending leaves
+        let count = self.count(provider, FvmQueryHeight::Pending).await?;
+        let leaves: Vec<_> = stream::iter(count.saturating_sub(window)..count)
+            .map(|index| async move {
+                let leaf = self.leaf(provider, index, FvmQueryHeight::Pending).await?;
+                anyhow::Ok((index, leaf))
+            })
+            .buffered(MAX_CONCURRENT_QUERIES)
+            .try_collect()
+            .await?;
+        let existing = leaves.into_iter().rev().find_map(|(index, leaf)| {
+            leaf.filter(|leaf| leaf.witnessed.0.to_bytes() == cid_bytes)
+                .map(|_| index)
+        });
+        if let Some(index) = existing {
+            tracing::debug!(address = %self.address, index, "skipped push of existing payload");
+            return Ok(PushOnceReturn::Existing { cid, index });
+        }
+        let tx = self
+            .push(provider, signer, from, Bytes::from(cid_bytes), options)
+            .await?;
+        Ok(PushOnceReturn::Pushed { cid, tx })
+    }
+
+    /// Push a payload of any size into the timehub as a detached blob.
+    ///
+    /// The payload is added to a bucket at `key`, using the same upload flow as any other
+    /// object, and only its hash is pushed, as a raw BLAKE3 CID. Since an object hash is the
+    /// BLAKE3 hash of its content, a leaf can be checked against the stored object, or
+    /// against a copy of the payload, without trusting the bucket.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn push_detached<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        bucket: &Bucket,
+        key: &str,
+        payload: Bytes,
+        options: PushDetachedOptions,
+    ) -> anyhow::Result<PushDetachedReturn>
+    where
+        C: Client + Send + Sync,
+    {
+        if options.add.compression.is_some() {
+            return Err(anyhow!("detached payloads can't be compressed"));
+        }
+        let hash = Multihash::wrap(BLAKE3_CODE, blake3::hash(&payload).as_bytes())?;
+        let cid = cid::Cid::new_v1(RAW_CODEC, hash);
+
+        // Add the payload first, so it can be fetched by the time its hash is witnessed
+        let size = payload.len() as u64;
+        let add = bucket
+            .add_reader(
+                provider,
+                signer,
+                from,
+                key,
+                Cursor::new(payload),
+                size,
+                options.add,
+            )
+            .await?;
+        let push = self
+            .push(
+                provider,
+                signer,
+                from,
+                Bytes::from(cid.to_bytes()),
+                options.push,
+            )
+            .await?;
+        Ok(PushDetachedReturn {
+            cid: cid.into(),
+            add,
+            push,
+        })
+    }
+
+    /// Push many payloads into the timehub.
+    ///
+    /// The actor takes one payload per transaction, so the push transactions are signed and
+    /// broadcasted back-to-back (see [`Wallet::send_pipelined`]), without waiting on a block
+    /// per payload. Pipelined transactions don't return the actor's result, so if
+    /// `options.index_timeout` is set, new leaves are scanned once the pushes are committed
+    /// to find each payload's index.
+    ///
+    /// Leaves don't record who pushed them or at which height, so they're matched by payload
+    /// alone. If another push of the same payload lands after the batch starts, whichever leaf
+    /// comes first is reported, which may not be the one this batch pushed. Payloads that are
+    /// unique to the batch, e.g., CIDs that include a nonce, are always matched correctly.
+    ///
+    /// If a push fails, the remaining payloads aren't pushed, and the failure is returned in
+    /// [`PushBatchReturn::failed`] along with the results of the pushes that were sent.
+    pub async fn push_batch<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut Wallet,
+        from: Address,
+        payloads: Vec<Bytes>,
+        options: PushBatchOptions,
+    ) -> anyhow::Result<PushBatchReturn>
+    where
+        C: Client + Send + Sync,
+    {
+        if let Some(payload) = payloads.iter().find(|p| p.len() > MAX_ACC_PAYLOAD_SIZE) {
+            return Err(anyhow!(
+                "max payload size is {} bytes; got a payload of {} bytes",
+                MAX_ACC_PAYLOAD_SIZE,
+                payload.len()
+            ));
+        }
+        // Leaves before the current count can't be ours
+        let start = match options.index_timeout {
+            Some(_) => Some(self.count(provider, FvmQueryHeight::Committed).await?),
+            None => None,
+        };
+
+        let txs = payloads
+            .iter()
+            .map(|payload| {
+                let params = RawBytes::serialize(PushParams {
+                    cid_bytes: payload.to_vec(),
+                    from,
+                })?;
+                anyhow::Ok(QueuedTransaction {
+                    to: self.address,
+                    value: Default::default(),
+                    method_num: Push as u64,
+                    params,
+                    gas_params: options.gas_params.clone(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let report = signer
+            .send_pipelined(provider, txs, options.broadcast_mode)
+            .await?;
+
+        let indices = match (options.index_timeout, start) {
+            (Some(timeout), Some(start)) if !report.results.is_empty() => {
+                let clock = clock_or_system(&options.clock);
+                let pushed = &payloads[..report.results.len()];
+                self.find_indices(provider, pushed, start, timeout, clock)
+                    .await?
+            }
+            _ => Vec::new(),
+        };
+        Ok(PushBatchReturn {
+            results: report.results,
+            failed: report.failed,
+            indices,
+        })
+    }
+
     /// Get leaf stored at a given index and height.
     /// Returns None if there is no leaf at the given index.
     pub async fn leaf(
@@ -157,6 +500,112 @@ impl Timehub {
         Ok(response.value)
     }
 
+    /// Returns a stream of the leaves in a range of indices, as `(index, leaf)` pairs.
+    ///
+    /// Leaves are queried concurrently but yielded in order, at the height the leaf count is
+    /// read at, so the stream is a consistent view even while payloads are being pushed.
+    /// A range without an end stops at the leaf count, e.g., `..` replays every leaf.
+    pub fn leaves_stream<'a>(
+        &'a self,
+        provider: &'a impl QueryProvider,
+        range: impl RangeBounds<u64>,
+        height: FvmQueryHeight,
+    ) -> impl Stream<Item = anyhow::Result<(u64, Leaf)>> + 'a {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => Some(end.saturating_add(1)),
+            Bound::Excluded(end) => Some(*end),
+            Bound::Unbounded => None,
+        };
+        stream::once(async move {
+            let message = local_message(self.address, Count as u64, Default::default());
+            let response = provider.call(message, height, decode_count).await?;
+            let height = FvmQueryHeight::Height(response.height.value());
+            let end = end.map_or(response.value, |end| end.min(response.value));
+            let leaves = stream::iter(start..end)
+                .map(move |index| async move {
+                    let leaf = self
+                        .leaf(provider, index, height)
+                        .await?
+                        .ok_or_else(|| anyhow!("no leaf at index {}", index))?;
+                    anyhow::Ok((index, leaf))
+                })
+                .buffered(MAX_CONCURRENT_QUERIES);
+            anyhow::Ok(leaves)
+        })
+        .try_flatten()
+    }
+
+    /// Returns a stream of the leaves pushed between two times, as `(index, leaf)` pairs.
+    ///
+    /// Times are in seconds since the UNIX epoch, like leaf timestamps, and the range is
+    /// inclusive of `from` and exclusive of `to`. Leaf timestamps are block times, so they
+    /// never decrease; the first leaf in the range is found with a binary search over the
+    /// leaves instead of a scan, and the stream ends at the first leaf at or after `to`.
+    /// Use [`Timehub::block_timestamp`] to map block heights to times.
+    pub fn leaves_between<'a>(
+        &'a self,
+        provider: &'a impl QueryProvider,
+        from: u64,
+        to: u64,
+        height: FvmQueryHeight,
+    ) -> impl Stream<Item = anyhow::Result<(u64, Leaf)>> + 'a {
+        stream::once(async move {
+            let message = local_message(self.address, Count as u64, Default::default());
+            let response = provider.call(message, height, decode_count).await?;
+            let height = FvmQueryHeight::Height(response.height.value());
+            let count = response.value;
+            let start = self.first_leaf_at(provider, from, count, height).await?;
+            let leaves = self
+                .leaves_stream(provider, start..count, height)
+                .try_take_while(move |(_, leaf)| future::ready(Ok(leaf.timestamp < to)));
+            anyhow::Ok(leaves)
+        })
+        .try_flatten()
+    }
+
+    /// Get the timestamp of a block in seconds since the UNIX epoch.
+    ///
+    /// Leaves are timestamped with the time of the block they're pushed in, so this maps
+    /// block heights to the times used by [`Timehub::leaves_between`].
+    pub async fn block_timestamp<C>(
+        provider: &impl TendermintClient<C>,
+        height: u64,
+    ) -> anyhow::Result<u64>
+    where
+        C: Client + Send + Sync,
+    {
+        let response = provider
+            .underlying()
+            .header(Height::try_from(height)?)
+            .await?;
+        u64::try_from(response.header.time.unix_timestamp())
+            .map_err(|_| anyhow!("block {} has a time before the UNIX epoch", height))
+    }
+
+    /// Get the most recent `n` leaves at a given height, as `(index, leaf)` pairs in index order.
+    ///
+    /// The actor returns one leaf per query, so the leaves are queried concurrently, all at the
+    /// height the leaf count is read at.
+    pub async fn peek(
+        &self,
+        provider: &impl QueryProvider,
+        n: u64,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Vec<(u64, Leaf)>> {
+        let message = local_message(self.address, Count as u64, Default::default());
+        let response = provider.call(message, height, decode_count).await?;
+        let height = FvmQueryHeight::Height(response.height.value());
+        let count = response.value;
+        self.leaves_stream(provider, count.saturating_sub(n)..count, height)
+            .try_collect()
+            .await
+    }
+
     /// Get total leaf count at a given height.
     pub async fn count(
         &self,
@@ -185,10 +634,237 @@ impl Timehub {
         provider: &impl QueryProvider,
         height: FvmQueryHeight,
     ) -> anyhow::Result<Cid> {
+        Ok(self.root_at(provider, height).await?.root)
+    }
+
+    /// Get the root at a given height, along with the block height it was read at.
+    ///
+    /// Heights like [`FvmQueryHeight::Committed`] are resolved to the block they were read at,
+    /// so the root can be bound to a specific chain height, e.g., in an audit record.
+    /// If a root cache is set, the root is verified and recorded, which also reads the leaf
+    /// count at the same height.
+    pub async fn root_at(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<RootAt> {
         let message = local_message(self.address, Root as u64, Default::default());
         let response = provider.call(message, height, decode_root).await?;
-        Ok(response.value)
+        let root = RootAt {
+            root: response.value,
+            height: response.height.value(),
+        };
+        if let Some(root_cache) = &self.root_cache {
+            let count = self
+                .count(provider, FvmQueryHeight::Height(root.height))
+                .await?;
+            root_cache.verify(self.address, &root, count)?;
+        }
+        Ok(root)
     }
+
+    /// Returns whether a raw transaction is a push to this timehub.
+    fn is_push(&self, tx: &[u8]) -> bool {
+        match fvm_ipld_encoding::from_slice(tx) {
+            Ok(ChainMessage::Signed(signed)) => {
+                signed.message.to == self.address && signed.message.method_num == Push as u64
+            }
+            _ => false,
+        }
+    }
+
+    /// Reads the leaves from `next` up to the leaf count at `height`, returning them with the
+    /// index to read from next. If `next` isn't known, the leaves added in the block are read.
+    async fn new_leaves(
+        &self,
+        provider: &impl QueryProvider,
+        next: Option<u64>,
+        height: u64,
+    ) -> anyhow::Result<(Vec<PushEvent>, u64)> {
+        let start = match next {
+            Some(next) => next,
+            None => {
+                let before = FvmQueryHeight::Height(height.saturating_sub(1));
+                self.count(provider, before).await?
+            }
+        };
+        let leaves: Vec<_> = self
+            .leaves_stream(provider, start.., FvmQueryHeight::Height(height))
+            .try_collect()
+            .await?;
+        let next = leaves.last().map_or(start, |(index, _)| index + 1);
+        let events = leaves
+            .into_iter()
+            .map(|(index, leaf)| PushEvent {
+                index,
+                leaf,
+                height,
+            })
+            .collect();
+        Ok((events, next))
+    }
+
+    /// Returns the index of the first of `count` leaves with a timestamp at or after
+    /// `timestamp`, or `count` if there is none.
+    async fn first_leaf_at(
+        &self,
+        provider: &impl QueryProvider,
+        timestamp: u64,
+        count: u64,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<u64> {
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let leaf = self
+                .leaf(provider, mid, height)
+                .await?
+                .ok_or_else(|| anyhow!("no leaf at index {}", mid))?;
+            if leaf.timestamp < timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Returns a stream of the leaves pushed into the timehub from now on.
+    ///
+    /// Transactions are received from a Tendermint event subscription on `client`, created
+    /// with [`ws_client`], so consumers can follow a timehub without polling
+    /// [`Timehub::count`]. A push transaction only marks the block to check; the leaves are
+    /// read from the timehub's state at that block, so failed pushes are skipped, and leaves
+    /// are yielded in index order even if an event is missed.
+    /// The stream ends when the subscription is closed.
+    ///
+    /// [`ws_client`]: recall_provider::json_rpc::ws_client
+    pub async fn subscribe<'a>(
+        &'a self,
+        client: &WebSocketClient,
+        provider: &'a impl QueryProvider,
+    ) -> anyhow::Result<BoxStream<'a, anyhow::Result<PushEvent>>> {
+        let subscription = client.subscribe(EventType::Tx.into()).await?;
+        tracing::debug!(address = %self.address, "subscribed to timehub pushes");
+        let heights = subscription
+            .map_err(anyhow::Error::from)
+            .try_filter_map(move |event| {
+                let height = match event.data {
+                    EventData::Tx { tx_result } if self.is_push(&tx_result.tx) => {
+                        Some(tx_result.height as u64)
+                    }
+                    _ => None,
+                };
+                future::ready(Ok(height))
+            })
+            .boxed();
+        let events = stream::try_unfold((heights, None), move |(mut heights, next)| async move {
+            let Some(height) = heights.try_next().await? else {
+                return Ok(None);
+            };
+            let (events, next) = self.new_leaves(provider, next, height).await?;
+            Ok(Some((events, (heights, Some(next)))))
+        })
+        .map_ok(|events| stream::iter(events.into_iter().map(anyhow::Ok)))
+        .try_flatten()
+        .boxed();
+        Ok(events)
+    }
+
+    /// Get the leaf count, root, and last push time at a given height.
+    ///
+    /// The root is read first and the other queries are pinned to the height it was read at,
+    /// so the stats are consistent with each other. The actor doesn't record the height of a
+    /// push or the size of its payload, so the last push is given by its leaf timestamp, and
+    /// payload bytes aren't counted; every payload is a CID.
+    pub async fn stats(
+        &self,
+        provider: &impl QueryProvider,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<TimehubStats> {
+        let root = self.root_at(provider, height).await?;
+        let height = FvmQueryHeight::Height(root.height);
+        let count = self.count(provider, height).await?;
+        let last_push_timestamp = match count.checked_sub(1) {
+            Some(index) => self
+                .leaf(provider, index, height)
+                .await?
+                .map(|leaf| leaf.timestamp),
+            None => None,
+        };
+        Ok(TimehubStats {
+            count,
+            root: root.root,
+            last_push_timestamp,
+            height: root.height,
+        })
+    }
+
+    /// Scans the leaves from `start` until every payload is found, returning their indices.
+    /// Payloads are matched in order, so repeated payloads get increasing indices.
+    /// A leaf with the same payload pushed by anyone else after `start` is indistinguishable
+    /// from ours, so it may be matched instead.
+    async fn find_indices(
+        &self,
+        provider: &impl QueryProvider,
+        payloads: &[Bytes],
+        start: u64,
+        timeout: Duration,
+        clock: Arc<dyn Clock>,
+    ) -> anyhow::Result<Vec<u64>> {
+        let deadline = clock.now() + timeout;
+        let mut indices: Vec<Option<u64>> = vec![None; payloads.len()];
+        let mut next = start;
+        loop {
+            let count = self.count(provider, FvmQueryHeight::Committed).await?;
+            let leaves: Vec<_> = stream::iter(next..count)
+                .map(|index| self.leaf(provider, index, FvmQueryHeight::Committed))
+                .buffered(MAX_CONCURRENT_QUERIES)
+                .try_collect()
+                .await?;
+            for (index, leaf) in (next..count).zip(leaves) {
+                let Some(leaf) = leaf else {
+                    continue;
+                };
+                let witnessed = leaf.witnessed.0.to_bytes();
+                let found = payloads
+                    .iter()
+                    .zip(indices.iter_mut())
+                    .find(|(payload, found)| found.is_none() && payload[..] == witnessed[..]);
+                if let Some((_, found)) = found {
+                    *found = Some(index);
+                }
+            }
+            next = count;
+
+            let missing = indices.iter().filter(|index| index.is_none()).count();
+            if missing == 0 {
+                return Ok(indices.into_iter().flatten().collect());
+            }
+            if clock.now() >= deadline {
+                return Err(anyhow!(
+                    "timed out waiting for {} of {} pushes to be committed",
+                    missing,
+                    payloads.len()
+                ));
+            }
+            clock.sleep(INDEX_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Returns the CID that [`Timehub::push_once`] pushes for a payload under an idempotency key.
+///
+/// It's a raw BLAKE3 CID of the key's length, the key, and the payload, so the same payload
+/// gives a different CID under each key. Anyone with the key and the payload can recompute it
+/// to find or verify the leaf.
+pub fn idempotent_cid(key: &[u8], payload: &[u8]) -> anyhow::Result<Cid> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&(key.len() as u64).to_be_bytes());
+    hasher.update(key);
+    hasher.update(payload);
+    let hash = Multihash::wrap(BLAKE3_CODE, hasher.finalize().as_bytes())?;
+    Ok(cid::Cid::new_v1(RAW_CODEC, hash).into())
 }
 
 fn decode_push_return(deliver_tx: &DeliverTx) -> anyhow::Result<PushReturn> {
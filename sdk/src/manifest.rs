@@ -0,0 +1,153 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use recall_provider::{
+    fvm_ipld_encoding,
+    fvm_shared::{address::Address, crypto::signature::Signature},
+    message::sign_secp256k1,
+    query::{FvmQueryHeight, QueryProvider},
+};
+use recall_signer::Signer;
+
+use crate::machine::{
+    bucket::{display_key, Bucket, QueryOptions},
+    Machine,
+};
+
+/// Prefix of the signed bytes, so a manifest signature can't be mistaken for a message signature.
+const MANIFEST_SIGNING_DOMAIN: &[u8] = b"recall-manifest-v1:";
+
+/// An object listed in a [`Manifest`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// The object key, escaped if it isn't valid UTF-8 (see [`display_key`]).
+    pub key: String,
+    /// The object's content hash.
+    pub hash: String,
+    /// The object size in bytes.
+    pub size: u64,
+}
+
+/// A height-stamped listing of a bucket's objects under a prefix.
+///
+/// Anyone with access to a node that retains state at `height` can check the manifest
+/// against the chain with [`Manifest::verify`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The bucket address.
+    pub address: String,
+    /// The block height the listing was taken at.
+    pub height: u64,
+    /// The key prefix the listing is limited to.
+    pub prefix: String,
+    /// The listed objects in key order.
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Build a manifest of all objects in the bucket under `prefix` at the given height.
+    pub async fn build(
+        provider: &impl QueryProvider,
+        bucket: &Bucket,
+        prefix: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<Self> {
+        let query = QueryOptions {
+            prefix: prefix.into(),
+            delimiter: "".into(),
+            height,
+            ..Default::default()
+        };
+        let mut entries = Vec::new();
+        // All pages are listed at the height of the first page, so the listing is consistent
+        let height = bucket
+            .query_pages(provider, query, |page| {
+                entries.extend(page.objects.into_iter().map(|(key, object)| ManifestEntry {
+                    key: display_key(&key),
+                    hash: object.hash.to_string(),
+                    size: object.size,
+                }));
+                Ok(())
+            })
+            .await?;
+        Ok(Self {
+            address: bucket.address().to_string(),
+            height,
+            prefix: prefix.into(),
+            entries,
+        })
+    }
+
+    /// Returns the bytes that are signed for the manifest.
+    pub fn signing_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = MANIFEST_SIGNING_DOMAIN.to_vec();
+        bytes.extend(fvm_ipld_encoding::to_vec(self)?);
+        Ok(bytes)
+    }
+
+    /// Sign the manifest with the signer's secp256k1 key.
+    pub fn sign(self, signer: &impl Signer) -> anyhow::Result<SignedManifest> {
+        let sk = signer
+            .secret_key()
+            .ok_or_else(|| anyhow!("signer does not have a secret key"))?;
+        let signature = sign_secp256k1(&sk, &self.signing_bytes()?);
+        let key_address = Address::new_secp256k1(&sk.public_key().serialize())?;
+        Ok(SignedManifest {
+            manifest: self,
+            signer: key_address.to_string(),
+            signature: hex::encode(signature.bytes()),
+        })
+    }
+
+    /// Check the manifest against the chain by listing the bucket again at the manifest height.
+    pub async fn verify(&self, provider: &impl QueryProvider) -> anyhow::Result<()> {
+        let address = recall_provider::util::parse_address(&self.address)?;
+        let bucket = Bucket::attach(address).await?;
+        let actual = Self::build(
+            provider,
+            &bucket,
+            &self.prefix,
+            FvmQueryHeight::Height(self.height),
+        )
+        .await?;
+        if actual.entries != self.entries {
+            return Err(anyhow!(
+                "manifest does not match bucket {} at height {}",
+                self.address,
+                self.height
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A [`Manifest`] with a signature over its contents.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedManifest {
+    /// The signed manifest.
+    pub manifest: Manifest,
+    /// The secp256k1 key address (f1/t1) of the signer.
+    pub signer: String,
+    /// The hex-encoded secp256k1 signature.
+    pub signature: String,
+}
+
+impl SignedManifest {
+    /// Verify the signature over the manifest.
+    pub fn verify_signature(&self) -> anyhow::Result<()> {
+        let signer = recall_provider::util::parse_address(&self.signer)?;
+        let signature = Signature::new_secp256k1(hex::decode(&self.signature)?);
+        signature
+            .verify(&self.manifest.signing_bytes()?, &signer)
+            .map_err(|e| anyhow!("invalid manifest signature: {}", e))
+    }
+
+    /// Verify the signature, and check the manifest against the chain.
+    pub async fn verify(&self, provider: &impl QueryProvider) -> anyhow::Result<()> {
+        self.verify_signature()?;
+        self.manifest.verify(provider).await
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::path::Path;
+
+use anyhow::anyhow;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use recall_provider::{
+    fvm_shared::address::Address,
+    object::ObjectProvider,
+    query::{FvmQueryHeight, QueryProvider},
+    tx::TxResult,
+    Client, Provider,
+};
+use recall_signer::Signer;
+
+use crate::machine::{
+    bucket::{
+        display_key, AddOptions, AddReceipt, Bucket, DeleteOptions, DeleteReceipt, GetOptions,
+        ListObjectsReturn, QueryOptions,
+    },
+    Machine,
+};
+
+/// A set of buckets behind a single key space.
+///
+/// Each key is routed to the bucket registered with the longest matching key prefix,
+/// e.g., `logs/` → bucket A and `media/` → bucket B.
+/// Keys are stored unchanged, including the route prefix.
+/// Keys that match no route go to the default bucket, if one is set.
+#[derive(Default)]
+pub struct Namespace {
+    routes: Vec<(String, Bucket)>,
+    default: Option<Bucket>,
+}
+
+impl Namespace {
+    /// Create an empty namespace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route keys starting with `prefix` to `bucket`.
+    pub fn with_route(mut self, prefix: impl Into<String>, bucket: Bucket) -> Self {
+        self.add_route(prefix, bucket);
+        self
+    }
+
+    /// Route keys that match no prefix to `bucket`.
+    pub fn with_default(mut self, bucket: Bucket) -> Self {
+        self.default = Some(bucket);
+        self
+    }
+
+    /// Route keys starting with `prefix` to `bucket`, replacing any existing route for `prefix`.
+    pub fn add_route(&mut self, prefix: impl Into<String>, bucket: Bucket) {
+        let prefix = prefix.into();
+        self.routes.retain(|(p, _)| *p != prefix);
+        self.routes.push((prefix, bucket));
+    }
+
+    /// Returns the route prefixes and their bucket addresses.
+    pub fn routes(&self) -> Vec<(String, Address)> {
+        self.routes
+            .iter()
+            .map(|(prefix, bucket)| (prefix.clone(), bucket.address()))
+            .collect()
+    }
+
+    /// Returns the bucket a key is routed to.
+    pub fn route(&self, key: impl AsRef<[u8]>) -> anyhow::Result<&Bucket> {
+        let key = key.as_ref();
+        match self.route_index(key) {
+            Some(i) => Ok(&self.routes[i].1),
+            None => self
+                .default
+                .as_ref()
+                .ok_or_else(|| anyhow!("no bucket routed for key '{}'", display_key(key))),
+        }
+    }
+
+    /// Add an object with a reader into the bucket routed for `key`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_reader<C, R>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: &str,
+        reader: R,
+        size: u64,
+        options: AddOptions,
+    ) -> anyhow::Result<TxResult<AddReceipt>>
+    where
+        C: Client + Send + Sync,
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        self.route(key)?
+            .add_reader(provider, signer, from, key, reader, size, options)
+            .await
+    }
+
+    /// Add a file into the bucket routed for `key`.
+    pub async fn add_from_path<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: &str,
+        path: impl AsRef<Path>,
+        options: AddOptions,
+    ) -> anyhow::Result<TxResult<AddReceipt>>
+    where
+        C: Client + Send + Sync,
+    {
+        self.route(key)?
+            .add_from_path(provider, signer, from, key, path, options)
+            .await
+    }
+
+    /// Delete an object from the bucket routed for `key`.
+    pub async fn delete<C>(
+        &self,
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        key: impl AsRef<[u8]>,
+        options: DeleteOptions,
+    ) -> anyhow::Result<TxResult<DeleteReceipt>>
+    where
+        C: Client + Send + Sync,
+    {
+        let key = key.as_ref();
+        self.route(key)?
+            .delete(provider, signer, from, key, options)
+            .await
+    }
+
+    /// Get an object from the bucket routed for `key`, writing its contents to `writer`.
+    pub async fn get<W>(
+        &self,
+        provider: &(impl QueryProvider + ObjectProvider),
+        key: impl AsRef<[u8]>,
+        writer: W,
+        options: GetOptions,
+    ) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let key = key.as_ref();
+        self.route(key)?.get(provider, key, writer, options).await
+    }
+
+    /// Query objects across all buckets that may hold keys under `options.prefix`.
+    ///
+    /// Results are merged in key order.
+    /// Each bucket only contributes keys that are routed to it.
+    /// All buckets are queried at the height of the first response.
+    pub async fn query(
+        &self,
+        provider: &impl QueryProvider,
+        mut options: QueryOptions,
+    ) -> anyhow::Result<ListObjectsReturn> {
+        let mut objects = Vec::new();
+        let mut common_prefixes = Vec::new();
+        let mut cutoff: Option<Vec<u8>> = None;
+        for (i, bucket) in self.candidates(&options.prefix) {
+            let response = bucket.list(provider, options.clone()).await?;
+            options.height = FvmQueryHeight::Height(response.height.value());
+
+            let list = response.value;
+            if let Some(next_key) = list.next_key {
+                // Keys at or past `next_key` weren't returned by this bucket,
+                // so the merged page must stop there too.
+                if cutoff.as_ref().is_none_or(|c| next_key < *c) {
+                    cutoff = Some(next_key);
+                }
+            }
+            objects.extend(
+                list.objects
+                    .into_iter()
+                    .filter(|(key, _)| self.route_index(key) == i),
+            );
+            common_prefixes.extend(list.common_prefixes);
+        }
+
+        if let Some(cutoff) = &cutoff {
+            objects.retain(|(key, _)| key < cutoff);
+            common_prefixes.retain(|prefix| prefix < cutoff);
+        }
+        objects.sort_by(|a, b| a.0.cmp(&b.0));
+        common_prefixes.sort();
+        common_prefixes.dedup();
+
+        let mut next_key = cutoff;
+        if options.limit > 0 && objects.len() as u64 > options.limit {
+            let rest = objects.split_off(options.limit as usize);
+            next_key = rest.into_iter().next().map(|(key, _)| key);
+        }
+        Ok(ListObjectsReturn {
+            objects,
+            common_prefixes,
+            next_key,
+        })
+    }
+
+    /// Returns the index of the longest route prefix matching `key`.
+    fn route_index(&self, key: &[u8]) -> Option<usize> {
+        self.routes
+            .iter()
+            .enumerate()
+            .filter(|(_, (prefix, _))| key.starts_with(prefix.as_bytes()))
+            .max_by_key(|(_, (prefix, _))| prefix.len())
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the buckets that may hold keys under `prefix`, with their route index.
+    /// The default bucket has no index.
+    fn candidates(&self, prefix: &str) -> Vec<(Option<usize>, &Bucket)> {
+        let mut buckets: Vec<(Option<usize>, &Bucket)> = self
+            .routes
+            .iter()
+            .enumerate()
+            .filter(|(_, (p, _))| p.starts_with(prefix) || prefix.starts_with(p.as_str()))
+            .map(|(i, (_, bucket))| (Some(i), bucket))
+            .collect();
+        if let Some(bucket) = &self.default {
+            buckets.push((None, bucket));
+        }
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_namespace_route() {
+        let bucket = |id| Bucket::attach(Address::new_id(id));
+        let namespace = Namespace::new()
+            .with_route("logs/", bucket(1).await.unwrap())
+            .with_route("logs/app/", bucket(2).await.unwrap())
+            .with_route("media/", bucket(3).await.unwrap());
+
+        let routed = |key: &str| namespace.route(key).map(|b| b.address());
+        assert_eq!(routed("logs/sys.log").unwrap(), Address::new_id(1));
+        assert_eq!(routed("logs/app/1.log").unwrap(), Address::new_id(2));
+        assert_eq!(routed("media/a.png").unwrap(), Address::new_id(3));
+        assert!(routed("other").is_err());
+
+        let namespace = namespace.with_default(bucket(4).await.unwrap());
+        assert_eq!(
+            namespace.route("other").unwrap().address(),
+            Address::new_id(4)
+        );
+    }
+}
@@ -13,7 +13,7 @@ use recall_provider::{
         chainid::ChainID,
     },
     json_rpc::Url,
-    util::parse_address,
+    util::{parse_address, parse_address_for_network},
 };
 use recall_signer::SubnetID;
 
@@ -116,11 +116,23 @@ impl Network {
     /// Sets the current [`FvmNetwork`].
     /// Note: This _must_ be called before using the SDK.
     pub fn init(&self) -> &Self {
+        address::set_current_network(self.fvm_network());
+        self
+    }
+
+    /// Returns the [`FvmNetwork`] used for address prefixes.
+    pub fn fvm_network(&self) -> FvmNetwork {
         match self {
-            Network::Mainnet => address::set_current_network(FvmNetwork::Mainnet),
-            _ => address::set_current_network(FvmNetwork::Testnet),
+            Network::Mainnet => FvmNetwork::Mainnet,
+            _ => FvmNetwork::Testnet,
         }
-        self
+    }
+
+    /// Parse an f/t/eth-address from string, validating that its prefix matches this network.
+    ///
+    /// See [`parse_address_for_network`].
+    pub fn parse_address(&self, s: &str) -> anyhow::Result<Address> {
+        parse_address_for_network(s, self.fvm_network())
     }
 
     pub fn get_config(&self) -> NetworkConfig {
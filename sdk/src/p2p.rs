@@ -0,0 +1,189 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Verified downloads from iroh nodes.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use bao_tree::{io::BaoContentItem, ChunkNum, ChunkRanges};
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+use iroh::blobs::{
+    get::fsm::{self, BlobContentNext, ConnectedNext, EndBlobNext},
+    protocol::{GetRequest, RangeSpecSeq, ALPN},
+    Hash as IrohHash,
+};
+use iroh::net::{Endpoint, NodeAddr};
+
+/// How object content is downloaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// From the Object API over HTTP.
+    #[default]
+    Http,
+    /// From the Object API's iroh node over the iroh blobs protocol.
+    /// Content is streamed with BLAKE3 verified streaming, so every chunk is checked against
+    /// the object hash as it arrives, instead of trusting the body of an HTTP response.
+    /// Range gets are verified too.
+    P2p,
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http => write!(f, "http"),
+            Self::P2p => write!(f, "p2p"),
+        }
+    }
+}
+
+impl FromStr for Transport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "http" => Ok(Self::Http),
+            "p2p" => Ok(Self::P2p),
+            _ => Err(anyhow!(
+                "unsupported transport '{}'; expected http or p2p",
+                s
+            )),
+        }
+    }
+}
+
+/// Downloads a blob from an iroh node, verifying each chunk against the blob hash.
+///
+/// If `bounds` are given, only the bytes between the inclusive offsets are yielded.
+/// The endpoint used for the download is closed when the stream ends, fails, or is dropped.
+pub(crate) async fn download(
+    node: NodeAddr,
+    hash: IrohHash,
+    bounds: Option<(u64, u64)>,
+) -> anyhow::Result<BoxStream<'static, anyhow::Result<Bytes>>> {
+    let node_id = node.node_id;
+    let endpoint = EndpointGuard(Some(Endpoint::builder().bind().await?));
+    let connection = endpoint
+        .get()
+        .connect(node, ALPN)
+        .await
+        .map_err(|e| anyhow!("failed to connect to iroh node {}: {}", node_id, e))?;
+
+    let ranges = match bounds {
+        Some((start, end)) => {
+            ChunkRanges::from(ChunkNum::full_chunks(start)..ChunkNum::chunks(end + 1))
+        }
+        None => ChunkRanges::all(),
+    };
+    let request = GetRequest::new(hash, RangeSpecSeq::from_ranges([ranges]));
+    let connected = fsm::start(connection, request).next().await?;
+    let ConnectedNext::StartRoot(root) = connected.next().await? else {
+        return Err(anyhow!("unexpected response from iroh node {}", node_id));
+    };
+    let (content, size) = root.next().next().await?;
+    tracing::debug!(%node_id, %hash, size, "downloading blob over iroh");
+
+    let (start, end) = bounds.unwrap_or((0, u64::MAX));
+    let state = (Some(content), endpoint);
+    let chunks = stream::try_unfold(state, move |(content, endpoint)| async move {
+        let Some(mut content) = content else {
+            return Ok(None);
+        };
+        loop {
+            match content.next().await {
+                BlobContentNext::More((next, item)) => {
+                    content = next;
+                    let BaoContentItem::Leaf(leaf) = item? else {
+                        continue;
+                    };
+                    let Some(chunk) = trim(leaf.offset, leaf.data, start, end) else {
+                        continue;
+                    };
+                    return Ok(Some((chunk, (Some(content), endpoint))));
+                }
+                BlobContentNext::Done(done) => {
+                    finish(done).await?;
+                    endpoint.close().await?;
+                    return Ok(None);
+                }
+            }
+        }
+    });
+    Ok(chunks.boxed())
+}
+
+/// Owns the endpoint of a download, closing it when the download is dropped before it ends,
+/// e.g., on an error or when the caller stops reading the stream.
+///
+/// iroh endpoints keep running until they're closed, so an endpoint that's only dropped would
+/// leak its socket and background tasks.
+struct EndpointGuard(Option<Endpoint>);
+
+impl EndpointGuard {
+    fn get(&self) -> &Endpoint {
+        self.0.as_ref().expect("endpoint is open")
+    }
+
+    /// Closes the endpoint, waiting for its connections to finish.
+    async fn close(mut self) -> anyhow::Result<()> {
+        if let Some(endpoint) = self.0.take() {
+            endpoint.close(0u32.into(), b"done").await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for EndpointGuard {
+    fn drop(&mut self) {
+        let Some(endpoint) = self.0.take() else {
+            return;
+        };
+        // Closing is async, so it's finished in the background
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(e) = endpoint.close(0u32.into(), b"dropped").await {
+                    tracing::debug!(error = %e, "failed to close iroh endpoint");
+                }
+            });
+        }
+    }
+}
+
+/// Reads the end of a blob response and closes the request.
+async fn finish(done: fsm::AtEndBlob) -> anyhow::Result<()> {
+    let EndBlobNext::Closing(closing) = done.next() else {
+        return Err(anyhow!("unexpected blob in response"));
+    };
+    closing.next().await?;
+    Ok(())
+}
+
+/// Trims a leaf at `offset` to the bytes between the inclusive offsets `start` and `end`.
+/// Returns `None` if the leaf has none of them.
+fn trim(offset: u64, data: Bytes, start: u64, end: u64) -> Option<Bytes> {
+    let leaf_end = offset + data.len() as u64;
+    if leaf_end <= start || offset > end {
+        return None;
+    }
+    let from = start.saturating_sub(offset) as usize;
+    let to = end.saturating_add(1).min(leaf_end) - offset;
+    Some(data.slice(from..to as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        let data = Bytes::from_static(b"0123456789");
+        assert_eq!(trim(10, data.clone(), 0, 100).unwrap(), data);
+        assert_eq!(trim(10, data.clone(), 12, 14).unwrap(), "234");
+        assert_eq!(trim(10, data.clone(), 0, 10).unwrap(), "0");
+        assert_eq!(trim(10, data.clone(), 19, 30).unwrap(), "9");
+        assert!(trim(10, data.clone(), 20, 30).is_none());
+        assert!(trim(10, data, 0, 9).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Glob and regex patterns for matching object keys.
+
+use anyhow::anyhow;
+use regex::bytes::Regex;
+
+/// A glob or regex pattern that object keys are matched against.
+///
+/// Globs match whole keys: `*` matches any characters except `/`, `**` matches any characters
+/// including `/`, `?` matches a single character except `/`, and `[...]` (or `[!...]`) matches
+/// a character class, e.g., `logs/2024-*/error.log`.
+/// Regexes match anywhere in a key unless they're anchored with `^` and `$`.
+#[derive(Clone, Debug)]
+pub struct KeyPattern {
+    regex: Regex,
+    prefix: String,
+}
+
+impl KeyPattern {
+    /// Compile a glob pattern.
+    pub fn glob(pattern: &str) -> anyhow::Result<Self> {
+        let mut expr = String::from("(?s)^");
+        let mut prefix = String::new();
+        let mut literal = true;
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    expr.push_str(".*");
+                    literal = false;
+                }
+                '*' => {
+                    expr.push_str("[^/]*");
+                    literal = false;
+                }
+                '?' => {
+                    expr.push_str("[^/]");
+                    literal = false;
+                }
+                '[' => {
+                    expr.push('[');
+                    let negated = matches!(chars.peek(), Some('!') | Some('^'));
+                    if negated {
+                        chars.next();
+                        expr.push_str("^/");
+                    }
+                    // A leading ']' is part of the class
+                    if chars.peek() == Some(&']') {
+                        chars.next();
+                        expr.push_str("\\]");
+                    }
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        match c {
+                            ']' => {
+                                closed = true;
+                                break;
+                            }
+                            '\\' | '[' | '&' | '~' => {
+                                expr.push('\\');
+                                expr.push(c);
+                            }
+                            _ => expr.push(c),
+                        }
+                    }
+                    if !closed {
+                        return Err(anyhow!(
+                            "invalid key glob '{}'; unclosed character class",
+                            pattern
+                        ));
+                    }
+                    expr.push(']');
+                    literal = false;
+                }
+                _ => {
+                    expr.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                    if literal {
+                        prefix.push(c);
+                    }
+                }
+            }
+        }
+        expr.push('$');
+        let regex =
+            Regex::new(&expr).map_err(|e| anyhow!("invalid key glob '{}': {}", pattern, e))?;
+        Ok(KeyPattern { regex, prefix })
+    }
+
+    /// Compile a regex pattern.
+    pub fn regex(pattern: &str) -> anyhow::Result<Self> {
+        let regex =
+            Regex::new(pattern).map_err(|e| anyhow!("invalid key regex '{}': {}", pattern, e))?;
+        Ok(KeyPattern {
+            regex,
+            prefix: String::new(),
+        })
+    }
+
+    /// Returns whether a key matches the pattern.
+    pub fn matches(&self, key: impl AsRef<[u8]>) -> bool {
+        self.regex.is_match(key.as_ref())
+    }
+
+    /// Returns the literal prefix that all matching keys start with.
+    /// The prefix is empty for regexes.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob() {
+        let pattern = KeyPattern::glob("logs/2024-*/error.log").unwrap();
+        assert_eq!(pattern.prefix(), "logs/2024-");
+        assert!(pattern.matches("logs/2024-01/error.log"));
+        assert!(!pattern.matches("logs/2024-01/02/error.log"));
+        assert!(!pattern.matches("logs/2024-01/error.log.gz"));
+
+        let pattern = KeyPattern::glob("data/**/*.[cj]sv").unwrap();
+        assert!(pattern.matches("data/a/b/c.csv"));
+        assert!(pattern.matches("data/x/y.jsv"));
+        assert!(!pattern.matches("data/y.tsv"));
+
+        let pattern = KeyPattern::glob("a?[!0-9].txt").unwrap();
+        assert!(pattern.matches("abc.txt"));
+        assert!(!pattern.matches("ab1.txt"));
+        assert!(!pattern.matches("a/c.txt"));
+
+        assert!(KeyPattern::glob("a[bc").is_err());
+
+        let pattern = KeyPattern::regex(r"error\.log$").unwrap();
+        assert_eq!(pattern.prefix(), "");
+        assert!(pattern.matches("logs/2024-01/error.log"));
+        assert!(!pattern.matches("logs/2024-01/access.log"));
+    }
+}
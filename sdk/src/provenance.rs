@@ -0,0 +1,314 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::io::Cursor;
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use cid::{multihash::Multihash, Cid};
+use serde::{Deserialize, Serialize};
+
+use recall_provider::{
+    fvm_ipld_encoding,
+    fvm_shared::{address::Address, clock::ChainEpoch, crypto::signature::Signature},
+    message::{sign_secp256k1, GasParams},
+    object::ObjectProvider,
+    query::{FvmQueryHeight, QueryProvider},
+    tx::BroadcastMode,
+    util::parse_address,
+    Client, Provider,
+};
+use recall_signer::Signer;
+
+use crate::machine::{
+    bucket::{display_key, AddOptions, Bucket},
+    timehub::{PushOptions, Timehub, BLAKE3_CODE, RAW_CODEC},
+    Machine,
+};
+
+/// Prefix of the signed bytes, so a provenance signature can't be mistaken for a message signature.
+const PROVENANCE_SIGNING_DOMAIN: &[u8] = b"recall-provenance-v1:";
+
+/// Suffix of the key the provenance record of an object is stored at.
+pub const PROVENANCE_KEY_SUFFIX: &str = ".provenance";
+
+/// The name and version of the tool that records provenance.
+const TOOL: &str = concat!("recall-sdk/", env!("CARGO_PKG_VERSION"));
+
+/// Who added an object, from where, and with what.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceRecord {
+    /// The bucket address.
+    pub bucket: String,
+    /// The object key, escaped if it isn't valid UTF-8 (see [`display_key`]).
+    pub key: String,
+    /// The object's content hash.
+    pub hash: String,
+    /// The object size in bytes.
+    pub size: u64,
+    /// The address the object was added from.
+    pub uploader: String,
+    /// The source the content was read from, e.g., a filename.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// The name and version of the tool that recorded the provenance.
+    pub tool: String,
+}
+
+impl ProvenanceRecord {
+    /// Returns the bytes that are signed for the record.
+    pub fn signing_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = PROVENANCE_SIGNING_DOMAIN.to_vec();
+        bytes.extend(fvm_ipld_encoding::to_vec(self)?);
+        Ok(bytes)
+    }
+
+    /// Returns the CID of the signed bytes, which is what a timehub witnesses.
+    pub fn cid(&self) -> anyhow::Result<Cid> {
+        let digest = blake3::hash(&self.signing_bytes()?);
+        let hash = Multihash::wrap(BLAKE3_CODE, digest.as_bytes())?;
+        Ok(Cid::new_v1(RAW_CODEC, hash))
+    }
+}
+
+/// The timehub entry that witnesses a provenance record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceWitness {
+    /// The timehub address.
+    pub timehub: String,
+    /// The index of the timehub leaf.
+    pub index: u64,
+}
+
+/// A [`ProvenanceRecord`] with a signature over its contents.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedProvenance {
+    /// The signed record.
+    pub record: ProvenanceRecord,
+    /// The secp256k1 key address (f1/t1) of the signer.
+    pub signer: String,
+    /// The hex-encoded secp256k1 signature.
+    pub signature: String,
+    /// The timehub entry witnessing the record, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness: Option<ProvenanceWitness>,
+}
+
+impl SignedProvenance {
+    /// Verify the signature over the record.
+    pub fn verify_signature(&self) -> anyhow::Result<()> {
+        let signer = parse_address(&self.signer)?;
+        let signature = Signature::new_secp256k1(hex::decode(&self.signature)?);
+        signature
+            .verify(&self.record.signing_bytes()?, &signer)
+            .map_err(|e| anyhow!("invalid provenance signature: {}", e))
+    }
+
+    /// Verify the signature, check that the object at the record's key still has the recorded
+    /// hash, and check that the witness, if any, witnessed the record.
+    pub async fn verify(
+        &self,
+        provider: &impl QueryProvider,
+        bucket: &Bucket,
+        key: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<()> {
+        self.verify_signature()?;
+        if self.record.bucket != bucket.address().to_string()
+            || self.record.key != display_key(key.as_bytes())
+        {
+            return Err(anyhow!(
+                "provenance record is for key '{}' in bucket {}",
+                self.record.key,
+                self.record.bucket
+            ));
+        }
+        let object = bucket
+            .find_object(provider, key, height)
+            .await?
+            .ok_or_else(|| anyhow!("object not found for key '{}'", key))?;
+        if object.hash.to_string() != self.record.hash || object.size != self.record.size {
+            return Err(anyhow!(
+                "object at key '{}' has changed since its provenance was recorded",
+                key
+            ));
+        }
+        if let Some(witness) = &self.witness {
+            let timehub = Timehub::attach(parse_address(&witness.timehub)?).await?;
+            let leaf = timehub
+                .leaf(provider, witness.index, height)
+                .await?
+                .ok_or_else(|| anyhow!("timehub has no leaf at index {}", witness.index))?;
+            if leaf.witnessed.0 != self.record.cid()? {
+                return Err(anyhow!(
+                    "timehub leaf {} doesn't witness the provenance record",
+                    witness.index
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Provenance recording options.
+#[derive(Clone, Default, Debug)]
+pub struct ProvenanceOptions {
+    /// The source the content was read from, e.g., a filename.
+    pub source: Option<String>,
+    /// Optional timehub to witness the record in.
+    pub timehub: Option<Address>,
+    /// TTL of the stored record. If not set, the subnet's default TTL is used.
+    pub ttl: Option<ChainEpoch>,
+    /// Broadcast mode for the record transaction.
+    /// The timehub push is always committed, since its index is needed for the record.
+    pub broadcast_mode: BroadcastMode,
+    /// Gas params for the transactions.
+    pub gas_params: GasParams,
+    /// Query block height for the object.
+    pub height: FvmQueryHeight,
+}
+
+/// A static wrapper around object provenance records.
+///
+/// A record is signed by the signer's key and stored as JSON next to the object, at the object
+/// key with [`PROVENANCE_KEY_SUFFIX`] appended. The record's CID can also be pushed into a
+/// timehub, which timestamps it.
+pub struct Provenance {}
+
+impl Provenance {
+    /// Record the provenance of an object that has been added to a bucket.
+    pub async fn record<C>(
+        provider: &impl Provider<C>,
+        signer: &mut impl Signer,
+        from: Address,
+        bucket: &Bucket,
+        key: &str,
+        options: ProvenanceOptions,
+    ) -> anyhow::Result<SignedProvenance>
+    where
+        C: Client + Send + Sync,
+    {
+        let object = bucket
+            .find_object(provider, key, options.height)
+            .await?
+            .ok_or_else(|| anyhow!("object not found for key '{}'", key))?;
+        let record = ProvenanceRecord {
+            bucket: bucket.address().to_string(),
+            key: display_key(key.as_bytes()),
+            hash: object.hash.to_string(),
+            size: object.size,
+            uploader: from.to_string(),
+            source: options.source,
+            tool: TOOL.into(),
+        };
+
+        let sk = signer
+            .secret_key()
+            .ok_or_else(|| anyhow!("signer does not have a secret key"))?;
+        let signature = sign_secp256k1(&sk, &record.signing_bytes()?);
+        let key_address = Address::new_secp256k1(&sk.public_key().serialize())?;
+
+        let witness = match options.timehub {
+            Some(address) => {
+                let timehub = Timehub::attach(address).await?;
+                let tx = timehub
+                    .push(
+                        provider,
+                        signer,
+                        from,
+                        Bytes::from(record.cid()?.to_bytes()),
+                        PushOptions {
+                            broadcast_mode: BroadcastMode::Commit,
+                            gas_params: options.gas_params.clone(),
+                        },
+                    )
+                    .await?;
+                let pushed = tx
+                    .data
+                    .ok_or_else(|| anyhow!("timehub push returned no index"))?;
+                Some(ProvenanceWitness {
+                    timehub: address.to_string(),
+                    index: pushed.index,
+                })
+            }
+            None => None,
+        };
+
+        let provenance = SignedProvenance {
+            record,
+            signer: key_address.to_string(),
+            signature: hex::encode(signature.bytes()),
+            witness,
+        };
+        let json = serde_json::to_vec(&provenance)?;
+        let size = json.len() as u64;
+        bucket
+            .add_reader(
+                provider,
+                signer,
+                from,
+                &format!("{}{}", key, PROVENANCE_KEY_SUFFIX),
+                Cursor::new(json),
+                size,
+                AddOptions {
+                    ttl: options.ttl,
+                    overwrite: true,
+                    broadcast_mode: options.broadcast_mode,
+                    gas_params: options.gas_params,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok(provenance)
+    }
+
+    /// Fetch the provenance record of an object.
+    /// Use [`SignedProvenance::verify`] to check it.
+    pub async fn fetch(
+        provider: &(impl QueryProvider + ObjectProvider),
+        bucket: &Bucket,
+        key: &str,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<SignedProvenance> {
+        let record_key = format!("{}{}", key, PROVENANCE_KEY_SUFFIX);
+        let response = provider
+            .download(bucket.address(), record_key.as_bytes(), None, height.into())
+            .await?;
+        let json = response.bytes().await?;
+        serde_json::from_slice(&json)
+            .map_err(|e| anyhow!("invalid provenance record for key '{}': {}", key, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use recall_signer::key::random_secretkey;
+
+    use super::*;
+
+    #[test]
+    fn test_provenance_signature() {
+        let sk = random_secretkey();
+        let record = ProvenanceRecord {
+            bucket: Address::new_id(1000).to_string(),
+            key: "foo/bar".into(),
+            hash: "rzghyg4z3p6vbz5jkgc75lk64fci7kieul65o6hk6xznx7lctkmq".into(),
+            size: 1024,
+            uploader: Address::new_id(1001).to_string(),
+            source: Some("bar.json".into()),
+            tool: TOOL.into(),
+        };
+        let mut provenance = SignedProvenance {
+            signer: Address::new_secp256k1(&sk.public_key().serialize())
+                .unwrap()
+                .to_string(),
+            signature: hex::encode(sign_secp256k1(&sk, &record.signing_bytes().unwrap()).bytes()),
+            record,
+            witness: None,
+        };
+        provenance.verify_signature().unwrap();
+
+        provenance.record.size += 1;
+        assert!(provenance.verify_signature().is_err());
+    }
+}
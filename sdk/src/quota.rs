@@ -0,0 +1,289 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use recall_provider::{
+    error::{self, ErrorCode},
+    fvm_shared::econ::TokenAmount,
+};
+
+use crate::clock::{Clock, SystemClock};
+
+/// Default length of a quota period.
+const DEFAULT_QUOTA_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Limits enforced by a [`Quota`] within each period.
+#[derive(Clone, Debug)]
+pub struct QuotaLimits {
+    /// Maximum number of bytes added.
+    pub max_bytes: Option<u64>,
+    /// Maximum number of objects added.
+    pub max_objects: Option<u64>,
+    /// Maximum tokens spent on inline credit purchases.
+    pub max_spend: Option<TokenAmount>,
+    /// Length of the period after which usage resets.
+    pub period: Duration,
+}
+
+impl Default for QuotaLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: None,
+            max_objects: None,
+            max_spend: None,
+            period: DEFAULT_QUOTA_PERIOD,
+        }
+    }
+}
+
+/// The limit that an operation would exceed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaResource {
+    /// [`QuotaLimits::max_bytes`].
+    Bytes,
+    /// [`QuotaLimits::max_objects`].
+    Objects,
+    /// [`QuotaLimits::max_spend`].
+    Spend,
+}
+
+/// Error returned when an operation would exceed a [`Quota`].
+///
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuotaExceededError {
+    /// The exceeded limit.
+    pub resource: QuotaResource,
+    /// The limit value.
+    pub limit: String,
+    /// Usage in the current period before the operation.
+    pub used: String,
+    /// The amount requested by the operation.
+    pub requested: String,
+}
+
+impl fmt::Display for QuotaExceededError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:?} quota exceeded: requested {} with {} of {} used",
+            self.code(),
+            self.resource,
+            self.requested,
+            self.used,
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for QuotaExceededError {}
+
+impl ErrorCode for QuotaExceededError {
+    fn code(&self) -> &'static str {
+        error::QUOTA_EXCEEDED
+    }
+}
+
+/// Usage counted against a [`Quota`] in the current period.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuotaUsage {
+    /// Bytes added.
+    pub bytes: u64,
+    /// Objects added.
+    pub objects: u64,
+    /// Tokens spent.
+    pub spend: TokenAmount,
+}
+
+/// A client-side usage budget shared between operations.
+///
+/// Adds that carry the same [`Quota`] (see `AddOptions::quota`) are rejected with a
+/// [`QuotaExceededError`] before anything is uploaded if they would exceed a limit.
+/// Usage of failed operations is refunded.
+#[derive(Clone, Debug)]
+pub struct Quota {
+    limits: QuotaLimits,
+    clock: Arc<dyn Clock>,
+    state: Arc<Mutex<(Instant, QuotaUsage)>>,
+}
+
+impl Quota {
+    /// Create a quota with the given limits, starting a period now.
+    pub fn new(limits: QuotaLimits) -> Self {
+        Self::with_clock(limits, Arc::new(SystemClock))
+    }
+
+    /// Create a quota whose periods are measured on the given clock.
+    pub fn with_clock(limits: QuotaLimits, clock: Arc<dyn Clock>) -> Self {
+        let started = clock.now();
+        Self {
+            limits,
+            clock,
+            state: Arc::new(Mutex::new((started, QuotaUsage::default()))),
+        }
+    }
+
+    /// Returns the quota limits.
+    pub fn limits(&self) -> &QuotaLimits {
+        &self.limits
+    }
+
+    /// Returns usage in the current period.
+    pub fn usage(&self) -> QuotaUsage {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.roll_period(&mut state);
+        state.1.clone()
+    }
+
+    /// Count an operation against the quota, returning a charge that's refunded if it's
+    /// dropped before [`QuotaCharge::commit`] is called.
+    pub(crate) fn charge(
+        &self,
+        bytes: u64,
+        objects: u64,
+        spend: TokenAmount,
+    ) -> Result<QuotaCharge, QuotaExceededError> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.roll_period(&mut state);
+        let usage = &mut state.1;
+        if let Some(max) = self.limits.max_bytes {
+            if usage.bytes.saturating_add(bytes) > max {
+                return Err(exceeded(QuotaResource::Bytes, max, usage.bytes, bytes));
+            }
+        }
+        if let Some(max) = self.limits.max_objects {
+            if usage.objects.saturating_add(objects) > max {
+                return Err(exceeded(
+                    QuotaResource::Objects,
+                    max,
+                    usage.objects,
+                    objects,
+                ));
+            }
+        }
+        if let Some(max) = &self.limits.max_spend {
+            if &usage.spend + &spend > *max {
+                return Err(exceeded(QuotaResource::Spend, max, &usage.spend, &spend));
+            }
+        }
+        usage.bytes += bytes;
+        usage.objects += objects;
+        usage.spend += &spend;
+        Ok(QuotaCharge {
+            quota: self.clone(),
+            started: state.0,
+            usage: Some(QuotaUsage {
+                bytes,
+                objects,
+                spend,
+            }),
+        })
+    }
+
+    /// Start a new period if the current one has elapsed.
+    fn roll_period(&self, state: &mut (Instant, QuotaUsage)) {
+        let now = self.clock.now();
+        if now.saturating_duration_since(state.0) >= self.limits.period {
+            *state = (now, QuotaUsage::default());
+        }
+    }
+}
+
+/// Usage counted against a [`Quota`] by an in-progress operation.
+#[derive(Debug)]
+pub(crate) struct QuotaCharge {
+    quota: Quota,
+    started: Instant,
+    usage: Option<QuotaUsage>,
+}
+
+impl QuotaCharge {
+    /// Keep the usage once the operation has succeeded.
+    pub(crate) fn commit(mut self) {
+        self.usage = None;
+    }
+}
+
+impl Drop for QuotaCharge {
+    fn drop(&mut self) {
+        let Some(charged) = self.usage.take() else {
+            return;
+        };
+        let mut state = self.quota.state.lock().unwrap_or_else(|e| e.into_inner());
+        // Usage from a previous period has already been reset
+        if state.0 != self.started {
+            return;
+        }
+        let usage = &mut state.1;
+        usage.bytes = usage.bytes.saturating_sub(charged.bytes);
+        usage.objects = usage.objects.saturating_sub(charged.objects);
+        usage.spend -= charged.spend;
+    }
+}
+
+fn exceeded(
+    resource: QuotaResource,
+    limit: impl ToString,
+    used: impl ToString,
+    requested: impl ToString,
+) -> QuotaExceededError {
+    QuotaExceededError {
+        resource,
+        limit: limit.to_string(),
+        used: used.to_string(),
+        requested: requested.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::ManualClock;
+
+    use super::*;
+
+    #[test]
+    fn test_quota() {
+        let quota = Quota::new(QuotaLimits {
+            max_bytes: Some(100),
+            max_objects: Some(2),
+            ..Default::default()
+        });
+        quota
+            .charge(60, 1, TokenAmount::default())
+            .unwrap()
+            .commit();
+
+        let err = quota.charge(50, 1, TokenAmount::default()).unwrap_err();
+        assert_eq!(err.resource, QuotaResource::Bytes);
+
+        // Dropped charges are refunded
+        drop(quota.charge(40, 1, TokenAmount::default()).unwrap());
+        assert_eq!(quota.usage().objects, 1);
+
+        quota
+            .charge(40, 1, TokenAmount::default())
+            .unwrap()
+            .commit();
+        let err = quota.charge(0, 1, TokenAmount::default()).unwrap_err();
+        assert_eq!(err.resource, QuotaResource::Objects);
+        assert_eq!(quota.usage().bytes, 100);
+
+        // Usage resets once the period has elapsed
+        let clock = ManualClock::new();
+        let quota = Quota::with_clock(
+            QuotaLimits {
+                max_objects: Some(1),
+                period: Duration::from_secs(60),
+                ..Default::default()
+            },
+            Arc::new(clock.clone()),
+        );
+        quota.charge(0, 1, TokenAmount::default()).unwrap().commit();
+        assert!(quota.charge(0, 1, TokenAmount::default()).is_err());
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(quota.usage().objects, 0);
+    }
+}
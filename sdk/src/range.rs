@@ -0,0 +1,113 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// A byte range of an object, following HTTP range semantics
+/// (`<https://www.rfc-editor.org/rfc/rfc7233#section-2.1>`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Range {
+    /// All bytes from an offset to the end of the object, e.g., "100-".
+    FromStart(u64),
+    /// The bytes between two offsets, inclusive, e.g., "0-99".
+    /// The end is clamped to the end of the object.
+    Bounded(u64, u64),
+    /// The last bytes of the object, e.g., "-100".
+    /// The whole object is selected if it's shorter than the suffix.
+    Suffix(u64),
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FromStart(start) => write!(f, "{}-", start),
+            Self::Bounded(start, end) => write!(f, "{}-{}", start, end),
+            Self::Suffix(len) => write!(f, "-{}", len),
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = anyhow::Error;
+
+    /// Parse a range in the format "start-end", "start-", or "-suffix".
+    /// A leading "bytes=" unit, as in an HTTP range header, is accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = s.trim();
+        let spec = spec.strip_prefix("bytes=").unwrap_or(spec);
+        let (start, end) = spec
+            .split_once('-')
+            .ok_or_else(|| anyhow!("invalid range '{}'; expected start-end", s))?;
+        let parse = |v: &str| {
+            v.parse::<u64>()
+                .map_err(|_| anyhow!("invalid range '{}'; offsets must be integers", s))
+        };
+        match (start.is_empty(), end.is_empty()) {
+            (true, true) => Err(anyhow!("invalid range '{}'; expected start-end", s)),
+            (true, false) => match parse(end)? {
+                0 => Err(anyhow!(
+                    "invalid range '{}'; suffix length must be positive",
+                    s
+                )),
+                len => Ok(Self::Suffix(len)),
+            },
+            (false, true) => Ok(Self::FromStart(parse(start)?)),
+            (false, false) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(anyhow!("invalid range '{}'; start is after end", s));
+                }
+                Ok(Self::Bounded(start, end))
+            }
+        }
+    }
+}
+
+impl Range {
+    /// Returns the inclusive offsets the range selects in an object of the given size,
+    /// or `None` if the range isn't satisfiable, e.g., it starts past the end of the object.
+    pub fn bounds(&self, size: u64) -> Option<(u64, u64)> {
+        if size == 0 {
+            return None;
+        }
+        match *self {
+            Self::FromStart(start) => (start < size).then_some((start, size - 1)),
+            Self::Bounded(start, end) => (start < size).then_some((start, end.min(size - 1))),
+            Self::Suffix(len) => (len > 0).then_some((size - len.min(size), size - 1)),
+        }
+    }
+
+    /// Returns the number of bytes the range selects in an object of the given size.
+    pub fn len(&self, size: u64) -> u64 {
+        self.bounds(size).map_or(0, |(start, end)| end - start + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        for (s, range) in [
+            ("0-99", Range::Bounded(0, 99)),
+            ("100-", Range::FromStart(100)),
+            ("-100", Range::Suffix(100)),
+        ] {
+            assert_eq!(s.parse::<Range>().unwrap(), range);
+            assert_eq!(range.to_string(), s);
+        }
+        assert_eq!("bytes=5-9".parse::<Range>().unwrap(), Range::Bounded(5, 9));
+        for s in ["", "-", "10-5", "-0", "a-b", "10"] {
+            assert!(s.parse::<Range>().is_err(), "{}", s);
+        }
+
+        assert_eq!(Range::Bounded(0, 99).bounds(50), Some((0, 49)));
+        assert_eq!(Range::FromStart(50).bounds(50), None);
+        assert_eq!(Range::Suffix(100).bounds(50), Some((0, 49)));
+        assert_eq!(Range::Suffix(10).len(50), 10);
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! An on-disk record of verified timehub roots.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::Write;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use recall_provider::{
+    error::{self, ErrorCode},
+    fvm_shared::address::Address,
+};
+
+use crate::machine::timehub::RootAt;
+
+/// Error returned when a timehub root is inconsistent with a root recorded in a [`RootCache`].
+///
+/// Timehubs are append-only, so an inconsistent root means an RPC node returned a forked or
+/// rewritten history, and neither root should be trusted without checking other nodes.
+/// Returned wrapped in [`anyhow::Error`]; use `downcast_ref` to detect it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RootEquivocationError {
+    /// The timehub address.
+    pub timehub: String,
+    /// The height the new root was read at.
+    pub height: u64,
+    /// How the root conflicts with the recorded roots.
+    pub reason: String,
+}
+
+impl fmt::Display for RootEquivocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: root of timehub {} at height {} is inconsistent: {}",
+            self.code(),
+            self.timehub,
+            self.height,
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for RootEquivocationError {}
+
+impl ErrorCode for RootEquivocationError {
+    fn code(&self) -> &'static str {
+        error::TIMEHUB_ROOT_EQUIVOCATION
+    }
+}
+
+/// A verified root, as stored in the cache file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RootRecord {
+    timehub: String,
+    height: u64,
+    root: String,
+    count: u64,
+}
+
+/// A root and the leaf count it covers.
+type Entry = (String, u64);
+
+/// An on-disk record of the timehub roots a client has seen, used to detect equivocation.
+///
+/// Each root is recorded with the height it was read at and the leaf count at that height.
+/// A new root is checked against the recorded ones before it's recorded: a height must always
+/// have the same root, leaf counts must never decrease as heights increase, and the same leaf
+/// count must always have the same root.
+/// Records are appended to a JSON lines file, so they survive restarts.
+/// Cloning the cache is cheap, and clones share the same records.
+/// See [`Timehub::with_root_cache`](crate::machine::timehub::Timehub::with_root_cache).
+#[derive(Clone)]
+pub struct RootCache {
+    path: PathBuf,
+    roots: Arc<Mutex<HashMap<String, BTreeMap<u64, Entry>>>>,
+}
+
+impl fmt::Debug for RootCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RootCache")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl RootCache {
+    /// Open a cache at `path`, loading the roots recorded by previous processes.
+    /// The file is created when the first root is recorded.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut roots: HashMap<String, BTreeMap<u64, Entry>> = HashMap::new();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                    let record: RootRecord = serde_json::from_str(line)?;
+                    roots
+                        .entry(record.timehub)
+                        .or_default()
+                        .insert(record.height, (record.root, record.count));
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(Self {
+            path,
+            roots: Arc::new(Mutex::new(roots)),
+        })
+    }
+
+    /// Returns the cache file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the recorded root at the greatest height for a timehub, if any.
+    pub fn latest(&self, timehub: Address) -> Option<(RootAt, u64)> {
+        let roots = self.lock();
+        let (height, (root, count)) = roots.get(&timehub.to_string())?.last_key_value()?;
+        let root = RootAt {
+            root: root.parse().ok()?,
+            height: *height,
+        };
+        Some((root, *count))
+    }
+
+    /// Check a root, read along with the timehub's leaf count at the same height, against the
+    /// recorded roots, and record it.
+    /// Returns a [`RootEquivocationError`] if it's inconsistent, in which case it isn't recorded.
+    pub fn verify(&self, timehub: Address, root: &RootAt, count: u64) -> anyhow::Result<()> {
+        let timehub = timehub.to_string();
+        let cid = root.root.to_string();
+        let mut roots = self.lock();
+        let recorded = roots.entry(timehub.clone()).or_default();
+        let conflict = check(recorded, root.height, &cid, count);
+        if let Some(reason) = conflict {
+            return Err(RootEquivocationError {
+                timehub,
+                height: root.height,
+                reason,
+            }
+            .into());
+        }
+        if recorded.contains_key(&root.height) {
+            return Ok(());
+        }
+
+        let record = RootRecord {
+            timehub,
+            height: root.height,
+            root: cid.clone(),
+            count,
+        };
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&line)?;
+        recorded.insert(root.height, (cid, count));
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, BTreeMap<u64, Entry>>> {
+        self.roots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Returns why a root at `height` covering `count` leaves conflicts with the recorded roots,
+/// or `None` if it doesn't.
+fn check(recorded: &BTreeMap<u64, Entry>, height: u64, root: &str, count: u64) -> Option<String> {
+    if let Some((recorded_root, recorded_count)) = recorded.get(&height) {
+        if recorded_root != root || *recorded_count != count {
+            return Some(format!(
+                "recorded root at the same height is {} with {} leaves",
+                recorded_root, recorded_count
+            ));
+        }
+        return None;
+    }
+    let before = recorded.range(..height).next_back();
+    let after = recorded
+        .range((Bound::Excluded(height), Bound::Unbounded))
+        .next();
+    for (other_height, (other_root, other_count)) in before.into_iter().chain(after) {
+        let decreased = if *other_height < height {
+            count < *other_count
+        } else {
+            count > *other_count
+        };
+        if decreased {
+            return Some(format!(
+                "leaf count {} at height {} doesn't follow {} at height {}",
+                count, height, other_count, other_height
+            ));
+        }
+        if count == *other_count && root != other_root {
+            return Some(format!(
+                "root {} with {} leaves differs from {} at height {}",
+                root, count, other_root, other_height
+            ));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use cid::multihash::Multihash;
+
+    use super::*;
+
+    fn root(height: u64, seed: u8) -> RootAt {
+        let hash = Multihash::wrap(0x1e, &[seed; 32]).unwrap();
+        RootAt {
+            root: cid::Cid::new_v1(0x71, hash).into(),
+            height,
+        }
+    }
+
+    #[test]
+    fn test_root_cache_equivocation() {
+        let path = std::env::temp_dir().join(format!("recall-roots-{}", rand::random::<u64>()));
+        let timehub = Address::new_id(1000);
+        let cache = RootCache::open(&path).unwrap();
+        cache.verify(timehub, &root(10, 1), 2).unwrap();
+        cache.verify(timehub, &root(20, 2), 3).unwrap();
+        cache.verify(timehub, &root(20, 2), 3).unwrap();
+        // Nothing was pushed between the heights
+        cache.verify(timehub, &root(15, 1), 2).unwrap();
+
+        // A different root at a recorded height
+        let err = cache.verify(timehub, &root(10, 3), 2).unwrap_err();
+        assert!(err.to_string().starts_with("RECALL-E-TH-001: "));
+        // A shrinking leaf count
+        assert!(cache.verify(timehub, &root(30, 4), 1).is_err());
+        // A different root with the same leaf count
+        assert!(cache.verify(timehub, &root(12, 5), 2).is_err());
+        // Other timehubs are independent
+        cache
+            .verify(Address::new_id(1001), &root(10, 3), 2)
+            .unwrap();
+
+        // Records survive reopening
+        let cache = RootCache::open(&path).unwrap();
+        let (latest, count) = cache.latest(timehub).unwrap();
+        assert_eq!((latest.height, count), (20, 3));
+        assert!(cache.verify(timehub, &root(10, 3), 2).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Utilities for writing integration tests against a live network.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use reqwest::{StatusCode, Url};
+use serde::Serialize;
+
+use recall_provider::{
+    fvm_shared::econ::TokenAmount,
+    tx::BroadcastMode,
+    {Client, Provider},
+};
+use recall_signer::{key::random_secretkey, AccountKind, Signer, Wallet};
+
+use crate::{
+    account::{Account, EVMSubnet},
+    clock::{clock_or_system, new_backoff_policy, retry, Clock},
+    credits::{BuyOptions, Credits},
+};
+
+/// Default time to wait for a faucet request to be served and the funds to arrive.
+const DEFAULT_FUNDING_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Account factory options.
+#[derive(Clone, Default, Debug)]
+pub struct AccountFactoryOptions {
+    /// Amount of the account's funds to spend on storage credit.
+    /// If not set, no credit is bought.
+    pub buy_credit: Option<TokenAmount>,
+    /// How long to retry faucet requests and wait for funds to arrive.
+    /// Defaults to five minutes.
+    pub timeout: Option<Duration>,
+    /// Clock used to back off between retries. Defaults to the system clock.
+    pub clock: Option<Arc<dyn Clock>>,
+}
+
+#[derive(Serialize)]
+struct FaucetRequest {
+    address: String,
+}
+
+/// Creates fresh, funded accounts for tests.
+///
+/// Each account gets a new random key and is funded by a faucet. Faucet requests that are
+/// rate limited or fail with a server error are retried with exponential backoff, honoring
+/// the faucet's `Retry-After` header.
+/// The faucet is expected to accept a `POST` with a JSON body like
+/// `{"address": "0x..."}` and send funds to the address on the subnet.
+pub struct AccountFactory {
+    faucet_url: Url,
+    subnet: EVMSubnet,
+    client: reqwest::Client,
+    options: AccountFactoryOptions,
+}
+
+impl AccountFactory {
+    /// Create a factory that requests funds from the faucet at `faucet_url` and checks
+    /// balances on `subnet`.
+    pub fn new(faucet_url: Url, subnet: EVMSubnet, options: AccountFactoryOptions) -> Self {
+        AccountFactory {
+            faucet_url,
+            subnet,
+            client: reqwest::Client::new(),
+            options,
+        }
+    }
+
+    /// Create a funded account, buying storage credit for it if the options say so.
+    /// The returned wallet's sequence is initialized and it's ready to send transactions.
+    pub async fn create<C>(&self, provider: &impl Provider<C>) -> anyhow::Result<Wallet>
+    where
+        C: Client + Send + Sync,
+    {
+        let mut wallet = Wallet::new_secp256k1(
+            random_secretkey(),
+            AccountKind::Ethereum,
+            self.subnet.id.clone(),
+        )?;
+        self.fund(&wallet).await?;
+        wallet.init_sequence(provider).await?;
+
+        if let Some(amount) = self.options.buy_credit.clone() {
+            let address = wallet.address();
+            Credits::buy(
+                provider,
+                &mut wallet,
+                address,
+                amount,
+                BuyOptions {
+                    broadcast_mode: BroadcastMode::Commit,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        Ok(wallet)
+    }
+
+    /// Request funds for a signer from the faucet and wait for them to arrive.
+    pub async fn fund(&self, signer: &impl Signer) -> anyhow::Result<TokenAmount> {
+        let timeout = self.options.timeout.unwrap_or(DEFAULT_FUNDING_TIMEOUT);
+        let clock = clock_or_system(&self.options.clock);
+        let request = FaucetRequest {
+            address: format!("0x{}", hex::encode(signer.eth_address()?.0)),
+        };
+
+        retry(new_backoff_policy(clock.clone(), timeout), || async {
+            let response = self
+                .client
+                .post(self.faucet_url.clone())
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| backoff::Error::transient(anyhow!(e)))?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs);
+            let err = anyhow!(
+                "faucet request failed with {}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            );
+            match (status, retry_after) {
+                (StatusCode::TOO_MANY_REQUESTS, Some(after)) => {
+                    tracing::debug!(?after, "faucet rate limited request");
+                    Err(backoff::Error::retry_after(err, after))
+                }
+                (StatusCode::TOO_MANY_REQUESTS, None) => Err(backoff::Error::transient(err)),
+                (status, _) if status.is_server_error() => Err(backoff::Error::transient(err)),
+                _ => Err(backoff::Error::permanent(err)),
+            }
+        })
+        .await?;
+
+        retry(new_backoff_policy(clock, timeout), || async {
+            match Account::balance(signer, self.subnet.clone()).await {
+                Ok(balance) if balance.is_positive() => Ok(balance),
+                Ok(_) => Err(backoff::Error::transient(anyhow!(
+                    "faucet funds did not arrive for {}",
+                    signer.address()
+                ))),
+                Err(e) => Err(backoff::Error::transient(e)),
+            }
+        })
+        .await
+    }
+}
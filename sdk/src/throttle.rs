@@ -0,0 +1,100 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream::{BoxStream, Stream, StreamExt};
+
+use crate::clock::Clock;
+
+/// A token bucket that limits throughput to a number of bytes per second.
+///
+/// The bucket holds up to one second of tokens, so transfers can burst up to the rate after
+/// being idle. Chunks larger than the bucket are let through once the bucket is full,
+/// and the deficit is paid off by the chunks that follow.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last: Instant,
+    clock: Arc<dyn Clock>,
+}
+
+impl TokenBucket {
+    /// Create a full bucket refilled at `rate` bytes per second.
+    pub(crate) fn new(rate: u64, clock: Arc<dyn Clock>) -> Self {
+        let rate = rate.max(1) as f64;
+        TokenBucket {
+            rate,
+            tokens: rate,
+            last: clock.now(),
+            clock,
+        }
+    }
+
+    /// Take tokens for `len` bytes, returning how long to wait before sending them.
+    pub(crate) fn take(&mut self, len: usize) -> Duration {
+        let now = self.clock.now();
+        let refill = now.saturating_duration_since(self.last).as_secs_f64() * self.rate;
+        self.tokens = (self.tokens + refill).min(self.rate);
+        self.last = now;
+        self.tokens -= len as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// Limits a stream of byte chunks to `rate` bytes per second, if a rate is given.
+pub(crate) fn throttle<S, E>(
+    stream: S,
+    rate: Option<u64>,
+    clock: Arc<dyn Clock>,
+) -> BoxStream<'static, Result<Bytes, E>>
+where
+    S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+    E: Send + 'static,
+{
+    let Some(rate) = rate else {
+        return stream.boxed();
+    };
+    let mut bucket = TokenBucket::new(rate, clock.clone());
+    stream
+        .then(move |item| {
+            let delay = match &item {
+                Ok(chunk) => bucket.take(chunk.len()),
+                Err(_) => Duration::ZERO,
+            };
+            let sleep = (!delay.is_zero()).then(|| clock.sleep(delay));
+            async move {
+                if let Some(sleep) = sleep {
+                    sleep.await;
+                }
+                item
+            }
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use crate::clock::ManualClock;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_throttle() {
+        let clock = ManualClock::new();
+        let chunks = vec![Ok::<_, ()>(Bytes::from(vec![0; 1024])); 10];
+        let throttled = throttle(stream::iter(chunks), Some(2048), Arc::new(clock.clone()));
+        assert_eq!(throttled.count().await, 10);
+        // The first 2KiB burst through, and the remaining 8KiB take four seconds
+        assert_eq!(clock.elapsed(), Duration::from_secs(4));
+    }
+}
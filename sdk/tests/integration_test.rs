@@ -17,6 +17,7 @@ use recall_sdk::{
         bucket::{AddOptions, Bucket, GetOptions, QueryOptions},
         Machine,
     },
+    range::Range,
 };
 use recall_signer::{key::parse_secret_key, AccountKind, Signer, Wallet};
 
@@ -174,7 +175,7 @@ async fn can_add_bucket() {
     let obj_path = obj_file.file_path().to_owned();
 
     let options = GetOptions {
-        range: Some("0-99".to_string()), // Get the first 100 bytes
+        range: Some(Range::Bounded(0, 99)), // Get the first 100 bytes
         ..Default::default()
     };
     let open_file = obj_file.open_rw().await.unwrap();
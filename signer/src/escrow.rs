@@ -0,0 +1,396 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Splits secret keys into shares for escrow and social recovery.
+//!
+//! A key is split into `n` shares with Shamir's secret sharing over GF(256), any `k` of which
+//! reconstruct it; fewer than `k` reveal nothing about the key. Each share can be encrypted to a
+//! recipient's secp256k1 public key, so shares can be handed out over untrusted channels and
+//! held by team members without a custodian.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use fendermint_crypto::PublicKey;
+use rand::RngCore;
+
+use crate::key::SecretKey;
+
+/// Length of a secret key in bytes.
+const SECRET_LEN: usize = 32;
+
+/// Length of a key fingerprint in bytes.
+const FINGERPRINT_LEN: usize = 8;
+
+/// Length of an encoded share: threshold, index, fingerprint, and share data.
+const SHARE_LEN: usize = 2 + FINGERPRINT_LEN + SECRET_LEN;
+
+/// Length of a compressed secp256k1 public key.
+const PUBLIC_KEY_LEN: usize = 33;
+
+/// Length of an encryption tag.
+const TAG_LEN: usize = 32;
+
+/// Key derivation context of the share encryption key.
+const ENCRYPTION_CONTEXT: &str = "recall-signer 2025 escrow share encryption";
+
+/// Key derivation context of the share authentication key.
+const AUTHENTICATION_CONTEXT: &str = "recall-signer 2025 escrow share authentication";
+
+/// One share of a split secret key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Share {
+    /// The number of shares needed to reconstruct the key.
+    pub threshold: u8,
+    /// The share's x-coordinate, starting at 1.
+    pub index: u8,
+    /// The fingerprint of the key's public key, used to check a reconstruction.
+    pub fingerprint: [u8; FINGERPRINT_LEN],
+    data: [u8; SECRET_LEN],
+}
+
+// Don't leak share data into logs.
+impl fmt::Debug for Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Share")
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .field("fingerprint", &hex::encode(self.fingerprint))
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl FromStr for Share {
+    type Err = anyhow::Error;
+
+    /// Parse a hex-encoded share.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(&hex::decode(s.trim()).context("cannot decode hex share")?)
+    }
+}
+
+impl Share {
+    /// Returns the share encoded as bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SHARE_LEN);
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.extend_from_slice(&self.fingerprint);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Decode a share from bytes.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() != SHARE_LEN {
+            return Err(anyhow!(
+                "invalid share length {}; expected {}",
+                bytes.len(),
+                SHARE_LEN
+            ));
+        }
+        let share = Share {
+            threshold: bytes[0],
+            index: bytes[1],
+            fingerprint: bytes[2..2 + FINGERPRINT_LEN].try_into()?,
+            data: bytes[2 + FINGERPRINT_LEN..].try_into()?,
+        };
+        if share.threshold == 0 || share.index == 0 {
+            return Err(anyhow!(
+                "invalid share; threshold and index must be positive"
+            ));
+        }
+        Ok(share)
+    }
+
+    /// Encrypt the share to a recipient's public key.
+    ///
+    /// The share is encrypted with a key derived from an ECDH exchange between an ephemeral key
+    /// and the recipient's key, and authenticated with a keyed BLAKE3 tag.
+    pub fn encrypt(&self, recipient: &PublicKey) -> anyhow::Result<EncryptedShare> {
+        let ephemeral = SecretKey::random(&mut rand::thread_rng());
+        let ephemeral_public = ephemeral.public_key().serialize_compressed();
+        let (encryption_key, authentication_key) =
+            derive_keys(&ephemeral, recipient, &ephemeral_public)?;
+        let mut ciphertext = self.to_bytes();
+        apply_keystream(&encryption_key, &mut ciphertext);
+        let tag = tag(&authentication_key, &ephemeral_public, &ciphertext);
+        Ok(EncryptedShare {
+            ephemeral: ephemeral_public,
+            ciphertext,
+            tag,
+        })
+    }
+}
+
+/// A [`Share`] encrypted to a recipient's public key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedShare {
+    ephemeral: [u8; PUBLIC_KEY_LEN],
+    ciphertext: Vec<u8>,
+    tag: [u8; TAG_LEN],
+}
+
+impl fmt::Display for EncryptedShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl FromStr for EncryptedShare {
+    type Err = anyhow::Error;
+
+    /// Parse a hex-encoded encrypted share.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(&hex::decode(s.trim()).context("cannot decode hex encrypted share")?)
+    }
+}
+
+impl EncryptedShare {
+    /// Returns the encrypted share encoded as bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.ephemeral[..], &self.ciphertext, &self.tag].concat()
+    }
+
+    /// Decode an encrypted share from bytes.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() != PUBLIC_KEY_LEN + SHARE_LEN + TAG_LEN {
+            return Err(anyhow!(
+                "invalid encrypted share length {}; expected {}",
+                bytes.len(),
+                PUBLIC_KEY_LEN + SHARE_LEN + TAG_LEN
+            ));
+        }
+        let (ephemeral, rest) = bytes.split_at(PUBLIC_KEY_LEN);
+        let (ciphertext, tag) = rest.split_at(SHARE_LEN);
+        Ok(EncryptedShare {
+            ephemeral: ephemeral.try_into()?,
+            ciphertext: ciphertext.to_vec(),
+            tag: tag.try_into()?,
+        })
+    }
+
+    /// Decrypt the share with the recipient's secret key.
+    pub fn decrypt(&self, sk: &SecretKey) -> anyhow::Result<Share> {
+        let ephemeral = PublicKey::parse_compressed(&self.ephemeral)
+            .map_err(|e| anyhow!("invalid ephemeral public key: {:?}", e))?;
+        let (encryption_key, authentication_key) = derive_keys(sk, &ephemeral, &self.ephemeral)?;
+        // Hash equality is constant-time
+        if tag(&authentication_key, &self.ephemeral, &self.ciphertext)
+            != blake3::Hash::from(self.tag)
+        {
+            return Err(anyhow!(
+                "cannot decrypt share; it's corrupted or encrypted to another key"
+            ));
+        }
+        let mut plaintext = self.ciphertext.clone();
+        apply_keystream(&encryption_key, &mut plaintext);
+        Share::from_bytes(&plaintext)
+    }
+}
+
+/// Split a secret key into `shares` shares, any `threshold` of which reconstruct it.
+pub fn split(sk: &SecretKey, threshold: u8, shares: u8) -> anyhow::Result<Vec<Share>> {
+    if threshold == 0 || threshold > shares {
+        return Err(anyhow!(
+            "invalid threshold {}; must be between 1 and the number of shares ({})",
+            threshold,
+            shares
+        ));
+    }
+    let fingerprint = fingerprint(&sk.public_key());
+    let secret = sk.serialize();
+
+    // Each secret byte is the constant term of a random polynomial of degree threshold - 1
+    let mut rng = rand::thread_rng();
+    let mut coefficients = vec![[0u8; SECRET_LEN]; threshold as usize];
+    coefficients[0].copy_from_slice(&secret[..]);
+    for c in coefficients.iter_mut().skip(1) {
+        rng.fill_bytes(c);
+    }
+
+    let shares = (1..=shares)
+        .map(|x| {
+            let mut data = [0u8; SECRET_LEN];
+            for (i, byte) in data.iter_mut().enumerate() {
+                // Horner's method, from the highest degree down
+                *byte = coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, c| gf_mul(acc, x) ^ c[i]);
+            }
+            Share {
+                threshold,
+                index: x,
+                fingerprint,
+                data,
+            }
+        })
+        .collect();
+    coefficients.iter_mut().for_each(|c| c.fill(0));
+    Ok(shares)
+}
+
+/// Reconstruct a secret key from at least threshold shares.
+pub fn combine(shares: &[Share]) -> anyhow::Result<SecretKey> {
+    let first = shares.first().ok_or_else(|| anyhow!("no shares given"))?;
+    if shares
+        .iter()
+        .any(|s| s.threshold != first.threshold || s.fingerprint != first.fingerprint)
+    {
+        return Err(anyhow!("shares are from different keys"));
+    }
+    let mut indexes: Vec<u8> = shares.iter().map(|s| s.index).collect();
+    indexes.sort_unstable();
+    indexes.dedup();
+    if indexes.len() != shares.len() {
+        return Err(anyhow!("shares must have distinct indexes"));
+    }
+    if shares.len() < first.threshold as usize {
+        return Err(anyhow!(
+            "not enough shares; got {} but need {}",
+            shares.len(),
+            first.threshold
+        ));
+    }
+    let shares = &shares[..first.threshold as usize];
+
+    // Lagrange interpolation at zero
+    let mut secret = [0u8; SECRET_LEN];
+    for (j, share) in shares.iter().enumerate() {
+        let mut basis = 1;
+        for (m, other) in shares.iter().enumerate() {
+            if m != j {
+                // x_m / (x_m - x_j); subtraction is xor in GF(256)
+                basis = gf_mul(basis, gf_div(other.index, other.index ^ share.index));
+            }
+        }
+        for (s, d) in secret.iter_mut().zip(share.data.iter()) {
+            *s ^= gf_mul(basis, *d);
+        }
+    }
+
+    let sk = SecretKey::try_from(secret.to_vec()).context("failed to reconstruct secret key")?;
+    secret.fill(0);
+    if fingerprint(&sk.public_key()) != first.fingerprint {
+        return Err(anyhow!(
+            "reconstructed key doesn't match the share fingerprint; a share may be corrupted"
+        ));
+    }
+    Ok(sk)
+}
+
+/// Returns the fingerprint of a public key.
+fn fingerprint(pk: &PublicKey) -> [u8; FINGERPRINT_LEN] {
+    let hash = blake3::hash(&pk.serialize());
+    let mut fingerprint = [0u8; FINGERPRINT_LEN];
+    fingerprint.copy_from_slice(&hash.as_bytes()[..FINGERPRINT_LEN]);
+    fingerprint
+}
+
+/// Derives the encryption and authentication keys from an ECDH exchange.
+fn derive_keys(
+    sk: &SecretKey,
+    pk: &PublicKey,
+    ephemeral: &[u8; PUBLIC_KEY_LEN],
+) -> anyhow::Result<([u8; 32], [u8; 32])> {
+    let scalar = libsecp256k1::SecretKey::parse_slice(&sk.serialize()[..])
+        .map_err(|e| anyhow!("invalid secret key: {:?}", e))?;
+    let mut shared = *pk;
+    shared
+        .tweak_mul_assign(&scalar)
+        .map_err(|e| anyhow!("failed to derive shared secret: {:?}", e))?;
+    let material = [&shared.serialize_compressed()[..], &ephemeral[..]].concat();
+    Ok((
+        blake3::derive_key(ENCRYPTION_CONTEXT, &material),
+        blake3::derive_key(AUTHENTICATION_CONTEXT, &material),
+    ))
+}
+
+/// XORs the data with a BLAKE3 keystream. Applying it twice restores the data.
+fn apply_keystream(key: &[u8; 32], data: &mut [u8]) {
+    let mut keystream = vec![0u8; data.len()];
+    blake3::Hasher::new_keyed(key)
+        .finalize_xof()
+        .fill(&mut keystream);
+    data.iter_mut().zip(keystream).for_each(|(d, k)| *d ^= k);
+}
+
+fn tag(key: &[u8; 32], ephemeral: &[u8], ciphertext: &[u8]) -> blake3::Hash {
+    blake3::Hasher::new_keyed(key)
+        .update(ephemeral)
+        .update(ciphertext)
+        .finalize()
+}
+
+/// Multiplication in GF(256) with the AES polynomial, without data-dependent branches.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// Division in GF(256). `b` must not be zero.
+fn gf_div(a: u8, b: u8) -> u8 {
+    // b^254 is the inverse of b
+    let mut inverse = 1;
+    let mut base = b;
+    let mut exp = 254u8;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            inverse = gf_mul(inverse, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    gf_mul(a, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::key::random_secretkey;
+
+    use super::*;
+
+    #[test]
+    fn test_split_combine_encrypted() {
+        let sk = random_secretkey();
+        let shares = split(&sk, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        let recipient = random_secretkey();
+        let encrypted: Vec<EncryptedShare> = shares
+            .iter()
+            .map(|s| s.encrypt(&recipient.public_key()).unwrap())
+            .collect();
+        let decrypted: Vec<Share> = encrypted
+            .iter()
+            .map(|e| {
+                let e: EncryptedShare = e.to_string().parse().unwrap();
+                e.decrypt(&recipient).unwrap()
+            })
+            .collect();
+        assert_eq!(decrypted, shares);
+        assert!(encrypted[0].decrypt(&random_secretkey()).is_err());
+
+        let combined = combine(&[shares[4].clone(), shares[0].clone(), shares[2].clone()]).unwrap();
+        assert_eq!(combined.public_key(), sk.public_key());
+        assert!(combine(&shares[..2]).is_err());
+
+        let mut corrupted = shares[1].clone();
+        corrupted.data[0] ^= 1;
+        assert!(combine(&[shares[0].clone(), corrupted, shares[2].clone()]).is_err());
+    }
+}
@@ -5,13 +5,17 @@
 //!
 //! A transaction signer for Recall.
 
+pub mod escrow;
 pub mod key;
+pub mod migrate;
+mod outbox;
 mod signer;
 mod subnet;
 mod void;
 mod wallet;
 
+pub use outbox::{Outbox, OutboxEntry};
 pub use signer::{EthAddress, Signer};
 pub use subnet::SubnetID;
 pub use void::Void;
-pub use wallet::{AccountKind, Wallet};
+pub use wallet::{AccountKind, PipelinedReport, QueuedTransaction, Wallet};
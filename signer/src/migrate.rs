@@ -0,0 +1,239 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Moves secret keys between storage backends.
+//!
+//! Keys are verified before and after a migration by signing a challenge message with the
+//! source key and checking it against the key loaded back from the destination.
+//! Only local backends are supported; OS keyrings and KMS backends aren't available yet.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+
+use recall_provider::{
+    fvm_ipld_encoding::RawBytes,
+    fvm_shared::{address::Address, chainid::ChainID, econ::TokenAmount},
+    message::{Message, OriginKind, SignedMessage},
+};
+
+use crate::key::{parse_secret_key, SecretKey};
+
+/// Chain ID used to sign migration challenges. Challenges are never broadcasted.
+const CHALLENGE_CHAIN_ID: u64 = 0;
+
+/// A place a secret key is stored.
+#[derive(Clone, Debug)]
+pub enum KeyBackend {
+    /// A hex-encoded key held in memory. Read-only.
+    Hex(String),
+    /// An environment variable holding a hex-encoded key. Read-only.
+    Env(String),
+    /// A file holding a hex-encoded key.
+    /// Files are written with owner-only permissions on Unix.
+    File(PathBuf),
+}
+
+impl fmt::Display for KeyBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hex(_) => write!(f, "hex"),
+            Self::Env(var) => write!(f, "env:{}", var),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+        }
+    }
+}
+
+impl KeyBackend {
+    /// Load the secret key from the backend.
+    pub fn load(&self) -> anyhow::Result<SecretKey> {
+        let hex_str = match self {
+            Self::Hex(hex_str) => hex_str.clone(),
+            Self::Env(var) => std::env::var(var)
+                .with_context(|| format!("cannot read environment variable {}", var))?,
+            Self::File(path) => fs::read_to_string(path)
+                .with_context(|| format!("cannot read key file {}", path.display()))?,
+        };
+        parse_secret_key(&hex_str)
+    }
+
+    /// Store the secret key in the backend.
+    ///
+    /// Fails if the backend already holds a different key (or one that can't be read), unless
+    /// `overwrite` is set, in which case the existing key is destroyed.
+    pub fn store(&self, sk: &SecretKey, overwrite: bool) -> anyhow::Result<()> {
+        match self {
+            Self::Hex(_) | Self::Env(_) => Err(anyhow!("{} backend is read-only", self)),
+            Self::File(path) => {
+                if !overwrite && path.exists() {
+                    let existing = self.load().ok();
+                    if existing.is_some_and(|existing| existing.public_key() == sk.public_key()) {
+                        return Ok(());
+                    }
+                    return Err(anyhow!(
+                        "{} already holds a different key; set overwrite to replace it",
+                        self
+                    ));
+                }
+                let temp_path = temp_path(path);
+                let result = write_private(&temp_path, sk)
+                    .and_then(|()| fs::rename(&temp_path, path).map_err(anyhow::Error::from));
+                if result.is_err() {
+                    let _ = fs::remove_file(&temp_path);
+                }
+                result
+            }
+        }
+    }
+}
+
+/// The outcome of a key migration.
+#[derive(Clone, Debug)]
+pub struct MigrationReport {
+    /// The source backend.
+    pub from: String,
+    /// The destination backend.
+    pub to: String,
+    /// The secp256k1 address of the migrated key.
+    pub address: Address,
+}
+
+/// Copy a secret key from one backend to another.
+///
+/// The source key signs a challenge that must verify both before the key is stored and after
+/// it's loaded back from the destination. The source is left untouched so it can be removed
+/// once the destination is in use.
+/// Migration fails if the destination already holds a different key, unless `overwrite` is set
+/// (see [`KeyBackend::store`]).
+pub fn migrate_key(
+    from: &KeyBackend,
+    to: &KeyBackend,
+    overwrite: bool,
+) -> anyhow::Result<MigrationReport> {
+    let sk = from
+        .load()
+        .with_context(|| format!("cannot load key from {}", from))?;
+    let address = Address::new_secp256k1(&sk.public_key().serialize())?;
+    let challenge = challenge_message(address);
+    let signed = SignedMessage::new_secp256k1(challenge, &sk, &challenge_chain_id())?;
+    verify_challenge(&signed).context("source key failed verification")?;
+
+    to.store(&sk, overwrite)
+        .with_context(|| format!("cannot store key in {}", to))?;
+
+    let migrated = to
+        .load()
+        .with_context(|| format!("cannot load key back from {}", to))?;
+    if migrated.public_key() != sk.public_key() {
+        return Err(anyhow!("key loaded from {} doesn't match the source", to));
+    }
+    let resigned =
+        SignedMessage::new_secp256k1(signed.message.clone(), &migrated, &challenge_chain_id())?;
+    verify_challenge(&resigned).context("migrated key failed verification")?;
+
+    Ok(MigrationReport {
+        from: from.to_string(),
+        to: to.to_string(),
+        address,
+    })
+}
+
+fn challenge_chain_id() -> ChainID {
+    ChainID::from(CHALLENGE_CHAIN_ID)
+}
+
+/// Returns a message that can't be mistaken for a real transaction, with a random sequence so
+/// challenges aren't reused.
+fn challenge_message(from: Address) -> Message {
+    Message {
+        version: Default::default(),
+        from,
+        to: from,
+        sequence: rand::random(),
+        value: TokenAmount::default(),
+        method_num: 0,
+        params: RawBytes::default(),
+        gas_limit: 0,
+        gas_fee_cap: TokenAmount::default(),
+        gas_premium: TokenAmount::default(),
+    }
+}
+
+fn verify_challenge(signed: &SignedMessage) -> anyhow::Result<()> {
+    SignedMessage::verify_signature(
+        OriginKind::Fvm,
+        &signed.message,
+        &signed.signature,
+        &challenge_chain_id(),
+    )?;
+    Ok(())
+}
+
+/// Write a secret key to a new file with owner-only permissions, syncing it to disk.
+fn write_private(path: &Path, sk: &SecretKey) -> anyhow::Result<()> {
+    let mut file = create_private(path)?;
+    file.write_all(hex::encode(sk.serialize()).as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Returns a unique path for a temporary file next to `path`.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{:016x}.tmp", name, rand::random::<u64>()))
+}
+
+#[cfg(unix)]
+fn create_private(path: &Path) -> anyhow::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    Ok(fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?)
+}
+
+#[cfg(not(unix))]
+fn create_private(path: &Path) -> anyhow::Result<File> {
+    Ok(fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::key::random_secretkey;
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_key() {
+        let sk = random_secretkey();
+        let path = std::env::temp_dir().join(format!("recall-key-{}", rand::random::<u64>()));
+        let from = KeyBackend::Hex(hex::encode(sk.serialize()));
+        let to = KeyBackend::File(path.clone());
+
+        let report = migrate_key(&from, &to, false).unwrap();
+        assert_eq!(to.load().unwrap().public_key(), sk.public_key());
+        assert_eq!(
+            report.address,
+            Address::new_secp256k1(&sk.public_key().serialize()).unwrap()
+        );
+        assert!(migrate_key(&to, &from, false).is_err());
+        // Migrating the same key again is a no-op
+        migrate_key(&from, &to, false).unwrap();
+
+        // A different key isn't overwritten without the flag
+        let other = random_secretkey();
+        let other_from = KeyBackend::Hex(hex::encode(other.serialize()));
+        assert!(migrate_key(&other_from, &to, false).is_err());
+        assert_eq!(to.load().unwrap().public_key(), sk.public_key());
+        migrate_key(&other_from, &to, true).unwrap();
+        assert_eq!(to.load().unwrap().public_key(), other.public_key());
+        fs::remove_file(path).unwrap();
+    }
+}
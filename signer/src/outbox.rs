@@ -0,0 +1,189 @@
+// Copyright 2025 Recall Contributors
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+
+use recall_provider::{fvm_ipld_encoding, message::Message};
+
+/// File extension of outbox entries.
+const ENTRY_EXTENSION: &str = "tx";
+
+/// A transaction persisted in an [`Outbox`].
+#[derive(Clone, Debug)]
+pub struct OutboxEntry {
+    /// The entry ID. Entries are replayed in ID order.
+    pub id: u64,
+    /// The unsigned message. The sequence is only meaningful if `signed_sequence` is set.
+    pub message: Message,
+    /// The sequence the message was last signed with, if it was signed.
+    pub signed_sequence: Option<u64>,
+    /// The CometBFT hash of the transaction the message was last signed into, if it was signed.
+    pub tx_hash: Option<Vec<u8>>,
+}
+
+/// A durable, on-disk queue of transactions that haven't been confirmed as submitted.
+///
+/// Used with [`crate::Wallet::send_durable`] and [`crate::Wallet::replay_outbox`],
+/// transactions are written to disk before they're broadcasted and removed once the broadcast
+/// succeeds, so that transactions interrupted by a crash can be replayed.
+#[derive(Clone, Debug)]
+pub struct Outbox {
+    dir: PathBuf,
+    next_id: Arc<Mutex<u64>>,
+}
+
+impl Outbox {
+    /// Open an outbox in `dir`, creating the directory if needed.
+    pub fn open(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let next_id = Self::entry_ids(&dir)?.last().map_or(0, |id| id + 1);
+        Ok(Self {
+            dir,
+            next_id: Arc::new(Mutex::new(next_id)),
+        })
+    }
+
+    /// Persist an unsigned message, returning its entry ID.
+    pub fn enqueue(&self, message: &Message) -> anyhow::Result<u64> {
+        let id = {
+            let mut next_id = self
+                .next_id
+                .lock()
+                .map_err(|_| anyhow!("outbox lock poisoned"))?;
+            let id = *next_id;
+            *next_id += 1;
+            id
+        };
+        self.write(&OutboxEntry {
+            id,
+            message: message.clone(),
+            signed_sequence: None,
+            tx_hash: None,
+        })?;
+        Ok(id)
+    }
+
+    /// Record the sequence an entry was signed with, and the hash of the signed transaction,
+    /// before it's broadcasted.
+    pub fn mark_signed(&self, id: u64, sequence: u64, tx_hash: Vec<u8>) -> anyhow::Result<()> {
+        let mut entry = self.read(id)?;
+        entry.message.sequence = sequence;
+        entry.signed_sequence = Some(sequence);
+        entry.tx_hash = Some(tx_hash);
+        self.write(&entry)
+    }
+
+    /// Remove an entry once its transaction has been submitted.
+    pub fn remove(&self, id: u64) -> anyhow::Result<()> {
+        match fs::remove_file(self.entry_path(id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns all persisted entries in ID order.
+    pub fn pending(&self) -> anyhow::Result<Vec<OutboxEntry>> {
+        Self::entry_ids(&self.dir)?
+            .into_iter()
+            .map(|id| self.read(id))
+            .collect()
+    }
+
+    fn read(&self, id: u64) -> anyhow::Result<OutboxEntry> {
+        let bytes = fs::read(self.entry_path(id))?;
+        let (message, signed_sequence, tx_hash) = fvm_ipld_encoding::from_slice(&bytes)
+            .map_err(|e| anyhow!("failed to decode outbox entry {}: {}", id, e))?;
+        Ok(OutboxEntry {
+            id,
+            message,
+            signed_sequence,
+            tx_hash,
+        })
+    }
+
+    /// Write an entry atomically, syncing it to disk before it replaces any previous version.
+    fn write(&self, entry: &OutboxEntry) -> anyhow::Result<()> {
+        let bytes =
+            fvm_ipld_encoding::to_vec(&(&entry.message, entry.signed_sequence, &entry.tx_hash))?;
+        let path = self.entry_path(entry.id);
+        let temp_path = path.with_extension("tmp");
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{:020}.{}", id, ENTRY_EXTENSION))
+    }
+
+    fn entry_ids(dir: &Path) -> anyhow::Result<Vec<u64>> {
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == ENTRY_EXTENSION) {
+                if let Some(id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse().ok())
+                {
+                    ids.push(id);
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use recall_provider::{
+        fvm_ipld_encoding::RawBytes,
+        fvm_shared::{address::Address, econ::TokenAmount},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_outbox() {
+        let dir = std::env::temp_dir().join(format!("recall-outbox-{}", rand::random::<u64>()));
+        let outbox = Outbox::open(&dir).unwrap();
+        let message = Message {
+            version: Default::default(),
+            from: Address::new_id(100),
+            to: Address::new_id(200),
+            sequence: 0,
+            value: TokenAmount::from_atto(1),
+            method_num: 0,
+            params: RawBytes::default(),
+            gas_limit: 1_000_000,
+            gas_fee_cap: TokenAmount::from_atto(100),
+            gas_premium: TokenAmount::from_atto(1),
+        };
+        let first = outbox.enqueue(&message).unwrap();
+        let second = outbox.enqueue(&message).unwrap();
+        outbox.mark_signed(first, 7, vec![1; 32]).unwrap();
+
+        // Entries survive reopening
+        let outbox = Outbox::open(&dir).unwrap();
+        let pending = outbox.pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].signed_sequence, Some(7));
+        assert_eq!(pending[0].message.sequence, 7);
+        assert_eq!(pending[0].tx_hash, Some(vec![1; 32]));
+        assert_eq!(pending[1].signed_sequence, None);
+
+        outbox.remove(first).unwrap();
+        assert_eq!(outbox.pending().unwrap()[0].id, second);
+        assert_eq!(outbox.enqueue(&message).unwrap(), second + 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
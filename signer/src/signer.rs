@@ -5,8 +5,12 @@ use async_trait::async_trait;
 
 use recall_provider::{
     fvm_ipld_encoding::RawBytes,
-    fvm_shared::{address::Address, crypto::signature::Signature, econ::TokenAmount, MethodNum},
+    fvm_shared::{
+        address::Address, crypto::signature::Signature, econ::TokenAmount, MethodNum,
+        BLOCK_GAS_LIMIT,
+    },
     message::{GasParams, Message, SignedMessage},
+    query::{FvmQueryHeight, QueryProvider, SimulationResult},
     tx::{BroadcastMode, DeliverTx, TxResult},
     util::get_eth_address,
     Client, Provider,
@@ -57,6 +61,47 @@ pub trait Signer: Clone + Send + Sync {
         decode_fn: F,
     ) -> anyhow::Result<TxResult<T>>;
 
+    /// Simulates a transaction without signing or broadcasting it, returning the would-be
+    /// result and gas usage, or the reason the transaction would fail.
+    ///
+    /// The message is run as a read-only call from the signer address against the pending state.
+    async fn simulate_transaction<
+        C: Client + Send + Sync,
+        T: Send + Sync,
+        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Send + Sync,
+    >(
+        &self,
+        provider: &impl Provider<C>,
+        to: Address,
+        value: TokenAmount,
+        method_num: MethodNum,
+        params: RawBytes,
+        decode_fn: F,
+    ) -> anyhow::Result<SimulationResult<T>> {
+        let from = self.address();
+        let sequence = provider
+            .actor_state(&from, FvmQueryHeight::Pending)
+            .await?
+            .value
+            .map(|(_, state)| state.sequence)
+            .unwrap_or_default();
+        let message = Message {
+            version: Default::default(),
+            from,
+            to,
+            sequence,
+            value,
+            method_num,
+            params,
+            gas_limit: BLOCK_GAS_LIMIT,
+            gas_fee_cap: Default::default(),
+            gas_premium: Default::default(),
+        };
+        provider
+            .simulate(message, FvmQueryHeight::Pending, decode_fn)
+            .await
+    }
+
     /// Returns a raw [`SignedMessage`].  
     fn sign_message(&self, message: Message) -> anyhow::Result<SignedMessage>;
 
@@ -4,14 +4,17 @@
 
 use std::fmt;
 use std::fmt::Write;
-use std::hash::Hasher;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
 use anyhow::anyhow;
 use fnv::FnvHasher;
 use ipc_api::{error::Error, subnet_id::MAX_CHAIN_ID};
 
-use recall_provider::{fvm_shared::chainid::ChainID, util::parse_address};
+use recall_provider::{
+    fvm_shared::{address::Address, chainid::ChainID},
+    util::parse_address,
+};
 
 fn hash(bytes: &[u8]) -> u64 {
     let mut hasher = FnvHasher::default();
@@ -47,6 +50,9 @@ impl SubnetID {
 
     /// Returns the parent subnet ID is it exists.
     pub fn parent(&self) -> anyhow::Result<Self> {
+        if self.is_faux() {
+            return Err(anyhow!("subnet {} has no parent", self));
+        }
         if let Some(parent) = self.inner().parent() {
             Ok(Self {
                 faux: Default::default(),
@@ -54,8 +60,85 @@ impl SubnetID {
                 explicit_chain_id: None,
             })
         } else {
-            Err(anyhow!("subnet has no parent"))
+            Err(anyhow!("subnet {} has no parent", self))
+        }
+    }
+
+    /// Returns the ID of a child subnet with the given subnet actor address.
+    pub fn child(&self, address: Address) -> anyhow::Result<Self> {
+        if self.is_faux() {
+            return Err(anyhow!(
+                "subnet {} is not a hierarchical subnet ID and can't have children",
+                self
+            ));
+        }
+        let mut children = self.real.children_as_ref().to_vec();
+        children.push(address);
+        Ok(Self {
+            faux: Default::default(),
+            real: ipc_api::subnet_id::SubnetID::new(self.real.root_id(), children),
+            explicit_chain_id: None,
+        })
+    }
+
+    /// Returns whether the subnet ID is a free-form value rather than a hierarchical
+    /// `/r<root>/<address>/...` ID.
+    pub fn is_faux(&self) -> bool {
+        !self.faux.is_empty()
+    }
+
+    /// Returns whether the subnet is a root network, i.e., it has no parent.
+    pub fn is_root(&self) -> bool {
+        self.is_faux() || self.real.is_root()
+    }
+
+    /// Returns the number of subnets between the subnet and its root network.
+    /// Root networks and faux subnets have a depth of zero.
+    pub fn depth(&self) -> usize {
+        if self.is_faux() {
+            0
+        } else {
+            self.real.children_as_ref().len()
+        }
+    }
+
+    /// Returns whether the subnet is a strict ancestor of `other`.
+    pub fn is_ancestor_of(&self, other: &SubnetID) -> bool {
+        if self.is_faux() || other.is_faux() || self.real.root_id() != other.real.root_id() {
+            return false;
+        }
+        let ours = self.real.children_as_ref();
+        let theirs = other.real.children_as_ref();
+        ours.len() < theirs.len() && theirs.starts_with(ours)
+    }
+
+    /// Returns whether the subnet is the direct parent of `other`.
+    pub fn is_parent_of(&self, other: &SubnetID) -> bool {
+        self.is_ancestor_of(other) && other.depth() == self.depth() + 1
+    }
+
+    /// Returns the closest subnet that's an ancestor of, or equal to, both subnets,
+    /// or `None` if they're in different root networks.
+    pub fn common_ancestor(&self, other: &SubnetID) -> Option<SubnetID> {
+        if self.is_faux() || other.is_faux() {
+            return (self == other).then(|| self.clone());
+        }
+        if self.real.root_id() != other.real.root_id() {
+            return None;
         }
+        let children: Vec<Address> = self
+            .real
+            .children_as_ref()
+            .iter()
+            .zip(other.real.children_as_ref())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| *a)
+            .collect();
+        Some(Self {
+            faux: Default::default(),
+            real: ipc_api::subnet_id::SubnetID::new(self.real.root_id(), children),
+            explicit_chain_id: None,
+        })
     }
 
     pub fn with_chain_id(self, chain_id: ChainID) -> SubnetID {
@@ -102,9 +185,27 @@ impl fmt::Display for SubnetID {
     }
 }
 
+/// Subnet IDs are equal if they have the same string form and chain ID.
+impl PartialEq for SubnetID {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string() && self.chain_id() == other.chain_id()
+    }
+}
+
+impl Eq for SubnetID {}
+
+impl Hash for SubnetID {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_string().hash(state);
+    }
+}
+
 impl FromStr for SubnetID {
     type Err = Error;
     fn from_str(id: &str) -> Result<Self, Error> {
+        if id.trim().is_empty() {
+            return Err(Error::InvalidID(id.into(), "subnet ID is empty".into()));
+        }
         if !id.starts_with("/r") {
             return Ok(Self {
                 faux: id.to_string(),
@@ -115,13 +216,29 @@ impl FromStr for SubnetID {
 
         let segments: Vec<&str> = id.split('/').skip(1).collect();
 
-        let root = segments[0][1..]
-            .parse::<u64>()
-            .map_err(|_| Error::InvalidID(id.into(), "invalid root ID".into()))?;
+        let root = &segments[0][1..];
+        if root.is_empty() {
+            return Err(Error::InvalidID(
+                id.into(),
+                "missing root ID after \"/r\"".into(),
+            ));
+        }
+        let root = root.parse::<u64>().map_err(|_| {
+            Error::InvalidID(
+                id.into(),
+                format!("invalid root ID {root}: expected an unsigned integer"),
+            )
+        })?;
 
         let mut children = Vec::new();
 
-        for addr in segments[1..].iter() {
+        for (i, addr) in segments[1..].iter().enumerate() {
+            if addr.is_empty() {
+                return Err(Error::InvalidID(
+                    id.into(),
+                    format!("empty child address at position {}", i + 1),
+                ));
+            }
             let addr = parse_address(addr).map_err(|e| {
                 Error::InvalidID(id.into(), format!("invalid child address {addr}: {e}"))
             })?;
@@ -135,3 +252,32 @@ impl FromStr for SubnetID {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subnet_id_hierarchy() {
+        let root = SubnetID::from_str("/r314159").unwrap();
+        let child = root.child(Address::new_id(1001)).unwrap();
+        let grandchild = child.child(Address::new_id(1002)).unwrap();
+        assert_eq!(grandchild.depth(), 2);
+        assert_eq!(grandchild.parent().unwrap(), child);
+        assert!(root.is_ancestor_of(&grandchild));
+        assert!(!root.is_parent_of(&grandchild));
+        assert!(child.is_parent_of(&grandchild));
+
+        let sibling = root.child(Address::new_id(2001)).unwrap();
+        assert_eq!(grandchild.common_ancestor(&sibling), Some(root.clone()));
+        assert!(root.parent().is_err());
+
+        let faux = SubnetID::from_str("foobar").unwrap();
+        assert!(faux.child(Address::new_id(1001)).is_err());
+        assert_eq!(root.common_ancestor(&faux), None);
+
+        for invalid in ["", "/r", "/rfoo", "/r314159/"] {
+            assert!(SubnetID::from_str(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -8,19 +8,26 @@ use async_trait::async_trait;
 use tokio::sync::Mutex;
 
 use recall_provider::{
+    audit::{self, AuditEvent, AuditSink},
     fvm_ipld_encoding::RawBytes,
     fvm_shared::{address::Address, crypto::signature::Signature, econ::TokenAmount, MethodNum},
-    message::{ChainMessage, GasParams, Message, OriginKind, SignedMessage},
+    json_rpc::is_tx_not_found,
+    message::{tx_hash, ChainMessage, GasParams, Message, OriginKind, SignedMessage},
     query::{FvmQueryHeight, QueryProvider},
-    tx::{BroadcastMode, DeliverTx, TxResult},
-    Client, Provider,
+    response::decode_empty,
+    tx::{BroadcastMode, DeliverTx, Hash, TxProvider, TxResult},
+    Client, Provider, TendermintClient,
 };
 
+use crate::outbox::Outbox;
 use crate::signer::{EthAddress, Signer};
 use crate::SubnetID;
 
 pub use fendermint_crypto::SecretKey;
 
+/// Default maximum number of retries when a transaction is rejected because of a sequence mismatch.
+const DEFAULT_MAX_SEQUENCE_RETRIES: u32 = 3;
+
 /// Indicates how an [`Address`] should be derived from a public key.
 ///
 /// [`AccountKind::Regular`] refers to native FVM addresses.
@@ -31,6 +38,44 @@ pub enum AccountKind {
     Ethereum,
 }
 
+/// A transaction queued for pipelined broadcast with [`Wallet::send_pipelined`].
+#[derive(Clone, Debug)]
+pub struct QueuedTransaction {
+    /// The recipient address.
+    pub to: Address,
+    /// Token value to send with the transaction.
+    pub value: TokenAmount,
+    /// The method to invoke on the recipient.
+    pub method_num: MethodNum,
+    /// Serialized method params.
+    pub params: RawBytes,
+    /// Gas params for the transaction.
+    pub gas_params: GasParams,
+}
+
+/// The outcome of [`Wallet::send_pipelined`].
+///
+/// Transactions are sent in order, so `results` holds one result for each transaction that was
+/// broadcasted before a failure, if any.
+#[derive(Debug)]
+pub struct PipelinedReport {
+    /// Results of the transactions that were broadcasted, in order.
+    pub results: Vec<TxResult<()>>,
+    /// The index and error of the transaction that failed.
+    /// Transactions after it were not sent.
+    pub failed: Option<(usize, anyhow::Error)>,
+}
+
+impl PipelinedReport {
+    /// Returns the results, or the error if a transaction failed.
+    pub fn into_result(self) -> anyhow::Result<Vec<TxResult<()>>> {
+        match self.failed {
+            Some((_, e)) => Err(e),
+            None => Ok(self.results),
+        }
+    }
+}
+
 /// [`Signer`] implementation that relies on a local [`SecretKey`] to sign messages.
 ///
 /// Note, because [`Wallet`] manages the account's sequence (nonce) with a mutex,
@@ -41,6 +86,8 @@ pub struct Wallet {
     sk: SecretKey,
     subnet_id: SubnetID,
     sequence: Arc<Mutex<u64>>,
+    max_sequence_retries: u32,
+    audit: Option<Arc<dyn AuditSink>>,
 }
 
 #[async_trait]
@@ -68,45 +115,56 @@ impl Signer for Wallet {
         value: TokenAmount,
         method_num: MethodNum,
         params: RawBytes,
-        mut gas_params: GasParams,
+        gas_params: GasParams,
         broadcast_mode: BroadcastMode,
         decode_fn: F,
     ) -> anyhow::Result<TxResult<T>> {
-        // Check gas fee cap and premium are within the limits
-        gas_params.set_limits();
-
-        let mut message = Message {
-            version: Default::default(),
-            from: self.addr,
-            to,
-            sequence: 0, // set to 0 for gas estimation and updated below
-            value,
-            method_num,
-            params,
-            gas_limit: gas_params.gas_limit,
-            gas_fee_cap: gas_params.gas_fee_cap,
-            gas_premium: gas_params.gas_premium,
-        };
+        let message = self
+            .new_message(provider, to, value, method_num, params, gas_params)
+            .await?;
 
-        // Estimate gas limit if the message does not have one
-        if message.gas_limit == 0 {
-            let gas_limit = provider
-                .estimate_gas_limit(message.clone(), FvmQueryHeight::Committed)
-                .await?;
-            message.gas_limit = gas_limit;
-        }
+        let gas_limit = message.gas_limit;
 
-        // Set sequence to the current value
+        // The decoder is only called once the transaction is delivered,
+        // so it's still available if a broadcast is retried.
+        let mut decode_fn = Some(decode_fn);
+        let mut retries = 0;
+        // Hold the lock until the broadcast is done, so transactions from clones of the wallet
+        // reach the node in sequence order
         let mut sequence_guard = self.sequence.lock().await;
-        let sequence = *sequence_guard;
-        message.sequence = sequence;
-        *sequence_guard += 1;
+        loop {
+            // Set sequence to the current value
+            let mut message = message.clone();
+            message.sequence = *sequence_guard;
 
-        let signed = SignedMessage::new_secp256k1(message, &self.sk, &self.subnet_id.chain_id())?;
-        let signed_message = ChainMessage::Signed(signed);
-        Ok(provider
-            .perform(signed_message, broadcast_mode, decode_fn)
-            .await?)
+            let signed =
+                SignedMessage::new_secp256k1(message, &self.sk, &self.subnet_id.chain_id())?;
+            let signed_message = ChainMessage::Signed(signed);
+            let decode_once = |deliver_tx: &DeliverTx| match decode_fn.take() {
+                Some(f) => f(deliver_tx),
+                None => Err(anyhow!("transaction data was already decoded")),
+            };
+            match provider
+                .perform(signed_message, broadcast_mode, decode_once)
+                .await
+            {
+                Err(e) if retries < self.max_sequence_retries && is_sequence_mismatch(&e) => {
+                    // Another transaction from the account landed first; refresh and re-sign.
+                    retries += 1;
+                    *sequence_guard = self.fetch_sequence(provider).await?;
+                }
+                result => {
+                    // A failed broadcast doesn't use up the sequence
+                    if result.is_ok() {
+                        *sequence_guard += 1;
+                    }
+                    let event =
+                        AuditEvent::transaction(self.addr, to, method_num, gas_limit, &result);
+                    audit::record(self.audit.as_deref(), event).await;
+                    return result;
+                }
+            }
+        }
     }
 
     fn sign_message(&self, message: Message) -> anyhow::Result<SignedMessage> {
@@ -145,22 +203,284 @@ impl Wallet {
             addr,
             subnet_id,
             sequence,
+            max_sequence_retries: DEFAULT_MAX_SEQUENCE_RETRIES,
+            audit: None,
         })
     }
 
+    /// Set the maximum number of times a transaction is re-signed and broadcasted when it's
+    /// rejected because of a sequence mismatch.
+    /// Set to zero to disable retries.
+    pub fn set_max_sequence_retries(&mut self, retries: u32) {
+        self.max_sequence_retries = retries;
+    }
+
+    /// Set a sink that records every transaction the wallet signs and broadcasts,
+    /// including its outcome.
+    pub fn set_audit_sink(&mut self, sink: Arc<dyn AuditSink>) {
+        self.audit = Some(sink);
+    }
+
+    /// Signs and broadcasts a batch of transactions back-to-back.
+    ///
+    /// The sequence is fetched once from the actor's on-chain state, and consecutive values are
+    /// assigned to the transactions in order. Each transaction is broadcasted without waiting for
+    /// the previous one to be committed, so `broadcast_mode` must be [`BroadcastMode::Async`] or
+    /// [`BroadcastMode::Sync`].
+    ///
+    /// If a transaction fails, the remaining transactions are not sent, and the wallet sequence
+    /// is left at the failed transaction's sequence so that it can be reused.
+    /// The results of the transactions sent before the failure are returned along with it in
+    /// [`PipelinedReport::failed`]; an error is only returned if nothing was sent.
+    pub async fn send_pipelined<C>(
+        &mut self,
+        provider: &impl Provider<C>,
+        txs: Vec<QueuedTransaction>,
+        broadcast_mode: BroadcastMode,
+    ) -> anyhow::Result<PipelinedReport>
+    where
+        C: Client + Send + Sync,
+    {
+        if matches!(broadcast_mode, BroadcastMode::Commit) {
+            return Err(anyhow!(
+                "pipelined transactions require async or sync broadcast mode"
+            ));
+        }
+
+        // Hold the lock for the whole batch so other transactions can't interleave.
+        let mut sequence_guard = self.sequence.lock().await;
+        *sequence_guard = self.fetch_sequence(provider).await?;
+        let mut report = PipelinedReport {
+            results: Vec::with_capacity(txs.len()),
+            failed: None,
+        };
+        for (i, tx) in txs.into_iter().enumerate() {
+            let message = self
+                .new_message(
+                    provider,
+                    tx.to,
+                    tx.value,
+                    tx.method_num,
+                    tx.params,
+                    tx.gas_params,
+                )
+                .await
+                .map_err(|e| anyhow!("failed to prepare pipelined transaction {}: {}", i, e))
+                .and_then(|mut message| {
+                    message.sequence = *sequence_guard;
+                    let gas_limit = message.gas_limit;
+                    let signed = self.sign_message(message)?;
+                    Ok((signed, gas_limit))
+                });
+            let (signed, gas_limit) = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    report.failed = Some((i, e));
+                    break;
+                }
+            };
+            let result = provider
+                .perform(ChainMessage::Signed(signed), broadcast_mode, decode_empty)
+                .await;
+            let event =
+                AuditEvent::transaction(self.addr, tx.to, tx.method_num, gas_limit, &result);
+            audit::record(self.audit.as_deref(), event).await;
+            match result {
+                Ok(result) => {
+                    *sequence_guard += 1;
+                    report.results.push(result);
+                }
+                Err(e) => {
+                    let e = anyhow!("failed to broadcast pipelined transaction {}: {}", i, e);
+                    report.failed = Some((i, e));
+                    break;
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Signs and broadcasts a transaction, persisting it in the outbox first.
+    ///
+    /// The transaction is removed from the outbox once the broadcast succeeds.
+    /// If the process stops before then, [`Wallet::replay_outbox`] resubmits it.
+    pub async fn send_durable<C>(
+        &mut self,
+        provider: &impl Provider<C>,
+        outbox: &Outbox,
+        tx: QueuedTransaction,
+        broadcast_mode: BroadcastMode,
+    ) -> anyhow::Result<TxResult<()>>
+    where
+        C: Client + Send + Sync,
+    {
+        let message = self
+            .new_message(
+                provider,
+                tx.to,
+                tx.value,
+                tx.method_num,
+                tx.params,
+                tx.gas_params,
+            )
+            .await?;
+        let id = outbox.enqueue(&message)?;
+        self.send_outbox_entry(provider, outbox, id, message, broadcast_mode)
+            .await
+    }
+
+    /// Resubmits the transactions left in the outbox, e.g., after a crash, in the order they
+    /// were enqueued.
+    ///
+    /// The sequence is first synced with the account's on-chain state.
+    /// A transaction that was signed with a sequence the account has since used may have been
+    /// committed, so it's looked up by hash: it's dropped from the outbox if it was committed
+    /// successfully, and re-signed with a new sequence otherwise, e.g., if its broadcast failed
+    /// and another transaction used the sequence. Transactions are submitted at least once;
+    /// a node without a transaction index never finds them, so they're all sent again.
+    /// Replay stops at the first failed transaction, which stays in the outbox.
+    pub async fn replay_outbox<C>(
+        &mut self,
+        provider: &impl Provider<C>,
+        outbox: &Outbox,
+        broadcast_mode: BroadcastMode,
+    ) -> anyhow::Result<Vec<TxResult<()>>>
+    where
+        C: Client + Send + Sync,
+    {
+        self.init_sequence(provider).await?;
+        let account_sequence = *self.sequence.lock().await;
+
+        let mut results = Vec::new();
+        for entry in outbox.pending()? {
+            if entry.message.from != self.addr {
+                return Err(anyhow!(
+                    "outbox entry {} is from {}, not {}",
+                    entry.id,
+                    entry.message.from,
+                    self.addr
+                ));
+            }
+            let committed = match (entry.signed_sequence, entry.tx_hash) {
+                (Some(sequence), Some(hash)) if sequence < account_sequence => {
+                    is_committed(provider, hash).await?
+                }
+                _ => false,
+            };
+            if committed {
+                outbox.remove(entry.id)?;
+                continue;
+            }
+            let result = self
+                .send_outbox_entry(provider, outbox, entry.id, entry.message, broadcast_mode)
+                .await?;
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Signs and broadcasts an outbox entry with the next sequence, removing the entry on success.
+    async fn send_outbox_entry<C>(
+        &mut self,
+        provider: &impl Provider<C>,
+        outbox: &Outbox,
+        id: u64,
+        mut message: Message,
+        broadcast_mode: BroadcastMode,
+    ) -> anyhow::Result<TxResult<()>>
+    where
+        C: Client + Send + Sync,
+    {
+        let (to, method_num, gas_limit) = (message.to, message.method_num, message.gas_limit);
+        // Hold the lock until the broadcast is done, as for `send_transaction`
+        let mut sequence_guard = self.sequence.lock().await;
+        message.sequence = *sequence_guard;
+        let signed = ChainMessage::Signed(SignedMessage::new_secp256k1(
+            message,
+            &self.sk,
+            &self.subnet_id.chain_id(),
+        )?);
+        // Persist the sequence and hash before broadcasting so a replay can tell if the
+        // transaction was committed
+        let hash = tx_hash(&signed)?;
+        outbox.mark_signed(id, *sequence_guard, hash.as_bytes().to_vec())?;
+        let result = provider.perform(signed, broadcast_mode, decode_empty).await;
+        let event = AuditEvent::transaction(self.addr, to, method_num, gas_limit, &result);
+        audit::record(self.audit.as_deref(), event).await;
+        // A failed broadcast doesn't use up the sequence
+        let result = result?;
+        *sequence_guard += 1;
+        drop(sequence_guard);
+        outbox.remove(id)?;
+        Ok(result)
+    }
+
+    /// Creates an unsigned message from the wallet, estimating the gas limit if needed.
+    async fn new_message<C>(
+        &self,
+        provider: &impl Provider<C>,
+        to: Address,
+        value: TokenAmount,
+        method_num: MethodNum,
+        params: RawBytes,
+        mut gas_params: GasParams,
+    ) -> anyhow::Result<Message>
+    where
+        C: Client + Send + Sync,
+    {
+        // Check gas fee cap and premium are within the limits
+        gas_params.set_limits();
+        let max_gas_limit = provider.block_gas_limit().await?;
+        gas_params.validate(max_gas_limit)?;
+
+        let mut message = Message {
+            version: Default::default(),
+            from: self.addr,
+            to,
+            sequence: 0, // set to 0 for gas estimation and updated by the caller
+            value,
+            method_num,
+            params,
+            gas_limit: gas_params.gas_limit,
+            gas_fee_cap: gas_params.gas_fee_cap,
+            gas_premium: gas_params.gas_premium,
+        };
+
+        // Estimate gas limit if the message does not have one
+        if message.gas_limit == 0 {
+            let gas_limit = provider
+                .estimate_gas_limit(message.clone(), FvmQueryHeight::Committed)
+                .await?;
+            if gas_limit == 0 || gas_limit > max_gas_limit {
+                return Err(anyhow!(
+                    "estimated gas limit {} is outside the valid range (1 to {})",
+                    gas_limit,
+                    max_gas_limit
+                ));
+            }
+            message.gas_limit = gas_limit;
+        }
+        Ok(message)
+    }
+
     /// Inititalize sequence from the actor's on-chain state.
     pub async fn init_sequence(&mut self, provider: &impl QueryProvider) -> anyhow::Result<()> {
+        let mut sequence_guard = self.sequence.lock().await;
+        *sequence_guard = self.fetch_sequence(provider).await?;
+        Ok(())
+    }
+
+    /// Returns the sequence from the actor's on-chain state.
+    /// Callers hold the sequence lock while fetching it, so it can't race a broadcast from a
+    /// clone of the wallet.
+    async fn fetch_sequence(&self, provider: &impl QueryProvider) -> anyhow::Result<u64> {
         // Using the `Pending` state to query just in case there are other transactions initiated by the signer.
         let res = provider
             .actor_state(&self.addr, FvmQueryHeight::Pending)
             .await?;
 
         match res.value {
-            Some((_, state)) => {
-                let mut sequence_guard = self.sequence.lock().await;
-                *sequence_guard = state.sequence;
-                Ok(())
-            }
+            Some((_, state)) => Ok(state.sequence),
             None => Err(anyhow!(
                 "failed to init sequence; actor {} cannot be found",
                 self.addr
@@ -185,6 +505,26 @@ impl Wallet {
     }
 }
 
+/// Returns whether a transaction was committed successfully, looking it up by hash.
+/// A transaction the node doesn't know of is reported as not committed.
+async fn is_committed<C>(provider: &impl TendermintClient<C>, hash: Vec<u8>) -> anyhow::Result<bool>
+where
+    C: Client + Send + Sync,
+{
+    let hash = Hash::try_from(hash)?;
+    match provider.underlying().tx(hash, false).await {
+        Ok(tx) => Ok(tx.tx_result.code.is_ok()),
+        Err(e) if is_tx_not_found(&e) => Ok(false),
+        Err(e) => Err(anyhow!("failed to look up transaction {}: {}", hash, e)),
+    }
+}
+
+/// Returns whether the error is a rejection caused by the message sequence not matching the
+/// account's on-chain sequence, e.g., because another transaction from the account landed first.
+fn is_sequence_mismatch(err: &anyhow::Error) -> bool {
+    err.to_string().contains("expected sequence")
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -236,4 +576,12 @@ mod tests {
         wallet.set_sequence(None, &mock_provider).await.unwrap();
         assert_eq!(*wallet.sequence.lock().await, 65);
     }
+
+    #[test]
+    fn test_is_sequence_mismatch() {
+        assert!(is_sequence_mismatch(&anyhow!(
+            "check error: expected sequence 65, got 64"
+        )));
+        assert!(!is_sequence_mismatch(&anyhow!("insufficient funds")));
+    }
 }